@@ -0,0 +1,164 @@
+//! Artifact upload
+//!
+//! Once a pipeline succeeds, the files matching its `artifacts` patterns are
+//! uploaded to the object store, along with their checksums, before the job's
+//! workspace is cleaned up.
+
+use anyhow::{Context, Result};
+use raibid_common::artifacts::{artifact_key, Artifact};
+use raibid_common::storage::ObjectStore;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Find the files matching artifact patterns, keyed by their path within the repository
+///
+/// Directories, and symlinks pointing outside the repository, are skipped.
+pub fn collect_artifacts(
+    repo_path: &Path,
+    patterns: &[String],
+) -> Result<BTreeMap<String, PathBuf>> {
+    let root = repo_path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", repo_path.display()))?;
+    let prefix = glob::Pattern::escape(&root.to_string_lossy());
+    let mut files = BTreeMap::new();
+
+    for pattern in patterns {
+        let full_pattern = format!("{}/{}", prefix, pattern.trim_start_matches("./"));
+        let paths = glob::glob(&full_pattern)
+            .with_context(|| format!("Invalid artifact pattern '{}'", pattern))?;
+
+        for path in paths {
+            let path = path.context("Failed to read artifact")?;
+            let resolved = path
+                .canonicalize()
+                .with_context(|| format!("Failed to resolve {}", path.display()))?;
+            if !resolved.starts_with(&root) || !resolved.is_file() {
+                continue;
+            }
+
+            let name = path
+                .strip_prefix(&root)
+                .with_context(|| format!("Artifact outside repository: {}", path.display()))?
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.insert(name, path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Upload files as a job's artifacts, returning the uploaded ones sorted by name
+///
+/// A file that fails to upload is logged and skipped, so the artifacts that
+/// did reach the store are still recorded.
+pub async fn upload_artifacts(
+    store: &dyn ObjectStore,
+    job_id: &str,
+    files: &BTreeMap<String, PathBuf>,
+) -> Vec<Artifact> {
+    let mut artifacts = Vec::with_capacity(files.len());
+
+    for (name, path) in files {
+        match upload_artifact(store, job_id, name, path).await {
+            Ok(artifact) => artifacts.push(artifact),
+            Err(e) => warn!("Failed to upload artifact for job {}: {:#}", job_id, e),
+        }
+    }
+
+    artifacts
+}
+
+/// Upload a single file as a job's artifact
+async fn upload_artifact(
+    store: &dyn ObjectStore,
+    job_id: &str,
+    name: &str,
+    path: &Path,
+) -> Result<Artifact> {
+    let data = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("Failed to read artifact {}", name))?;
+    let artifact = Artifact::new(name, &data);
+
+    store
+        .put(&artifact_key(job_id, name), data)
+        .await
+        .with_context(|| format!("Failed to upload artifact {}", name))?;
+    Ok(artifact)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use raibid_common::storage::LocalObjectStore;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, name: &str, contents: &[u8]) {
+        let path = root.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_collect_artifacts() {
+        let repo = TempDir::new().unwrap();
+        write(repo.path(), "target/release/app", b"binary");
+        write(repo.path(), "dist/app.tar.gz", b"archive");
+        write(repo.path(), "dist/notes.txt", b"notes");
+
+        let patterns = vec![
+            "target/release/app".to_string(),
+            "./dist/*.tar.gz".to_string(),
+            "target/*".to_string(),
+            "missing/*".to_string(),
+        ];
+        let files = collect_artifacts(repo.path(), &patterns).unwrap();
+
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            ["dist/app.tar.gz", "target/release/app"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_artifacts() {
+        let repo = TempDir::new().unwrap();
+        write(repo.path(), "dist/app.tar.gz", b"archive");
+        let storage = TempDir::new().unwrap();
+        let store = LocalObjectStore::new(storage.path());
+
+        let files = collect_artifacts(repo.path(), &["dist/*".to_string()]).unwrap();
+        let artifacts = upload_artifacts(&store, "job-1", &files).await;
+
+        assert_eq!(artifacts, [Artifact::new("dist/app.tar.gz", b"archive")]);
+        assert_eq!(
+            store
+                .get_bytes(&artifact_key("job-1", "dist/app.tar.gz"))
+                .await
+                .unwrap(),
+            Some(b"archive".to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_artifacts_keeps_successful_uploads() {
+        let repo = TempDir::new().unwrap();
+        write(repo.path(), "dist/app.tar.gz", b"archive");
+        let storage = TempDir::new().unwrap();
+        let store = LocalObjectStore::new(storage.path());
+
+        let mut files = collect_artifacts(repo.path(), &["dist/*".to_string()]).unwrap();
+        files.insert(
+            "dist/a-missing".to_string(),
+            repo.path().join("dist/a-missing"),
+        );
+        let artifacts = upload_artifacts(&store, "job-1", &files).await;
+
+        assert_eq!(artifacts, [Artifact::new("dist/app.tar.gz", b"archive")]);
+    }
+}
@@ -0,0 +1,110 @@
+//! Job cancellation
+//!
+//! The server requests cancellation by setting a per-job key in Redis. While a job
+//! runs, the consumer watches that key and trips a [`CancellationToken`] that the
+//! executors observe. Build commands run in their own process group so that a
+//! cancellation kills the whole tree (e.g. `cargo` and the `rustc` processes it spawns).
+
+use raibid_common::queue::cancel_key;
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use std::time::Duration;
+use tokio::process::{Child, Command};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// Spawn a task that cancels `token` once a cancellation is requested for the job
+///
+/// The task exits when the token is cancelled, either by the watcher itself or by
+/// the caller once the job has finished. It keeps one Redis connection across
+/// polls, reconnecting after a failed check.
+pub fn spawn_cancel_watcher(
+    client: redis::Client,
+    job_id: String,
+    token: CancellationToken,
+    poll_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let key = cancel_key(&job_id);
+        let mut conn = None;
+
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => return,
+                _ = tokio::time::sleep(poll_interval) => {}
+            }
+
+            match is_cancel_requested(&client, &mut conn, &key).await {
+                Ok(true) => {
+                    info!("Cancellation requested for job {}", job_id);
+                    token.cancel();
+                    return;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("Failed to check cancellation for job {}: {}", job_id, e);
+                    conn = None;
+                }
+            }
+        }
+    })
+}
+
+/// Check whether a cancellation has been requested for a job
+///
+/// Connects on first use and reuses `conn` afterwards.
+pub async fn is_cancel_requested(
+    client: &redis::Client,
+    conn: &mut Option<MultiplexedConnection>,
+    key: &str,
+) -> Result<bool, redis::RedisError> {
+    let conn = match conn {
+        Some(conn) => conn,
+        None => conn.insert(client.get_multiplexed_async_connection().await?),
+    };
+    conn.exists(key).await
+}
+
+/// Configure a command to run in its own process group
+pub fn isolate_process_group(cmd: &mut Command) {
+    #[cfg(unix)]
+    cmd.process_group(0);
+}
+
+/// Kill a child process and every process in its group, then reap it
+pub async fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        debug!("Killing process group {}", pid);
+        // SAFETY: kill(2) has no memory-safety preconditions; a negative pid
+        // addresses the process group created by `isolate_process_group`.
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+        }
+    }
+
+    // Fall back to killing the direct child (and reap it either way)
+    let _ = child.kill().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Stdio;
+
+    #[tokio::test]
+    async fn test_kill_process_group() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "sleep 30 & sleep 30"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        isolate_process_group(&mut cmd);
+
+        let mut child = cmd.spawn().unwrap();
+        kill_process_group(&mut child).await;
+
+        let status = child.wait().await.unwrap();
+        assert!(!status.success());
+    }
+}
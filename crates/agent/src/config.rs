@@ -1,6 +1,12 @@
 //! Agent configuration
 
+use raibid_common::agents::DEFAULT_HEARTBEAT_INTERVAL_SECS;
+use raibid_common::queue::{
+    DEFAULT_CONSUMER_GROUP, DEFAULT_DEAD_LETTER_STREAM, DEFAULT_JOB_STREAM,
+    DEFAULT_LOG_STREAM_MAX_LENGTH,
+};
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 /// Agent configuration
@@ -15,14 +21,32 @@ pub struct AgentConfig {
     /// Workspace directory for cloning repositories
     pub workspace_dir: PathBuf,
 
-    /// Maximum number of concurrent jobs
+    /// Maximum number of jobs run in parallel
     pub max_concurrent_jobs: usize,
 
     /// Poll interval in milliseconds
     pub poll_interval_ms: u64,
 
-    /// Maximum number of retry attempts for failed jobs
+    /// Maximum number of times a job abandoned by a crashed agent is re-run
     pub max_retries: u32,
+
+    /// Idle time in milliseconds after which another agent's pending job is reclaimed
+    pub reclaim_idle_ms: u64,
+
+    /// Interval between registry heartbeats in seconds
+    pub heartbeat_interval_secs: u64,
+
+    /// Wrap rustc with sccache in build steps
+    pub use_sccache: bool,
+
+    /// OCI registry release images are pushed to (defaults to the Gitea host)
+    pub registry_url: Option<String>,
+
+    /// Address to serve Prometheus metrics on (disabled if unset)
+    pub metrics_addr: Option<SocketAddr>,
+
+    /// Lines each job's log stream is trimmed to once archived (0 = unlimited)
+    pub max_log_lines: usize,
 }
 
 impl Default for AgentConfig {
@@ -37,6 +61,12 @@ impl Default for AgentConfig {
             max_concurrent_jobs: 1,
             poll_interval_ms: 1000, // 1 second
             max_retries: 3,
+            reclaim_idle_ms: 5 * 60 * 1000, // 5 minutes
+            heartbeat_interval_secs: DEFAULT_HEARTBEAT_INTERVAL_SECS,
+            use_sccache: false,
+            registry_url: None,
+            metrics_addr: None,
+            max_log_lines: DEFAULT_LOG_STREAM_MAX_LENGTH,
         }
     }
 }
@@ -59,6 +89,9 @@ pub struct RedisConfig {
     /// Consumer group name
     pub consumer_group: String,
 
+    /// Stream that receives jobs which exhausted their retries
+    pub dead_letter_stream: String,
+
     /// Connection timeout in seconds
     pub connection_timeout_secs: u64,
 }
@@ -69,8 +102,9 @@ impl Default for RedisConfig {
             host: "raibid-redis-master.raibid-redis.svc.cluster.local".to_string(),
             port: 6379,
             password: None,
-            queue_stream: "raibid:jobs".to_string(),
-            consumer_group: "raibid-workers".to_string(),
+            queue_stream: DEFAULT_JOB_STREAM.to_string(),
+            consumer_group: DEFAULT_CONSUMER_GROUP.to_string(),
+            dead_letter_stream: DEFAULT_DEAD_LETTER_STREAM.to_string(),
             connection_timeout_secs: 30,
         }
     }
@@ -97,6 +131,7 @@ mod tests {
         assert!(!config.agent_id.is_empty());
         assert_eq!(config.max_concurrent_jobs, 1);
         assert_eq!(config.poll_interval_ms, 1000);
+        assert_eq!(config.heartbeat_interval_secs, 10);
     }
 
     #[test]
@@ -1,16 +1,28 @@
 //! Redis Streams job consumer
 
+use crate::cancel::spawn_cancel_watcher;
 use crate::config::AgentConfig;
 use crate::error::{AgentError, AgentResult};
 use crate::executor::JobExecutor;
-use chrono::Utc;
+use crate::heartbeat::ActiveJobs;
+use crate::metrics::AgentMetrics;
+use crate::reclaim::{
+    claim_stale_entries, dead_letter, keepalive_interval, retries_exhausted, spawn_claim_keepalive,
+};
+use raibid_common::commit_status::CommitStatusReporter;
 use raibid_common::jobs::{Job, JobStatus};
+use raibid_common::logs::{archive_log, trim_log};
+use raibid_common::queue::{JobEnvelope, ENVELOPE_FIELD};
+use raibid_common::storage::{ObjectStore, StorageConfig};
+use raibid_common::store::{JobStore, RedisJobStore};
 use redis::aio::MultiplexedConnection;
 use redis::streams::{StreamReadOptions, StreamReadReply};
 use redis::{AsyncCommands, Client, FromRedisValue};
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 /// Job message from Redis Streams
@@ -20,6 +32,8 @@ pub struct JobMessage {
     pub id: String,
     /// Job data
     pub job: Job,
+    /// Event that produced the job
+    pub event_type: String,
 }
 
 /// Redis Streams job consumer
@@ -27,6 +41,11 @@ pub struct JobConsumer {
     config: Arc<AgentConfig>,
     client: Client,
     executor: JobExecutor,
+    job_store: RedisJobStore,
+    active_jobs: ActiveJobs,
+    metrics: AgentMetrics,
+    statuses: CommitStatusReporter,
+    object_store: Option<Arc<dyn ObjectStore>>,
 }
 
 impl JobConsumer {
@@ -52,15 +71,53 @@ impl JobConsumer {
         // Create consumer group if it doesn't exist
         Self::ensure_consumer_group(&client, &config).await?;
 
-        let executor = JobExecutor::new(config.clone());
+        let metrics = AgentMetrics::new();
+        let object_store = match StorageConfig::from_env()
+            .and_then(|config| config.map(|config| config.open()).transpose())
+        {
+            Ok(store) => store,
+            Err(e) => {
+                warn!("Log archival and artifact upload disabled: {:#}", e);
+                None
+            }
+        };
+        let mut executor = JobExecutor::new(config.clone()).with_metrics(metrics.clone());
+        if let Some(ref store) = object_store {
+            executor = executor.with_object_store(store.clone());
+        }
+        let job_store = RedisJobStore::new(client.clone());
+        let statuses = CommitStatusReporter::from_env().unwrap_or_else(|e| {
+            warn!("Commit status reporting disabled: {:#}", e);
+            CommitStatusReporter::default()
+        });
 
         Ok(Self {
             config,
             client,
             executor,
+            job_store,
+            active_jobs: ActiveJobs::default(),
+            metrics,
+            statuses,
+            object_store,
         })
     }
 
+    /// Get the Redis client used by the consumer
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Get the set of jobs this consumer is running
+    pub fn active_jobs(&self) -> ActiveJobs {
+        self.active_jobs.clone()
+    }
+
+    /// Get the metrics recorded by this consumer
+    pub fn metrics(&self) -> &AgentMetrics {
+        &self.metrics
+    }
+
     /// Ensure the consumer group exists
     async fn ensure_consumer_group(client: &Client, config: &AgentConfig) -> AgentResult<()> {
         let mut conn = client.get_multiplexed_async_connection().await?;
@@ -97,70 +154,210 @@ impl JobConsumer {
     }
 
     /// Run the consumer loop
+    ///
+    /// Up to `max_concurrent_jobs` jobs run at once, each in its own task. The
+    /// loop only claims as many messages as there are free worker slots, so
+    /// unclaimed jobs stay in the stream for other agents. Jobs abandoned by
+    /// crashed agents are reclaimed before new ones are read.
+    ///
+    /// The blocking read has a connection of its own, so it never holds up the
+    /// acknowledgements and claim keepalives of running jobs.
     pub async fn run(self) -> AgentResult<()> {
+        let workers = self.config.max_concurrent_jobs.max(1);
         info!(
-            "Starting job consumer loop for agent {}",
-            self.config.agent_id
+            "Starting job consumer loop for agent {} with {} worker(s)",
+            self.config.agent_id, workers
         );
 
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let consumer = Arc::new(self);
+        let slots = Arc::new(Semaphore::new(workers));
+        let mut conn = consumer.client.get_multiplexed_async_connection().await?;
+        let mut read_conn = consumer.client.get_multiplexed_async_connection().await?;
         let last_id = ">".to_string(); // Start with new messages
 
+        // Look for abandoned jobs as often as running jobs refresh their claims
+        let reclaim_interval = keepalive_interval(consumer.config.reclaim_idle_ms);
+        let mut last_reclaim: Option<Instant> = None;
+
         loop {
-            match self.poll_jobs(&mut conn, &last_id).await {
-                Ok(messages) => {
-                    if !messages.is_empty() {
-                        info!("Received {} job(s)", messages.len());
-
-                        for msg in messages {
-                            // Process each job
-                            if let Err(e) = self.process_job(&mut conn, &msg).await {
-                                error!("Failed to process job {}: {}", msg.job.id, e);
-
-                                // Try to mark job as failed
-                                let _ = self
-                                    .update_job_status(
-                                        &mut conn,
-                                        &msg.job.id,
-                                        JobStatus::Failed,
-                                        Some(format!("Error: {}", e)),
-                                    )
-                                    .await;
-
-                                // Acknowledge the message even on failure to avoid reprocessing
-                                let _ = self.acknowledge_message(&mut conn, &msg.id).await;
-                            } else {
-                                // Acknowledge successful processing
-                                if let Err(e) = self.acknowledge_message(&mut conn, &msg.id).await {
-                                    error!("Failed to acknowledge message {}: {}", msg.id, e);
-                                }
-                            }
+            // Wait for a free worker slot before claiming more work
+            let permit = slots
+                .clone()
+                .acquire_owned()
+                .await
+                .map_err(|e| AgentError::Internal(format!("Worker pool closed: {}", e)))?;
+            let mut permits = vec![permit];
+            while let Ok(permit) = slots.clone().try_acquire_owned() {
+                permits.push(permit);
+            }
+
+            let mut messages = Vec::new();
+            if last_reclaim.is_none_or(|at| at.elapsed() >= reclaim_interval) {
+                last_reclaim = Some(Instant::now());
+                match consumer.reclaim_jobs(&mut conn, permits.len()).await {
+                    Ok(reclaimed) => messages = reclaimed,
+                    Err(e) => warn!("Error reclaiming pending jobs: {}", e),
+                }
+            }
+
+            let free = permits.len() - messages.len();
+            if free > 0 {
+                match consumer.poll_jobs(&mut read_conn, &last_id, free).await {
+                    Ok(new_messages) => {
+                        if !new_messages.is_empty() {
+                            info!("Received {} job(s)", new_messages.len());
                         }
+                        messages.extend(new_messages);
                     }
-                }
-                Err(e) => {
-                    error!("Error polling jobs: {}", e);
-                    // Wait before retrying
-                    tokio::time::sleep(Duration::from_millis(self.config.poll_interval_ms * 2))
+                    Err(e) => {
+                        error!("Error polling jobs: {}", e);
+                        // Wait before retrying
+                        tokio::time::sleep(Duration::from_millis(
+                            consumer.config.poll_interval_ms * 2,
+                        ))
                         .await;
+                    }
                 }
             }
 
+            // Each job holds a slot until it finishes; unused slots are released
+            for (msg, permit) in messages.into_iter().zip(permits) {
+                let consumer = consumer.clone();
+                let mut conn = conn.clone();
+                tokio::spawn(async move {
+                    consumer.handle_message(&mut conn, &msg).await;
+                    drop(permit);
+                });
+            }
+
             // Wait before next poll
-            tokio::time::sleep(Duration::from_millis(self.config.poll_interval_ms)).await;
+            tokio::time::sleep(Duration::from_millis(consumer.config.poll_interval_ms)).await;
+        }
+    }
+
+    /// Process a job message and acknowledge it
+    async fn handle_message(&self, conn: &mut MultiplexedConnection, msg: &JobMessage) {
+        if let Ok(mut jobs) = self.active_jobs.lock() {
+            jobs.insert(msg.job.id.clone());
+        }
+
+        // Keep the entry from looking abandoned while the job runs
+        let keepalive = spawn_claim_keepalive(conn.clone(), &self.config, msg.id.clone());
+        let result = self.process_job(msg).await;
+        keepalive.abort();
+
+        if let Ok(mut jobs) = self.active_jobs.lock() {
+            jobs.remove(&msg.job.id);
+        }
+
+        if let Err(e) = result {
+            error!("Failed to process job {}: {}", msg.job.id, e);
+
+            // Try to mark job as failed
+            if let Ok(job) = self
+                .job_store
+                .mark_finished(&msg.job.id, JobStatus::Failed, None)
+                .await
+            {
+                self.statuses.report(&job).await;
+            }
+
+            // Acknowledge the message even on failure to avoid reprocessing
+            let _ = self.acknowledge_message(conn, &msg.id).await;
+        } else {
+            // Acknowledge successful processing
+            if let Err(e) = self.acknowledge_message(conn, &msg.id).await {
+                error!("Failed to acknowledge message {}: {}", msg.id, e);
+            }
+        }
+    }
+
+    /// Claim jobs abandoned by other agents
+    ///
+    /// Returns the jobs to re-run. Entries whose job already finished are
+    /// acknowledged, and jobs that exhausted their retries are moved to the
+    /// dead-letter stream and marked failed.
+    async fn reclaim_jobs(
+        &self,
+        conn: &mut MultiplexedConnection,
+        count: usize,
+    ) -> AgentResult<Vec<JobMessage>> {
+        let entries = claim_stale_entries(conn, &self.config, count).await?;
+        let mut messages = Vec::new();
+
+        for entry in entries {
+            let msg = match Self::parse_job_message(&entry.id, &entry.map) {
+                Ok(msg) => msg,
+                Err(e) => {
+                    warn!("Failed to parse reclaimed message {}: {}", entry.id, e);
+                    let _ = self.acknowledge_message(conn, &entry.id).await;
+                    continue;
+                }
+            };
+
+            let job_id = &msg.job.id;
+            let record = self.job_store.get(job_id).await?;
+            let attempts = record.as_ref().map_or(0, |job| job.attempts);
+
+            if record.is_some_and(|job| job.status.is_terminal()) {
+                // The previous owner finished the job but died before acknowledging it
+                debug!("Reclaimed job {} already finished", job_id);
+                self.acknowledge_message(conn, &msg.id).await?;
+                continue;
+            }
+
+            if retries_exhausted(attempts, self.config.max_retries) {
+                warn!(
+                    "Job {} abandoned after {} attempt(s), moving to {}",
+                    job_id, attempts, self.config.redis.dead_letter_stream
+                );
+
+                let envelope_json: String = entry.get(ENVELOPE_FIELD).unwrap_or_default();
+                dead_letter(
+                    conn,
+                    &self.config,
+                    &msg.id,
+                    &envelope_json,
+                    attempts,
+                    "retries exhausted",
+                )
+                .await?;
+
+                match self
+                    .job_store
+                    .mark_finished(job_id, JobStatus::Failed, None)
+                    .await
+                {
+                    Ok(job) => self.statuses.report(&job).await,
+                    Err(e) => warn!("Failed to mark job {} as failed: {:#}", job_id, e),
+                }
+                self.acknowledge_message(conn, &msg.id).await?;
+                continue;
+            }
+
+            info!(
+                "Reclaimed job {} (attempt {} of {})",
+                job_id,
+                attempts + 1,
+                self.config.max_retries + 1
+            );
+            messages.push(msg);
         }
+
+        Ok(messages)
     }
 
-    /// Poll for new jobs from Redis Streams
+    /// Poll for up to `count` new jobs from Redis Streams
     async fn poll_jobs(
         &self,
         conn: &mut MultiplexedConnection,
         last_id: &str,
+        count: usize,
     ) -> AgentResult<Vec<JobMessage>> {
         // XREADGROUP GROUP group consumer [COUNT count] [BLOCK milliseconds] STREAMS key [key ...] id [id ...]
         let opts = StreamReadOptions::default()
             .group(&self.config.redis.consumer_group, &self.config.agent_id)
-            .count(self.config.max_concurrent_jobs)
+            .count(count)
             .block(self.config.poll_interval_ms as usize);
 
         let streams: StreamReadReply = conn
@@ -186,104 +383,145 @@ impl JobConsumer {
     }
 
     /// Parse a job message from Redis stream data
-    fn parse_job_message(
+    pub fn parse_job_message(
         id: &str,
         data: &HashMap<String, redis::Value>,
     ) -> AgentResult<JobMessage> {
-        // Extract job JSON from the stream data
-        let job_json = data
-            .get("job")
+        // Extract envelope JSON from the stream data
+        let envelope_json = data
+            .get(ENVELOPE_FIELD)
             .and_then(|v| {
                 // Convert Redis Value to String using FromRedisValue
                 String::from_redis_value(v).ok()
             })
-            .ok_or_else(|| AgentError::JobParsing("Missing 'job' field in message".to_string()))?;
+            .ok_or_else(|| {
+                AgentError::JobParsing(format!("Missing '{}' field in message", ENVELOPE_FIELD))
+            })?;
 
-        // Parse job from JSON
-        let job: Job = serde_json::from_str(&job_json)
-            .map_err(|e| AgentError::JobParsing(format!("Invalid job JSON: {}", e)))?;
+        // Parse and version-check the envelope
+        let envelope = JobEnvelope::decode(&envelope_json)
+            .map_err(|e| AgentError::JobParsing(format!("{:#}", e)))?;
 
         Ok(JobMessage {
             id: id.to_string(),
-            job,
+            job: envelope.job,
+            event_type: envelope.event_type,
         })
     }
 
     /// Process a single job
-    async fn process_job(
-        &self,
-        conn: &mut MultiplexedConnection,
-        msg: &JobMessage,
-    ) -> AgentResult<()> {
+    async fn process_job(&self, msg: &JobMessage) -> AgentResult<()> {
         let job_id = &msg.job.id;
         info!("Processing job: {}", job_id);
 
-        // Update job status to running
-        self.update_job_status(conn, job_id, JobStatus::Running, None)
+        // Jobs queued without a record (e.g. by older servers) are recorded as received
+        if self.job_store.get(job_id).await?.is_none() {
+            self.job_store.save(&msg.job).await?;
+        }
+
+        // Update job status to running, unless it was cancelled before it started
+        let job = self
+            .job_store
+            .mark_running(job_id, &self.config.agent_id)
             .await?;
+        if job.status.is_terminal() {
+            info!(
+                "Skipping job {}: {} before it started",
+                job_id,
+                job.status.as_str().to_lowercase()
+            );
+            return Ok(());
+        }
+        self.statuses.report(&job).await;
+
+        // Watch for cancellation while the job runs
+        let cancel = CancellationToken::new();
+        let watcher = spawn_cancel_watcher(
+            self.client.clone(),
+            job_id.clone(),
+            cancel.clone(),
+            Duration::from_millis(self.config.poll_interval_ms),
+        );
 
         // Execute the job
-        let result = self.executor.execute(&msg.job).await;
+        let result = self.executor.execute(&msg.job, &cancel).await;
+        cancel.cancel();
+        let _ = watcher.await;
+
+        // Record step results before the final status so they're visible once
+        // the job is terminal
+        if let Ok(ref result) = result {
+            self.metrics.record_steps(&result.steps);
+            if let Err(e) = self.job_store.save_steps(job_id, &result.steps).await {
+                warn!("Failed to save step results for job {}: {:#}", job_id, e);
+            }
+            if !result.uploads.is_empty() {
+                if let Err(e) = self.job_store.save_artifacts(job_id, &result.uploads).await {
+                    warn!("Failed to save artifact list for job {}: {:#}", job_id, e);
+                }
+            }
+        }
 
         // Update final status based on result
-        match result {
-            Ok(exit_code) => {
-                let status = if exit_code == 0 {
-                    JobStatus::Success
-                } else {
-                    JobStatus::Failed
-                };
-
-                self.update_job_status(
-                    conn,
-                    job_id,
-                    status,
-                    Some(format!("Exit code: {}", exit_code)),
-                )
-                .await?;
-
+        let (status, exit_code) = match result {
+            Ok(result) if result.cancelled => {
+                info!("Job {} cancelled", job_id);
+                (JobStatus::Cancelled, None)
+            }
+            Ok(result) => {
+                let exit_code = result.exit_code();
                 info!("Job {} completed with exit code {}", job_id, exit_code);
+
+                if result.success {
+                    (JobStatus::Success, Some(exit_code))
+                } else {
+                    (JobStatus::Failed, Some(exit_code))
+                }
+            }
+            Err(AgentError::Cancelled) => {
+                info!("Job {} cancelled", job_id);
+                (JobStatus::Cancelled, None)
             }
             Err(e) => {
-                self.update_job_status(
-                    conn,
-                    job_id,
-                    JobStatus::Failed,
-                    Some(format!("Execution error: {}", e)),
-                )
-                .await?;
-
                 error!("Job {} failed: {}", job_id, e);
+                (JobStatus::Failed, None)
             }
-        }
-
-        Ok(())
-    }
+        };
 
-    /// Update job status in Redis
-    async fn update_job_status(
-        &self,
-        conn: &mut MultiplexedConnection,
-        job_id: &str,
-        status: JobStatus,
-        message: Option<String>,
-    ) -> AgentResult<()> {
-        let status_key = format!("raibid:job:{}:status", job_id);
-        let status_value = serde_json::json!({
-            "status": status.as_str(),
-            "agent_id": self.config.agent_id,
-            "updated_at": Utc::now().to_rfc3339(),
-            "message": message,
-        });
+        // Archive the log before the job is terminal, so it's there once the
+        // server looks for it
+        self.archive_log(job_id).await;
 
-        let _: () = conn.set(&status_key, status_value.to_string()).await?;
+        let job = self
+            .job_store
+            .mark_finished(job_id, status, exit_code)
+            .await?;
+        self.statuses.report(&job).await;
+        self.metrics.record_job(status);
 
-        // Set TTL on status key (24 hours)
-        let _: () = conn.expire(&status_key, 86400).await?;
+        Ok(())
+    }
 
-        debug!("Updated job {} status to {}", job_id, status);
+    /// Archive a finished job's log to the object store, if one is configured
+    ///
+    /// The log stream is trimmed to `max_log_lines` only once archived, so
+    /// without an object store it keeps every line until the job is pruned.
+    async fn archive_log(&self, job_id: &str) {
+        let Some(store) = &self.object_store else {
+            return;
+        };
 
-        Ok(())
+        let result = async {
+            let mut conn = self.client.get_multiplexed_async_connection().await?;
+            let lines = archive_log(&mut conn, store.as_ref(), job_id).await?;
+            trim_log(&mut conn, job_id, self.config.max_log_lines).await?;
+            anyhow::Ok(lines)
+        }
+        .await;
+        match result {
+            Ok(lines) => debug!("Archived {} log lines of job {}", lines, job_id),
+            Err(e) => warn!("Failed to archive log of job {}: {:#}", job_id, e),
+        }
     }
 
     /// Acknowledge a processed message
@@ -313,29 +551,19 @@ mod tests {
     #[test]
     fn test_parse_job_message() {
         let mut data = HashMap::new();
-        let job = Job {
-            id: "test-job-1".to_string(),
-            repo: "test/repo".to_string(),
-            branch: "main".to_string(),
-            commit: "abc123".to_string(),
-            status: JobStatus::Pending,
-            started_at: Utc::now(),
-            finished_at: None,
-            duration: None,
-            agent_id: None,
-            exit_code: None,
-        };
+        let job = Job::new("test-job-1", "test/repo", "main", "abc123");
 
-        let job_json = serde_json::to_string(&job).unwrap();
+        let envelope_json = JobEnvelope::new(job, "push", None).encode().unwrap();
         data.insert(
-            "job".to_string(),
-            redis::Value::BulkString(job_json.into_bytes()),
+            ENVELOPE_FIELD.to_string(),
+            redis::Value::BulkString(envelope_json.into_bytes()),
         );
 
         let msg = JobConsumer::parse_job_message("1234567890-0", &data).unwrap();
         assert_eq!(msg.id, "1234567890-0");
         assert_eq!(msg.job.id, "test-job-1");
         assert_eq!(msg.job.repo, "test/repo");
+        assert_eq!(msg.event_type, "push");
     }
 
     #[test]
@@ -349,7 +577,7 @@ mod tests {
     fn test_parse_job_message_invalid_json() {
         let mut data = HashMap::new();
         data.insert(
-            "job".to_string(),
+            ENVELOPE_FIELD.to_string(),
             redis::Value::BulkString(b"invalid json".to_vec()),
         );
 
@@ -33,6 +33,14 @@ pub enum AgentError {
     #[error("Configuration error: {0}")]
     Configuration(String),
 
+    /// Job store error
+    #[error("Job store error: {0:#}")]
+    Store(#[from] anyhow::Error),
+
+    /// Job was cancelled
+    #[error("Job cancelled")]
+    Cancelled,
+
     /// Internal error
     #[error("Internal error: {0}")]
     Internal(String),
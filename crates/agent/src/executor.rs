@@ -1,20 +1,29 @@
 //! Job execution logic
+//!
+//! Prepares a workspace for a job and drives the [`PipelineExecutor`] over the
+//! repository's pipeline.
 
+use crate::artifacts::{collect_artifacts, upload_artifacts};
 use crate::config::AgentConfig;
 use crate::error::{AgentError, AgentResult};
 use crate::git::GitManager;
+use crate::metrics::AgentMetrics;
+use crate::pipeline::{load_pipeline, PipelineConfig, PipelineExecutor, PipelineResult};
+use raibid_common::artifacts::Artifact;
 use raibid_common::jobs::Job;
-use std::path::PathBuf;
-use std::process::Stdio;
+use raibid_common::storage::ObjectStore;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 /// Job executor
 pub struct JobExecutor {
     config: Arc<AgentConfig>,
     git_manager: GitManager,
+    metrics: AgentMetrics,
+    object_store: Option<Arc<dyn ObjectStore>>,
 }
 
 impl JobExecutor {
@@ -25,28 +34,51 @@ impl JobExecutor {
         Self {
             config,
             git_manager,
+            metrics: AgentMetrics::new(),
+            object_store: None,
         }
     }
 
+    /// Record metrics into a shared registry
+    pub fn with_metrics(mut self, metrics: AgentMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Upload pipeline artifacts to an object store
+    pub fn with_object_store(mut self, store: Arc<dyn ObjectStore>) -> Self {
+        self.object_store = Some(store);
+        self
+    }
+
     /// Execute a job
     ///
-    /// # Returns
-    /// Exit code of the build process
-    pub async fn execute(&self, job: &Job) -> AgentResult<i32> {
+    /// Clones the repository, runs its pipeline, stores artifact metadata and
+    /// uploads the pipeline's declared artifacts.
+    /// A job cancelled while its pipeline runs returns a result with `cancelled`
+    /// set; one cancelled before that returns [`AgentError::Cancelled`].
+    pub async fn execute(
+        &self,
+        job: &Job,
+        cancel: &CancellationToken,
+    ) -> AgentResult<PipelineResult> {
         info!("Executing job: {}", job.id);
 
-        // Step 1: Clone the repository
-        let repo_path = self.clone_repository(job).await?;
-
-        // Step 2: Execute the build pipeline
-        let exit_code = self.run_build_pipeline(&repo_path, job).await?;
+        // Step 1: Clone the repository into the job's own workspace
+        let workspace = self.git_manager.job_workspace(&job.id);
+        let result = match self.clone_repository(job).await {
+            Ok(_) if cancel.is_cancelled() => Err(AgentError::Cancelled),
+            // Step 2: Execute the build pipeline
+            Ok(repo_path) => self.run_build_pipeline(&repo_path, job, cancel).await,
+            Err(e) => Err(e),
+        };
 
-        // Step 3: Clean up (optional - keep for debugging in dev)
-        if let Err(e) = self.cleanup(&repo_path) {
+        // Step 3: Clean up the job's workspace, including failed clones
+        if let Err(e) = self.cleanup(&workspace) {
             warn!("Failed to cleanup workspace: {}", e);
         }
 
-        Ok(exit_code)
+        result
     }
 
     /// Clone the repository for the job
@@ -61,18 +93,27 @@ impl JobExecutor {
         // Clone in a blocking task since git2 is synchronous
         let git_manager = self.git_manager.clone();
         let repo_url_clone = repo_url.clone();
-        let branch = job.branch.clone();
+        let (branch, fetch_ref) = clone_refs(job);
         let commit = if !job.commit.is_empty() {
             Some(job.commit.clone())
         } else {
             None
         };
 
+        let job_id = job.id.clone();
+        let start = Instant::now();
         let repo_path = tokio::task::spawn_blocking(move || {
-            git_manager.clone_repository(&repo_url_clone, &branch, commit.as_deref())
+            git_manager.clone_repository(
+                &job_id,
+                &repo_url_clone,
+                branch.as_deref(),
+                commit.as_deref(),
+                fetch_ref.as_deref(),
+            )
         })
         .await
         .map_err(|e| AgentError::Internal(format!("Task join error: {}", e)))??;
+        self.metrics.record_clone(start.elapsed());
 
         info!("Repository cloned to: {:?}", repo_path);
 
@@ -81,117 +122,162 @@ impl JobExecutor {
 
     /// Construct repository URL from repo name
     fn construct_repo_url(&self, repo_name: &str) -> String {
-        // For MVP, use environment variable or default to Gitea in cluster
-        let gitea_host = std::env::var("GITEA_HOST")
-            .unwrap_or_else(|_| "gitea.raibid-gitea.svc.cluster.local:3000".to_string());
-
-        format!("http://{}/{}.git", gitea_host, repo_name)
+        format!("http://{}/{}.git", gitea_host(), repo_name)
     }
 
-    /// Run the build pipeline
-    async fn run_build_pipeline(&self, repo_path: &PathBuf, job: &Job) -> AgentResult<i32> {
-        info!("Running build pipeline for job: {}", job.id);
-
-        // For MVP, execute a simple Rust build
-        // In the future, this should be configurable based on agent type
-        let exit_code = self.run_rust_build(repo_path).await?;
+    /// Build the pipeline configuration for a job
+    ///
+    /// Release builds push a Docker image tagged with the release tag to the
+    /// registry; other builds don't publish images.
+    fn pipeline_config(&self, repo_path: &Path, job: &Job) -> PipelineConfig {
+        let (registry_url, image_tag) = match job.tag {
+            Some(ref tag) => {
+                let registry = self.config.registry_url.clone().unwrap_or_else(gitea_host);
+                let image = release_image(&registry, &job.repo, tag);
+                (Some(registry), Some(image))
+            }
+            None => (None, None),
+        };
 
-        Ok(exit_code)
+        PipelineConfig {
+            job_id: job.id.clone(),
+            repo_path: repo_path.to_path_buf(),
+            use_sccache: self.config.use_sccache,
+            registry_url,
+            image_tag,
+            redis_url: Some(self.config.redis.connection_url()),
+        }
     }
 
-    /// Run Rust build pipeline
-    async fn run_rust_build(&self, repo_path: &PathBuf) -> AgentResult<i32> {
-        info!("Running Rust build");
-
-        // Step 1: cargo check
-        let check_exit = self.run_command(repo_path, "cargo", &["check"]).await?;
-        if check_exit != 0 {
-            warn!("cargo check failed with exit code {}", check_exit);
-            return Ok(check_exit);
-        }
+    /// Run the build pipeline
+    ///
+    /// Runs the repository's `.raibid.yml` pipeline, or the default Rust pipeline
+    /// when the repository doesn't declare one.
+    async fn run_build_pipeline(
+        &self,
+        repo_path: &Path,
+        job: &Job,
+        cancel: &CancellationToken,
+    ) -> AgentResult<PipelineResult> {
+        info!("Running build pipeline for job: {}", job.id);
 
-        // Step 2: cargo test
-        let test_exit = self.run_command(repo_path, "cargo", &["test"]).await?;
-        if test_exit != 0 {
-            warn!("cargo test failed with exit code {}", test_exit);
-            return Ok(test_exit);
+        let definition = load_pipeline(repo_path)
+            .map_err(|e| AgentError::BuildExecution(format!("{:#}", e)))?
+            .for_branch(&job.branch);
+        let patterns = definition.artifacts.clone();
+
+        let executor = PipelineExecutor::new(self.pipeline_config(repo_path, job))
+            .map_err(|e| AgentError::BuildExecution(format!("{:#}", e)))?
+            .with_definition(definition)
+            .with_cancellation(cancel.clone());
+
+        let mut result = executor
+            .execute()
+            .await
+            .map_err(|e| AgentError::BuildExecution(format!("{:#}", e)))?;
+
+        // Metadata is best effort: a Redis hiccup shouldn't fail a finished build
+        if let Some(ref artifacts) = result.artifacts {
+            if let Err(e) = executor.store_artifacts(artifacts).await {
+                warn!("Failed to store artifacts for job {}: {:#}", job.id, e);
+            }
         }
 
-        // Step 3: cargo build --release
-        let build_exit = self
-            .run_command(repo_path, "cargo", &["build", "--release"])
-            .await?;
-        if build_exit != 0 {
-            warn!("cargo build failed with exit code {}", build_exit);
-            return Ok(build_exit);
+        // Failed builds leave partial output behind, so only successful ones
+        // publish artifacts
+        if result.success && !patterns.is_empty() {
+            result.uploads = self.upload_artifacts(repo_path, job, &patterns).await;
         }
 
-        info!("Rust build completed successfully");
-        Ok(0)
+        Ok(result)
     }
 
-    /// Run a command and stream output
-    async fn run_command(
+    /// Upload the files matching a pipeline's artifact patterns
+    ///
+    /// Best effort like artifact metadata: a job whose upload fails still
+    /// succeeds, with the failed artifacts missing from its list.
+    async fn upload_artifacts(
         &self,
-        working_dir: &PathBuf,
-        program: &str,
-        args: &[&str],
-    ) -> AgentResult<i32> {
-        debug!("Running command: {} {}", program, args.join(" "));
-
-        let mut child = Command::new(program)
-            .args(args)
-            .current_dir(working_dir)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| {
-                AgentError::BuildExecution(format!("Failed to spawn {}: {}", program, e))
-            })?;
-
-        // Stream stdout
-        if let Some(stdout) = child.stdout.take() {
-            let reader = BufReader::new(stdout);
-            let mut lines = reader.lines();
-
-            tokio::spawn(async move {
-                while let Ok(Some(line)) = lines.next_line().await {
-                    info!("[stdout] {}", line);
-                }
-            });
-        }
+        repo_path: &Path,
+        job: &Job,
+        patterns: &[String],
+    ) -> Vec<Artifact> {
+        let Some(store) = &self.object_store else {
+            warn!(
+                "Job {} declares artifacts but no object store is configured",
+                job.id
+            );
+            return Vec::new();
+        };
 
-        // Stream stderr
-        if let Some(stderr) = child.stderr.take() {
-            let reader = BufReader::new(stderr);
-            let mut lines = reader.lines();
+        let files = match collect_artifacts(repo_path, patterns) {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Failed to collect artifacts for job {}: {:#}", job.id, e);
+                return Vec::new();
+            }
+        };
+        let artifacts = upload_artifacts(store.as_ref(), &job.id, &files).await;
+        info!(
+            "Uploaded {} of {} artifacts for job {}",
+            artifacts.len(),
+            files.len(),
+            job.id
+        );
+        artifacts
+    }
 
-            tokio::spawn(async move {
-                while let Ok(Some(line)) = lines.next_line().await {
-                    warn!("[stderr] {}", line);
-                }
-            });
+    /// Clean up a job's workspace after execution
+    fn cleanup(&self, workspace: &Path) -> AgentResult<()> {
+        if workspace.exists() {
+            debug!("Cleaning up workspace: {:?}", workspace);
+            std::fs::remove_dir_all(workspace)?;
         }
+        Ok(())
+    }
+}
 
-        // Wait for the command to complete
-        let status = child.wait().await.map_err(|e| {
-            AgentError::BuildExecution(format!("Failed to wait for {}: {}", program, e))
-        })?;
+/// Branch to clone and extra ref to fetch for a job
+///
+/// Pull requests clone the repository they were opened against and fetch the
+/// head commit from its pull request ref, since the head branch may only exist
+/// in a fork. Tags aren't branches: release builds clone the default branch and
+/// check out the tagged commit.
+fn clone_refs(job: &Job) -> (Option<String>, Option<String>) {
+    match job.pull_request {
+        Some(ref pr) => (None, Some(pr.fetch_ref())),
+        None if job.is_release() => (None, None),
+        None => (Some(job.branch.clone()), None),
+    }
+}
 
-        let exit_code = status.code().unwrap_or(-1);
-        debug!("Command {} exited with code {}", program, exit_code);
+/// Get the Gitea host repositories are cloned from
+fn gitea_host() -> String {
+    // For MVP, use environment variable or default to Gitea in cluster
+    std::env::var("GITEA_HOST")
+        .unwrap_or_else(|_| "gitea.raibid-gitea.svc.cluster.local:3000".to_string())
+}
 
-        Ok(exit_code)
-    }
+/// Get the image reference a release is published as (`<registry>/<repo>:<tag>`)
+///
+/// Image names must be lowercase, and tags may only contain `[A-Za-z0-9_.-]`.
+fn release_image(registry: &str, repo: &str, tag: &str) -> String {
+    let registry = registry
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/');
+    let tag: String = tag
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
 
-    /// Clean up workspace after job execution
-    fn cleanup(&self, repo_path: &PathBuf) -> AgentResult<()> {
-        if repo_path.exists() {
-            debug!("Cleaning up repository: {:?}", repo_path);
-            std::fs::remove_dir_all(repo_path)?;
-        }
-        Ok(())
-    }
+    format!("{}/{}:{}", registry, repo.to_lowercase(), tag)
 }
 
 #[cfg(test)]
@@ -220,27 +306,76 @@ mod tests {
         std::env::remove_var("GITEA_HOST");
     }
 
-    #[tokio::test]
-    async fn test_run_command_success() {
-        let config = Arc::new(AgentConfig::default());
-        let executor = JobExecutor::new(config);
-
-        let temp_dir = std::env::temp_dir();
-        let exit_code = executor.run_command(&temp_dir, "echo", &["hello"]).await;
-
-        assert!(exit_code.is_ok());
-        assert_eq!(exit_code.unwrap(), 0);
+    #[test]
+    fn test_pipeline_config_from_agent_config() {
+        let config = Arc::new(AgentConfig {
+            use_sccache: true,
+            ..AgentConfig::default()
+        });
+        let executor = JobExecutor::new(config.clone());
+
+        let job = Job::new("job-1", "user/repo", "main", "abc123");
+
+        let pipeline = executor.pipeline_config(Path::new("/tmp/repo"), &job);
+        assert_eq!(pipeline.job_id, "job-1");
+        assert!(pipeline.use_sccache);
+        assert_eq!(pipeline.redis_url, Some(config.redis.connection_url()));
+        assert_eq!(pipeline.registry_url, None);
+        assert_eq!(pipeline.image_tag, None);
+
+        let release = Job {
+            branch: "v1.2.3".to_string(),
+            tag: Some("v1.2.3".to_string()),
+            ..job
+        };
+        let executor = JobExecutor::new(Arc::new(AgentConfig {
+            registry_url: Some("https://registry.example.com/".to_string()),
+            ..AgentConfig::default()
+        }));
+
+        let pipeline = executor.pipeline_config(Path::new("/tmp/repo"), &release);
+        assert_eq!(
+            pipeline.registry_url.as_deref(),
+            Some("https://registry.example.com/")
+        );
+        assert_eq!(
+            pipeline.image_tag.as_deref(),
+            Some("registry.example.com/user/repo:v1.2.3")
+        );
     }
 
-    #[tokio::test]
-    async fn test_run_command_failure() {
-        let config = Arc::new(AgentConfig::default());
-        let executor = JobExecutor::new(config);
-
-        let temp_dir = std::env::temp_dir();
-        let exit_code = executor.run_command(&temp_dir, "false", &[]).await;
+    #[test]
+    fn test_clone_refs() {
+        let job = Job::new("job-1", "owner/repo", "feature", "abc123");
+        assert_eq!(clone_refs(&job), (Some("feature".to_string()), None));
+
+        // A fork's branch doesn't exist in the base repository
+        let fork_pr = Job {
+            pull_request: Some(raibid_common::jobs::PullRequest {
+                number: 7,
+                base_branch: "main".to_string(),
+                head_repo: "contributor/repo".to_string(),
+                head_ref: "refs/merge-requests/7/head".to_string(),
+            }),
+            ..job.clone()
+        };
+        assert_eq!(
+            clone_refs(&fork_pr),
+            (None, Some("refs/merge-requests/7/head".to_string()))
+        );
+
+        let release = Job {
+            tag: Some("v1.0.0".to_string()),
+            ..job
+        };
+        assert_eq!(clone_refs(&release), (None, None));
+    }
 
-        assert!(exit_code.is_ok());
-        assert_ne!(exit_code.unwrap(), 0);
+    #[test]
+    fn test_release_image() {
+        assert_eq!(
+            release_image("gitea.local:3000", "Raibid-Labs/CI", "v1.0+build/1"),
+            "gitea.local:3000/raibid-labs/ci:v1.0-build-1"
+        );
     }
 }
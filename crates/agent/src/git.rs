@@ -17,29 +17,47 @@ impl GitManager {
         Self { workspace_dir }
     }
 
-    /// Clone a repository to the workspace
+    /// Get the workspace directory reserved for a job
+    ///
+    /// Each job clones into its own directory so concurrent builds of the same
+    /// repository don't share a checkout.
+    pub fn job_workspace(&self, job_id: &str) -> PathBuf {
+        self.workspace_dir.join(job_id)
+    }
+
+    /// Clone a repository into a job's workspace
     ///
     /// # Arguments
+    /// * `job_id` - Job the checkout belongs to
     /// * `repo_url` - Repository URL (e.g., "https://github.com/user/repo.git")
-    /// * `branch` - Branch name to checkout
+    /// * `branch` - Branch name to checkout (the remote's default branch if `None`)
     /// * `commit` - Optional commit SHA to checkout
+    /// * `fetch_ref` - Extra ref to fetch after cloning (e.g. `refs/pull/42/head`),
+    ///   checked out unless `commit` is given
     ///
     /// # Returns
     /// Path to the cloned repository
     pub fn clone_repository(
         &self,
+        job_id: &str,
         repo_url: &str,
-        branch: &str,
+        branch: Option<&str>,
         commit: Option<&str>,
+        fetch_ref: Option<&str>,
     ) -> AgentResult<PathBuf> {
-        info!("Cloning repository: {} (branch: {})", repo_url, branch);
+        info!(
+            "Cloning repository: {} (branch: {})",
+            repo_url,
+            branch.unwrap_or("default")
+        );
 
-        // Create workspace directory if it doesn't exist
-        std::fs::create_dir_all(&self.workspace_dir)?;
+        // Create the job's workspace directory if it doesn't exist
+        let job_workspace = self.job_workspace(job_id);
+        std::fs::create_dir_all(&job_workspace)?;
 
         // Extract repo name from URL
         let repo_name = Self::extract_repo_name(repo_url)?;
-        let repo_path = self.workspace_dir.join(&repo_name);
+        let repo_path = job_workspace.join(&repo_name);
 
         // Remove existing directory if it exists
         if repo_path.exists() {
@@ -47,6 +65,43 @@ impl GitManager {
             std::fs::remove_dir_all(&repo_path)?;
         }
 
+        // Clone the repository
+        let mut builder = RepoBuilder::new();
+        if let Some(branch) = branch {
+            builder.branch(branch);
+        }
+        builder.fetch_options(Self::fetch_options());
+
+        let repo = builder.clone(repo_url, &repo_path)?;
+        info!("Repository cloned to {:?}", repo_path);
+
+        // Pull request heads aren't on any branch of the repository, and may
+        // only exist there under the provider's pull request ref
+        if let Some(fetch_ref) = fetch_ref {
+            debug!("Fetching ref: {}", fetch_ref);
+            let refspec = format!("+{0}:{0}", fetch_ref);
+            repo.find_remote("origin")?.fetch(
+                &[&refspec],
+                Some(&mut Self::fetch_options()),
+                None,
+            )?;
+
+            if commit.is_none() {
+                let oid = repo.refname_to_id(fetch_ref)?;
+                self.checkout_commit(&repo, &oid.to_string())?;
+            }
+        }
+
+        // Checkout specific commit if provided
+        if let Some(commit_sha) = commit {
+            self.checkout_commit(&repo, commit_sha)?;
+        }
+
+        Ok(repo_path)
+    }
+
+    /// Fetch options with credential callbacks
+    fn fetch_options() -> FetchOptions<'static> {
         // Setup callbacks for authentication
         let mut callbacks = RemoteCallbacks::new();
         callbacks.credentials(|_url, username_from_url, _allowed_types| {
@@ -58,24 +113,9 @@ impl GitManager {
             }
         });
 
-        // Setup fetch options
         let mut fetch_options = FetchOptions::new();
         fetch_options.remote_callbacks(callbacks);
-
-        // Clone the repository
-        let mut builder = RepoBuilder::new();
-        builder.branch(branch);
-        builder.fetch_options(fetch_options);
-
-        let repo = builder.clone(repo_url, &repo_path)?;
-        info!("Repository cloned to {:?}", repo_path);
-
-        // Checkout specific commit if provided
-        if let Some(commit_sha) = commit {
-            self.checkout_commit(&repo, commit_sha)?;
-        }
-
-        Ok(repo_path)
+        fetch_options
     }
 
     /// Checkout a specific commit
@@ -178,10 +218,66 @@ mod tests {
         assert!(GitManager::extract_repo_name("").is_err());
     }
 
+    /// Commit an empty tree to `update_ref` in `repo`
+    fn commit(repo: &Repository, update_ref: Option<&str>, parents: &[&git2::Commit]) -> git2::Oid {
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(update_ref, &signature, &signature, "commit", &tree, parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_clone_fetches_pull_request_ref() {
+        // The base repository only has the fork's commit under the PR ref
+        let base_dir = tempfile::tempdir().unwrap();
+        let base = Repository::init(base_dir.path()).unwrap();
+        let main = commit(&base, Some("HEAD"), &[]);
+        let main_commit = base.find_commit(main).unwrap();
+        let head = commit(&base, Some("refs/pull/7/head"), &[&main_commit]);
+
+        let workspace = tempfile::tempdir().unwrap();
+        let manager = GitManager::new(workspace.path().to_path_buf());
+        let url = base_dir.path().to_str().unwrap();
+
+        let path = manager
+            .clone_repository(
+                "job-1",
+                url,
+                None,
+                Some(&head.to_string()),
+                Some("refs/pull/7/head"),
+            )
+            .unwrap();
+        let clone = Repository::open(&path).unwrap();
+        assert_eq!(clone.head().unwrap().target(), Some(head));
+
+        // Without a commit the fetched ref is checked out, so it must have been fetched
+        let path = manager
+            .clone_repository("job-2", url, None, None, Some("refs/pull/7/head"))
+            .unwrap();
+        let clone = Repository::open(&path).unwrap();
+        assert_eq!(clone.head().unwrap().target(), Some(head));
+    }
+
     #[test]
     fn test_git_manager_creation() {
         let workspace = PathBuf::from("/tmp/test-workspace");
         let manager = GitManager::new(workspace.clone());
         assert_eq!(manager.workspace_dir, workspace);
     }
+
+    #[test]
+    fn test_job_workspaces_are_isolated() {
+        let manager = GitManager::new(PathBuf::from("/tmp/test-workspace"));
+
+        assert_eq!(
+            manager.job_workspace("job-1"),
+            PathBuf::from("/tmp/test-workspace/job-1")
+        );
+        assert_ne!(
+            manager.job_workspace("job-1"),
+            manager.job_workspace("job-2")
+        );
+    }
 }
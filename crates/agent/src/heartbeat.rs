@@ -0,0 +1,176 @@
+//! Agent heartbeat
+//!
+//! The agent registers itself in the agent registry on startup and refreshes its
+//! entry every heartbeat interval with the jobs it is running and the host load.
+//! The server reports the agent as offline once heartbeats stop arriving.
+
+use chrono::{DateTime, Utc};
+use raibid_common::agents::{AgentInfo, AgentStatus};
+use raibid_common::store::{AgentRegistry, RedisAgentRegistry};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::config::AgentConfig;
+
+/// IDs of the jobs an agent is currently running
+pub type ActiveJobs = Arc<Mutex<BTreeSet<String>>>;
+
+/// Periodically publishes this agent's registration
+pub struct Heartbeat {
+    config: Arc<AgentConfig>,
+    registry: RedisAgentRegistry,
+    active_jobs: ActiveJobs,
+    hostname: String,
+    started_at: DateTime<Utc>,
+}
+
+impl Heartbeat {
+    /// Create a heartbeat reporting the given active jobs
+    pub fn new(
+        config: Arc<AgentConfig>,
+        registry: RedisAgentRegistry,
+        active_jobs: ActiveJobs,
+    ) -> Self {
+        Self {
+            config,
+            registry,
+            active_jobs,
+            hostname: hostname(),
+            started_at: Utc::now(),
+        }
+    }
+
+    /// Build the registration sent with the next heartbeat
+    pub fn snapshot(&self) -> AgentInfo {
+        let current_jobs: Vec<String> = self
+            .active_jobs
+            .lock()
+            .map(|jobs| jobs.iter().cloned().collect())
+            .unwrap_or_default();
+
+        let status = if current_jobs.is_empty() {
+            AgentStatus::Idle
+        } else {
+            AgentStatus::Busy
+        };
+
+        AgentInfo {
+            id: self.config.agent_id.clone(),
+            hostname: self.hostname.clone(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            status,
+            current_jobs,
+            capacity: self.config.max_concurrent_jobs.max(1) as u32,
+            load: load_per_cpu(),
+            started_at: self.started_at,
+            last_heartbeat: Utc::now(),
+        }
+    }
+
+    /// Send one heartbeat
+    pub async fn beat(&self) -> anyhow::Result<()> {
+        self.registry.heartbeat(&self.snapshot()).await
+    }
+
+    /// Send heartbeats until the returned task is aborted
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        let interval = Duration::from_secs(self.config.heartbeat_interval_secs.max(1));
+        info!(
+            "Registering agent {} on {}",
+            self.config.agent_id, self.hostname
+        );
+
+        tokio::spawn(async move {
+            loop {
+                match self.beat().await {
+                    Ok(()) => debug!("Sent heartbeat"),
+                    Err(e) => warn!("Failed to send heartbeat: {:#}", e),
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    /// Remove this agent from the registry
+    pub async fn deregister(&self) {
+        if let Err(e) = self.registry.remove(&self.config.agent_id).await {
+            warn!("Failed to deregister agent: {:#}", e);
+        }
+    }
+}
+
+/// Get the host name, falling back to `HOSTNAME` or "unknown"
+fn hostname() -> String {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        // SAFETY: the buffer outlives the call and its length is passed along
+        let rc = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+        if rc == 0 {
+            let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+            if let Ok(name) = std::str::from_utf8(&buf[..len]) {
+                if !name.is_empty() {
+                    return name.to_string();
+                }
+            }
+        }
+    }
+
+    std::env::var("HOSTNAME").unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Get the one-minute load average divided by the number of CPUs
+fn load_per_cpu() -> Option<f64> {
+    #[cfg(unix)]
+    {
+        let mut loadavg = [0f64; 1];
+        // SAFETY: the buffer holds the single sample requested
+        let samples = unsafe { libc::getloadavg(loadavg.as_mut_ptr(), 1) };
+        if samples == 1 {
+            let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+            return Some(loadavg[0] / cpus as f64);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heartbeat(active_jobs: ActiveJobs) -> Heartbeat {
+        let config = AgentConfig {
+            agent_id: "agent-1".to_string(),
+            max_concurrent_jobs: 4,
+            ..Default::default()
+        };
+        let client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
+
+        Heartbeat::new(
+            Arc::new(config),
+            RedisAgentRegistry::new(client),
+            active_jobs,
+        )
+    }
+
+    #[test]
+    fn test_snapshot_reports_active_jobs() {
+        let active_jobs = ActiveJobs::default();
+        let heartbeat = heartbeat(active_jobs.clone());
+
+        let idle = heartbeat.snapshot();
+        assert_eq!(idle.id, "agent-1");
+        assert_eq!(idle.status, AgentStatus::Idle);
+        assert_eq!(idle.capacity, 4);
+        assert!(!idle.hostname.is_empty());
+
+        active_jobs.lock().unwrap().insert("job-1".to_string());
+        let busy = heartbeat.snapshot();
+        assert_eq!(busy.status, AgentStatus::Busy);
+        assert_eq!(busy.current_jobs, vec!["job-1"]);
+    }
+}
@@ -8,18 +8,30 @@
 //! - Result reporting back to the server
 //! - Complete Rust build pipeline (check, test, build, clippy, audit)
 //! - Docker image building and publishing
+//! - Artifact upload to object storage
 //! - Log streaming to Redis
+//! - Job cancellation
+//! - Recovery of jobs abandoned by crashed agents
+//! - Registration and heartbeats in the agent registry
+//! - Prometheus metrics for steps, jobs and clones
 
 #![allow(dead_code)]
 
+use raibid_common::store::RedisAgentRegistry;
 use std::sync::Arc;
+use tracing::info;
 
+pub mod artifacts;
+pub mod cancel;
 pub mod config;
 pub mod consumer;
 pub mod error;
 pub mod executor;
 pub mod git;
+pub mod heartbeat;
+pub mod metrics;
 pub mod pipeline;
+pub mod reclaim;
 
 // Re-export commonly used types
 pub use config::{AgentConfig, RedisConfig};
@@ -27,6 +39,8 @@ pub use consumer::{JobConsumer, JobMessage};
 pub use error::{AgentError, AgentResult};
 pub use executor::JobExecutor;
 pub use git::GitManager;
+pub use heartbeat::Heartbeat;
+pub use metrics::AgentMetrics;
 pub use pipeline::{
     ArtifactMetadata, BuildStep, PipelineConfig, PipelineExecutor, PipelineResult, StepResult,
 };
@@ -47,8 +61,37 @@ impl Agent {
     }
 
     /// Run the agent
+    ///
+    /// Heartbeats are sent (and metrics served, if configured) for as long as the
+    /// consumer runs. On Ctrl+C the agent leaves the registry; jobs it was running
+    /// are reclaimed by other agents.
     pub async fn run(self) -> AgentResult<()> {
-        self.consumer.run().await
+        let registry = RedisAgentRegistry::new(self.consumer.client().clone());
+        let heartbeat = Arc::new(Heartbeat::new(
+            self.config.clone(),
+            registry,
+            self.consumer.active_jobs(),
+        ));
+        let heartbeat_task = heartbeat.clone().spawn();
+        let metrics_task = self
+            .config
+            .metrics_addr
+            .map(|addr| self.consumer.metrics().spawn_listener(addr));
+
+        let result = tokio::select! {
+            result = self.consumer.run() => result,
+            _ = tokio::signal::ctrl_c() => {
+                info!("Received Ctrl+C signal, shutting down");
+                Ok(())
+            }
+        };
+
+        heartbeat_task.abort();
+        if let Some(task) = metrics_task {
+            task.abort();
+        }
+        heartbeat.deregister().await;
+        result
     }
 }
 
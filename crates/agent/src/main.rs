@@ -85,11 +85,16 @@ fn load_config() -> anyhow::Result<AgentConfig> {
         config.redis.password = Some(redis_password);
     }
 
-    if let Ok(queue_stream) = std::env::var("QUEUE_STREAM") {
+    // Accept both the agent-specific and the shared raibid variable names
+    if let Ok(queue_stream) =
+        std::env::var("QUEUE_STREAM").or_else(|_| std::env::var("RAIBID_REDIS_JOB_STREAM"))
+    {
         config.redis.queue_stream = queue_stream;
     }
 
-    if let Ok(consumer_group) = std::env::var("CONSUMER_GROUP") {
+    if let Ok(consumer_group) =
+        std::env::var("CONSUMER_GROUP").or_else(|_| std::env::var("RAIBID_REDIS_CONSUMER_GROUP"))
+    {
         config.redis.consumer_group = consumer_group;
     }
 
@@ -105,5 +110,37 @@ fn load_config() -> anyhow::Result<AgentConfig> {
         config.poll_interval_ms = poll_interval.parse()?;
     }
 
+    if let Ok(max_retries) = std::env::var("MAX_RETRIES") {
+        config.max_retries = max_retries.parse()?;
+    }
+
+    if let Ok(reclaim_idle) = std::env::var("RECLAIM_IDLE_MS") {
+        config.reclaim_idle_ms = reclaim_idle.parse()?;
+    }
+
+    if let Ok(dead_letter_stream) = std::env::var("DEAD_LETTER_STREAM") {
+        config.redis.dead_letter_stream = dead_letter_stream;
+    }
+
+    if let Ok(heartbeat_interval) = std::env::var("HEARTBEAT_INTERVAL_SECS") {
+        config.heartbeat_interval_secs = heartbeat_interval.parse()?;
+    }
+
+    if let Ok(use_sccache) = std::env::var("USE_SCCACHE") {
+        config.use_sccache = use_sccache.parse()?;
+    }
+
+    if let Ok(registry_url) = std::env::var("REGISTRY_URL") {
+        config.registry_url = Some(registry_url);
+    }
+
+    if let Ok(metrics_addr) = std::env::var("METRICS_ADDR") {
+        config.metrics_addr = Some(metrics_addr.parse()?);
+    }
+
+    if let Ok(max_log_lines) = std::env::var("MAX_LOG_LINES") {
+        config.max_log_lines = max_log_lines.parse()?;
+    }
+
     Ok(config)
 }
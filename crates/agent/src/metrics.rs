@@ -0,0 +1,179 @@
+//! Agent metrics
+//!
+//! Step durations, job outcomes and clone times are always recorded. When
+//! `METRICS_ADDR` is set the agent serves them in the Prometheus text format at
+//! `GET /metrics` on that address.
+
+use axum::{http::header, response::IntoResponse, routing::get, Router};
+use prometheus::{
+    exponential_buckets, Histogram, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry,
+    TextEncoder,
+};
+use raibid_common::jobs::{JobStatus, StepResult};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Content type of the Prometheus text exposition format
+const TEXT_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Agent metrics registry
+#[derive(Clone)]
+pub struct AgentMetrics {
+    registry: Registry,
+    step_duration: HistogramVec,
+    jobs: IntCounterVec,
+    clone_duration: Histogram,
+}
+
+impl AgentMetrics {
+    /// Create a registry with all agent metrics
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        // 1s to ~68min
+        let step_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "raibid_agent_step_duration_seconds",
+                "Pipeline step duration by step and result",
+            )
+            .buckets(exponential_buckets(1.0, 2.0, 13).expect("valid buckets")),
+            &["step", "result"],
+        )
+        .expect("valid metric");
+        let jobs = IntCounterVec::new(
+            Opts::new("raibid_agent_jobs_total", "Jobs finished by status"),
+            &["status"],
+        )
+        .expect("valid metric");
+        // 100ms to ~3.4min
+        let clone_duration = Histogram::with_opts(
+            HistogramOpts::new(
+                "raibid_agent_clone_duration_seconds",
+                "Time taken to clone a job's repository",
+            )
+            .buckets(exponential_buckets(0.1, 2.0, 12).expect("valid buckets")),
+        )
+        .expect("valid metric");
+
+        registry
+            .register(Box::new(step_duration.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(jobs.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(clone_duration.clone()))
+            .expect("unique metric");
+
+        Self {
+            registry,
+            step_duration,
+            jobs,
+            clone_duration,
+        }
+    }
+
+    /// Record the steps of a finished pipeline
+    pub fn record_steps(&self, steps: &[StepResult]) {
+        for step in steps {
+            let result = if step.success { "success" } else { "failed" };
+            self.step_duration
+                .with_label_values(&[&step.step, result])
+                .observe(step.duration_secs as f64);
+        }
+    }
+
+    /// Record a job reaching a terminal status
+    pub fn record_job(&self, status: JobStatus) {
+        let label = status.as_str().to_lowercase();
+        self.jobs.with_label_values(&[&label]).inc();
+    }
+
+    /// Record how long a clone took
+    pub fn record_clone(&self, elapsed: Duration) {
+        self.clone_duration.observe(elapsed.as_secs_f64());
+    }
+
+    /// Render all metrics in the Prometheus text format
+    pub fn render(&self) -> anyhow::Result<String> {
+        Ok(TextEncoder::new().encode_to_string(&self.registry.gather())?)
+    }
+
+    /// Serve `GET /metrics` on an address until the returned task is aborted
+    pub fn spawn_listener(&self, addr: SocketAddr) -> tokio::task::JoinHandle<()> {
+        let metrics = self.clone();
+        let app = Router::new().route(
+            "/metrics",
+            get(move || {
+                let metrics = metrics.clone();
+                async move { metrics.scrape() }
+            }),
+        );
+
+        tokio::spawn(async move {
+            let listener = match tokio::net::TcpListener::bind(addr).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    warn!("Failed to bind metrics listener on {}: {}", addr, e);
+                    return;
+                }
+            };
+
+            info!("Serving metrics on http://{}/metrics", addr);
+            if let Err(e) = axum::serve(listener, app).await {
+                warn!("Metrics listener stopped: {}", e);
+            }
+        })
+    }
+
+    /// Build the scrape response
+    fn scrape(&self) -> axum::response::Response {
+        match self.render() {
+            Ok(text) => ([(header::CONTENT_TYPE, TEXT_CONTENT_TYPE)], text).into_response(),
+            Err(e) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to encode metrics: {:#}", e),
+            )
+                .into_response(),
+        }
+    }
+}
+
+impl Default for AgentMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(name: &str, success: bool, duration_secs: u64) -> StepResult {
+        StepResult {
+            step: name.to_string(),
+            success,
+            exit_code: Some(if success { 0 } else { 1 }),
+            duration_secs,
+            output: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_recorded_metrics() {
+        let metrics = AgentMetrics::new();
+        metrics.record_steps(&[step("build", true, 30), step("test", false, 5)]);
+        metrics.record_job(JobStatus::Failed);
+        metrics.record_clone(Duration::from_millis(250));
+
+        let text = metrics.render().unwrap();
+        assert!(text.contains(
+            r#"raibid_agent_step_duration_seconds_count{result="success",step="build"} 1"#
+        ));
+        assert!(text
+            .contains(r#"raibid_agent_step_duration_seconds_sum{result="failed",step="test"} 5"#));
+        assert!(text.contains(r#"raibid_agent_jobs_total{status="failed"} 1"#));
+        assert!(text.contains("raibid_agent_clone_duration_seconds_count 1"));
+    }
+}
@@ -1,6 +1,7 @@
 //! Build pipeline execution module
 //!
-//! This module handles the complete Rust build pipeline including:
+//! This module runs the steps of a [`PipelineDefinition`], either the one a
+//! repository declares in `.raibid.yml` or the default Rust pipeline:
 //! - Code quality checks (cargo check, clippy, fmt)
 //! - Testing (cargo test)
 //! - Building (cargo build)
@@ -9,22 +10,29 @@
 //! - Log streaming to Redis
 //! - Artifact metadata management
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
+use raibid_common::artifacts::Artifact;
+pub use raibid_common::jobs::StepResult;
+use raibid_common::jobs::{JobLogEntry, LogLevel, LogStream};
+use raibid_common::logs::parse_log_entry;
+use raibid_common::pipeline::{PipelineDefinition, PipelineStep};
+use raibid_common::queue::{log_stream_key, LOG_ENTRY_FIELD};
+use redis::aio::MultiplexedConnection;
+use redis::streams::StreamRangeReply;
+use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
-use tokio::time::timeout;
+use tokio::sync::OnceCell;
+use tokio::time::{sleep_until, Instant};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
-/// Maximum timeout for individual build steps (5 minutes)
-const STEP_TIMEOUT: Duration = Duration::from_secs(5 * 60);
-
-/// Maximum timeout for entire pipeline (30 minutes)
-const PIPELINE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+use crate::cancel::{isolate_process_group, kill_process_group};
 
 /// Pipeline execution configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,21 +100,64 @@ impl BuildStep {
             BuildStep::DockerPush => "Pushing Docker image",
         }
     }
+
+    /// Get the shell command run by this step
+    pub fn command(&self, image_tag: Option<&str>) -> String {
+        match self {
+            BuildStep::Check => "cargo check --all-features".to_string(),
+            BuildStep::Clippy => "cargo clippy --all-features -- -D warnings".to_string(),
+            BuildStep::Format => "cargo fmt -- --check".to_string(),
+            BuildStep::Test => "cargo test --all-features".to_string(),
+            BuildStep::Build => "cargo build --release".to_string(),
+            BuildStep::Audit => "cargo audit".to_string(),
+            BuildStep::DockerBuild => match image_tag {
+                Some(tag) => format!("docker build -t {} .", tag),
+                None => "docker build .".to_string(),
+            },
+            BuildStep::DockerPush => match image_tag {
+                Some(tag) => format!("docker push {}", tag),
+                None => "docker push".to_string(),
+            },
+        }
+    }
+
+    /// Get the pipeline step definition for this step
+    pub fn to_step(&self, image_tag: Option<&str>) -> PipelineStep {
+        PipelineStep::new(self.name(), vec![self.command(image_tag)])
+    }
 }
 
-/// Result of a build step
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StepResult {
-    /// Step name
-    pub step: String,
-    /// Whether the step succeeded
-    pub success: bool,
-    /// Exit code
-    pub exit_code: Option<i32>,
-    /// Duration in seconds
-    pub duration_secs: u64,
-    /// Captured stdout/stderr (first 10KB)
-    pub output: String,
+/// Get the default Rust pipeline, used when a repository has no `.raibid.yml`
+pub fn default_pipeline() -> PipelineDefinition {
+    let steps = [
+        BuildStep::Check,
+        BuildStep::Format,
+        BuildStep::Clippy,
+        BuildStep::Test,
+        BuildStep::Build,
+        BuildStep::Audit,
+    ];
+
+    PipelineDefinition {
+        env: Default::default(),
+        timeout_seconds: raibid_common::pipeline::DEFAULT_PIPELINE_TIMEOUT_SECS,
+        steps: steps.iter().map(|step| step.to_step(None)).collect(),
+        artifacts: Vec::new(),
+    }
+}
+
+/// Load the pipeline declared by a repository, falling back to [`default_pipeline`]
+pub fn load_pipeline(repo_path: &Path) -> Result<PipelineDefinition> {
+    match PipelineDefinition::load_from_repo(repo_path)? {
+        Some(definition) => {
+            info!(path = ?repo_path, "Using pipeline from .raibid.yml");
+            Ok(definition)
+        }
+        None => {
+            debug!(path = ?repo_path, "No .raibid.yml found, using default Rust pipeline");
+            Ok(default_pipeline())
+        }
+    }
 }
 
 /// Complete pipeline execution result
@@ -122,6 +173,31 @@ pub struct PipelineResult {
     pub total_duration_secs: u64,
     /// Artifact metadata (if build succeeded)
     pub artifacts: Option<ArtifactMetadata>,
+    /// Files uploaded to the object store as the job's artifacts
+    #[serde(default)]
+    pub uploads: Vec<Artifact>,
+    /// Whether the pipeline was cancelled before completing
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+impl PipelineResult {
+    /// Get the exit code that represents this result
+    ///
+    /// A failed pipeline reports the exit code of the step that stopped it.
+    pub fn exit_code(&self) -> i32 {
+        if self.success {
+            return 0;
+        }
+
+        self.steps
+            .iter()
+            .rev()
+            .find(|step| !step.success)
+            .and_then(|step| step.exit_code)
+            .filter(|code| *code != 0)
+            .unwrap_or(1)
+    }
 }
 
 /// Metadata about build artifacts
@@ -135,10 +211,21 @@ pub struct ArtifactMetadata {
     pub built_at: String,
 }
 
+/// Error returned when a step is killed for running past its deadline
+#[derive(Debug, thiserror::Error)]
+#[error("Step deadline exceeded")]
+struct DeadlineExceeded;
+
 /// Pipeline executor
 pub struct PipelineExecutor {
     config: PipelineConfig,
+    definition: PipelineDefinition,
     redis_client: Option<redis::Client>,
+    /// Connection shared by every Redis write, opened on first use
+    redis_conn: OnceCell<MultiplexedConnection>,
+    cancel: CancellationToken,
+    /// Number of the last line written to the job's log
+    log_line: AtomicU64,
 }
 
 impl PipelineExecutor {
@@ -152,65 +239,111 @@ impl PipelineExecutor {
 
         Ok(Self {
             config,
+            definition: default_pipeline(),
             redis_client,
+            redis_conn: OnceCell::new(),
+            cancel: CancellationToken::new(),
+            log_line: AtomicU64::new(0),
         })
     }
 
+    /// Run the steps of `definition` instead of the default Rust pipeline
+    pub fn with_definition(mut self, definition: PipelineDefinition) -> Self {
+        self.definition = definition;
+        self
+    }
+
+    /// Stop the pipeline and kill the running step when `cancel` fires
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
     /// Execute the complete build pipeline
     pub async fn execute(&self) -> Result<PipelineResult> {
         info!(
             job_id = %self.config.job_id,
-            "Starting Rust build pipeline"
+            steps = self.definition.steps.len(),
+            "Starting build pipeline"
         );
 
+        // A retried job carries on numbering the lines of the previous attempt
+        self.log_line
+            .store(self.log_length().await.unwrap_or(0), Ordering::SeqCst);
+
         let start_time = std::time::Instant::now();
         let mut step_results = Vec::new();
         let mut overall_success = true;
-
-        // Define the build pipeline steps
-        let steps = vec![
-            BuildStep::Check,
-            BuildStep::Format,
-            BuildStep::Clippy,
-            BuildStep::Test,
-            BuildStep::Build,
-            BuildStep::Audit,
-        ];
+        let mut cancelled = false;
+        let pipeline_deadline = Instant::now() + self.definition.timeout();
 
         // Execute each step with timeout
-        for step in steps {
-            match timeout(
-                PIPELINE_TIMEOUT - start_time.elapsed(),
-                self.execute_step(step),
-            )
-            .await
-            {
-                Ok(Ok(result)) => {
-                    if !result.success {
+        for step in &self.definition.steps {
+            if self.cancel.is_cancelled() {
+                cancelled = true;
+                overall_success = false;
+                break;
+            }
+
+            match self.run_step(step, Some(pipeline_deadline)).await {
+                Ok(result) => {
+                    let failed = !result.success;
+                    step_results.push(result);
+
+                    if Instant::now() >= pipeline_deadline {
+                        error!(
+                            job_id = %self.config.job_id,
+                            "Pipeline timeout exceeded"
+                        );
                         overall_success = false;
+                        step_results.push(StepResult {
+                            step: "timeout".to_string(),
+                            success: false,
+                            exit_code: None,
+                            duration_secs: start_time.elapsed().as_secs(),
+                            output: "Pipeline timeout exceeded".to_string(),
+                        });
+                        break;
+                    }
+
+                    if self.cancel.is_cancelled() {
+                        warn!(
+                            job_id = %self.config.job_id,
+                            step = %step.name,
+                            "Pipeline cancelled"
+                        );
+                        cancelled = true;
+                        overall_success = false;
+                        break;
                     }
-                    step_results.push(result);
 
-                    // Stop pipeline on failure
-                    if !overall_success {
+                    if failed && step.allow_failure {
+                        warn!(
+                            job_id = %self.config.job_id,
+                            step = %step.name,
+                            "Step failed but is allowed to fail"
+                        );
+                    } else if failed {
+                        // Stop pipeline on failure
                         warn!(
                             job_id = %self.config.job_id,
-                            step = step.name(),
+                            step = %step.name,
                             "Pipeline stopped due to step failure"
                         );
+                        overall_success = false;
                         break;
                     }
                 }
-                Ok(Err(e)) => {
+                Err(e) => {
                     error!(
                         job_id = %self.config.job_id,
-                        step = step.name(),
+                        step = %step.name,
                         error = %e,
                         "Step execution failed"
                     );
                     overall_success = false;
                     step_results.push(StepResult {
-                        step: step.name().to_string(),
+                        step: step.name.clone(),
                         success: false,
                         exit_code: None,
                         duration_secs: 0,
@@ -218,44 +351,50 @@ impl PipelineExecutor {
                     });
                     break;
                 }
-                Err(_) => {
-                    error!(
-                        job_id = %self.config.job_id,
-                        "Pipeline timeout exceeded"
-                    );
-                    overall_success = false;
-                    step_results.push(StepResult {
-                        step: "timeout".to_string(),
-                        success: false,
-                        exit_code: None,
-                        duration_secs: start_time.elapsed().as_secs(),
-                        output: "Pipeline timeout exceeded".to_string(),
-                    });
-                    break;
-                }
             }
         }
 
-        // Build and push Docker image if build succeeded
+        // Build and push Docker image if build succeeded; the job fails if
+        // either step does, since a release without its image isn't published
         let mut artifacts = None;
         if overall_success && self.config.registry_url.is_some() {
-            if let Ok(result) = self.execute_step(BuildStep::DockerBuild).await {
-                step_results.push(result.clone());
-                if result.success {
-                    if let Ok(push_result) = self.execute_step(BuildStep::DockerPush).await {
-                        step_results.push(push_result.clone());
-                        if push_result.success {
-                            artifacts = Some(ArtifactMetadata {
-                                image: self.config.image_tag.clone(),
-                                binaries: self.find_binaries().await,
-                                built_at: Utc::now().to_rfc3339(),
-                            });
+            for step in [BuildStep::DockerBuild, BuildStep::DockerPush] {
+                match self.execute_step(step).await {
+                    Ok(result) => {
+                        let success = result.success;
+                        step_results.push(result);
+                        if !success {
+                            overall_success = false;
+                            break;
                         }
                     }
-                } else {
-                    overall_success = false;
+                    Err(e) => {
+                        error!(
+                            job_id = %self.config.job_id,
+                            step = %step.name(),
+                            error = %e,
+                            "Step execution failed"
+                        );
+                        overall_success = false;
+                        step_results.push(StepResult {
+                            step: step.name().to_string(),
+                            success: false,
+                            exit_code: None,
+                            duration_secs: 0,
+                            output: format!("Error: {}", e),
+                        });
+                        break;
+                    }
                 }
             }
+
+            if overall_success {
+                artifacts = Some(ArtifactMetadata {
+                    image: self.config.image_tag.clone(),
+                    binaries: self.find_binaries().await,
+                    built_at: Utc::now().to_rfc3339(),
+                });
+            }
         }
 
         let total_duration_secs = start_time.elapsed().as_secs();
@@ -266,6 +405,8 @@ impl PipelineExecutor {
             steps: step_results,
             total_duration_secs,
             artifacts,
+            uploads: Vec::new(),
+            cancelled,
         };
 
         info!(
@@ -278,170 +419,203 @@ impl PipelineExecutor {
         Ok(result)
     }
 
-    /// Execute a single build step
+    /// Execute a single built-in build step
     async fn execute_step(&self, step: BuildStep) -> Result<StepResult> {
+        self.run_step(&step.to_step(self.config.image_tag.as_deref()), None)
+            .await
+    }
+
+    /// Execute a single pipeline step
+    ///
+    /// The step is killed once it exceeds its own timeout or reaches
+    /// `pipeline_deadline`, whichever comes first.
+    async fn run_step(
+        &self,
+        step: &PipelineStep,
+        pipeline_deadline: Option<Instant>,
+    ) -> Result<StepResult> {
         info!(
             job_id = %self.config.job_id,
-            step = step.name(),
-            "Executing build step: {}",
-            step.description()
+            step = %step.name,
+            "Executing build step"
         );
 
         let start_time = std::time::Instant::now();
 
         // Send step start log to Redis
-        self.log_to_redis(&format!(">>> Starting step: {}", step.description()))
-            .await?;
-
-        // Build the command
-        let mut cmd = self.build_command(step)?;
+        self.log_to_redis(
+            &step.name,
+            LogStream::System,
+            LogLevel::Info,
+            &format!(">>> Starting step: {}", step.name),
+        )
+        .await?;
 
         // Execute with timeout and capture output
-        let result = timeout(STEP_TIMEOUT, self.run_command(&mut cmd, step)).await;
+        let step_deadline = Instant::now() + step.timeout();
+        let deadline = pipeline_deadline.map_or(step_deadline, |pipeline_deadline| {
+            pipeline_deadline.min(step_deadline)
+        });
+        let result = self.run_commands(step, deadline).await;
 
         let duration_secs = start_time.elapsed().as_secs();
 
         match result {
-            Ok(Ok((exit_code, output))) => {
+            Ok((exit_code, output)) => {
                 let success = exit_code == 0;
 
                 // Send step completion log to Redis
                 let status = if success { "SUCCESS" } else { "FAILED" };
-                self.log_to_redis(&format!(
-                    "<<< Step {} {} (exit code: {}, duration: {}s)",
-                    step.name(),
-                    status,
-                    exit_code,
-                    duration_secs
-                ))
+                let level = match (success, step.allow_failure) {
+                    (true, _) => LogLevel::Info,
+                    (false, true) => LogLevel::Warn,
+                    (false, false) => LogLevel::Error,
+                };
+                self.log_to_redis(
+                    &step.name,
+                    LogStream::System,
+                    level,
+                    &format!(
+                        "<<< Step {} {} (exit code: {}, duration: {}s)",
+                        step.name, status, exit_code, duration_secs
+                    ),
+                )
                 .await?;
 
                 Ok(StepResult {
-                    step: step.name().to_string(),
+                    step: step.name.clone(),
                     success,
                     exit_code: Some(exit_code),
                     duration_secs,
                     output,
                 })
             }
-            Ok(Err(e)) => {
-                error!(
+            Err(e) if e.is::<DeadlineExceeded>() => {
+                warn!(
                     job_id = %self.config.job_id,
-                    step = step.name(),
-                    error = %e,
-                    "Step failed with error"
+                    step = %step.name,
+                    "Step timeout exceeded"
                 );
 
-                self.log_to_redis(&format!("<<< Step {} FAILED: {}", step.name(), e))
-                    .await?;
+                self.log_to_redis(
+                    &step.name,
+                    LogStream::System,
+                    LogLevel::Error,
+                    &format!("<<< Step {} TIMEOUT after {}s", step.name, duration_secs),
+                )
+                .await?;
 
+                let output = if deadline < step_deadline {
+                    "Pipeline timeout exceeded".to_string()
+                } else {
+                    format!("Step timeout exceeded ({} seconds)", step.timeout_seconds)
+                };
                 Ok(StepResult {
-                    step: step.name().to_string(),
+                    step: step.name.clone(),
                     success: false,
                     exit_code: None,
                     duration_secs,
-                    output: format!("Error: {}", e),
+                    output,
                 })
             }
-            Err(_) => {
-                warn!(
+            Err(e) => {
+                error!(
                     job_id = %self.config.job_id,
-                    step = step.name(),
-                    "Step timeout exceeded"
+                    step = %step.name,
+                    error = %e,
+                    "Step failed with error"
                 );
 
-                self.log_to_redis(&format!(
-                    "<<< Step {} TIMEOUT after {}s",
-                    step.name(),
-                    duration_secs
-                ))
+                self.log_to_redis(
+                    &step.name,
+                    LogStream::System,
+                    LogLevel::Error,
+                    &format!("<<< Step {} FAILED: {}", step.name, e),
+                )
                 .await?;
 
                 Ok(StepResult {
-                    step: step.name().to_string(),
+                    step: step.name.clone(),
                     success: false,
                     exit_code: None,
                     duration_secs,
-                    output: format!("Step timeout exceeded ({} seconds)", STEP_TIMEOUT.as_secs()),
+                    output: format!("Error: {}", e),
                 })
             }
         }
     }
 
-    /// Build the command for a specific step
-    fn build_command(&self, step: BuildStep) -> Result<Command> {
-        let mut cmd = match step {
-            BuildStep::Check => {
-                let mut c = Command::new("cargo");
-                c.args(["check", "--all-features"]);
-                c
-            }
-            BuildStep::Clippy => {
-                let mut c = Command::new("cargo");
-                c.args(["clippy", "--all-features", "--", "-D", "warnings"]);
-                c
-            }
-            BuildStep::Format => {
-                let mut c = Command::new("cargo");
-                c.args(["fmt", "--", "--check"]);
-                c
-            }
-            BuildStep::Test => {
-                let mut c = Command::new("cargo");
-                c.args(["test", "--all-features"]);
-                c
-            }
-            BuildStep::Build => {
-                let mut c = Command::new("cargo");
-                c.args(["build", "--release"]);
-                c
-            }
-            BuildStep::Audit => {
-                let mut c = Command::new("cargo");
-                c.args(["audit"]);
-                c
-            }
-            BuildStep::DockerBuild => {
-                let mut c = Command::new("docker");
-                c.arg("build");
-                if let Some(ref tag) = self.config.image_tag {
-                    c.args(["-t", tag]);
-                }
-                c.arg(".");
-                c
-            }
-            BuildStep::DockerPush => {
-                let mut c = Command::new("docker");
-                c.arg("push");
-                if let Some(ref tag) = self.config.image_tag {
-                    c.arg(tag);
-                }
-                c
+    /// Run the commands of a step in order, stopping at the first failure
+    async fn run_commands(&self, step: &PipelineStep, deadline: Instant) -> Result<(i32, String)> {
+        let mut output = String::new();
+
+        for command in &step.commands {
+            self.log_to_redis(
+                &step.name,
+                LogStream::System,
+                LogLevel::Info,
+                &format!("$ {}", command),
+            )
+            .await
+            .ok();
+
+            let mut cmd = self.build_command(step, command);
+            let exit_code = self
+                .run_command(&mut cmd, &step.name, &mut output, deadline)
+                .await?;
+            if exit_code != 0 {
+                return Ok((exit_code, output));
             }
-        };
+        }
+
+        Ok((0, output))
+    }
+
+    /// Build the shell command for one of a step's commands
+    fn build_command(&self, step: &PipelineStep, command: &str) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
 
         // Set working directory
         cmd.current_dir(&self.config.repo_path);
 
         // Set environment for sccache if enabled
-        if self.config.use_sccache
-            && matches!(step, BuildStep::Build | BuildStep::Check | BuildStep::Test)
-        {
+        if self.config.use_sccache {
             cmd.env("RUSTC_WRAPPER", "sccache");
         }
 
+        // Pipeline-level env first so step env can override it
+        cmd.envs(&self.definition.env);
+        cmd.envs(&step.env);
+
         // Ensure output is captured
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        Ok(cmd)
+        // Run in a separate process group so cancellation and timeouts can kill
+        // the whole tree
+        isolate_process_group(&mut cmd);
+
+        // Don't leave the shell running if the step's future is dropped
+        cmd.kill_on_drop(true);
+
+        cmd
     }
 
-    /// Run a command and capture output, streaming logs to Redis
-    async fn run_command(&self, cmd: &mut Command, step: BuildStep) -> Result<(i32, String)> {
+    /// Run a command, appending its output to `output_buffer` and streaming logs to Redis
+    ///
+    /// The command's process group is killed if it's still running at `deadline`,
+    /// returning a [`DeadlineExceeded`] error.
+    async fn run_command(
+        &self,
+        cmd: &mut Command,
+        step: &str,
+        output_buffer: &mut String,
+        deadline: Instant,
+    ) -> Result<i32> {
         let mut child = cmd
             .spawn()
-            .context(format!("Failed to spawn {} command", step.name()))?;
+            .context(format!("Failed to spawn {} command", step))?;
 
         let stdout = child.stdout.take().context("Failed to capture stdout")?;
         let stderr = child.stderr.take().context("Failed to capture stderr")?;
@@ -449,13 +623,28 @@ impl PipelineExecutor {
         let mut stdout_reader = BufReader::new(stdout).lines();
         let mut stderr_reader = BufReader::new(stderr).lines();
 
-        let mut output_buffer = String::new();
         const MAX_OUTPUT_SIZE: usize = 10 * 1024; // 10KB
 
-        // Stream output lines
-        loop {
+        let mut stdout_open = true;
+        let mut stderr_open = true;
+
+        let expired = sleep_until(deadline);
+        tokio::pin!(expired);
+
+        // Stream output lines until both pipes close
+        while stdout_open || stderr_open {
             tokio::select! {
-                line = stdout_reader.next_line() => {
+                _ = self.cancel.cancelled() => {
+                    warn!(step = step, "Killing step due to cancellation");
+                    kill_process_group(&mut child).await;
+                    return Err(anyhow!("Step {} cancelled", step));
+                }
+                _ = &mut expired => {
+                    warn!(step = step, "Killing step due to timeout");
+                    kill_process_group(&mut child).await;
+                    return Err(DeadlineExceeded.into());
+                }
+                line = stdout_reader.next_line(), if stdout_open => {
                     match line {
                         Ok(Some(line)) => {
                             // Add to buffer (truncate if too large)
@@ -464,16 +653,18 @@ impl PipelineExecutor {
                                 output_buffer.push('\n');
                             }
                             // Stream to Redis
-                            self.log_to_redis(&line).await.ok();
-                            debug!(step = step.name(), "stdout: {}", line);
+                            self.log_to_redis(step, LogStream::Stdout, LogLevel::Info, &line)
+                                .await
+                                .ok();
+                            debug!(step = step, "stdout: {}", line);
                         }
-                        Ok(None) => {}
+                        Ok(None) => stdout_open = false,
                         Err(e) => {
-                            warn!(step = step.name(), error = %e, "Error reading stdout");
+                            warn!(step = step, error = %e, "Error reading stdout");
                         }
                     }
                 }
-                line = stderr_reader.next_line() => {
+                line = stderr_reader.next_line(), if stderr_open => {
                     match line {
                         Ok(Some(line)) => {
                             // Add to buffer (truncate if too large)
@@ -482,47 +673,74 @@ impl PipelineExecutor {
                                 output_buffer.push('\n');
                             }
                             // Stream to Redis
-                            self.log_to_redis(&line).await.ok();
-                            debug!(step = step.name(), "stderr: {}", line);
+                            self.log_to_redis(step, LogStream::Stderr, LogLevel::Info, &line)
+                                .await
+                                .ok();
+                            debug!(step = step, "stderr: {}", line);
                         }
-                        Ok(None) => {}
+                        Ok(None) => stderr_open = false,
                         Err(e) => {
-                            warn!(step = step.name(), error = %e, "Error reading stderr");
+                            warn!(step = step, error = %e, "Error reading stderr");
                         }
                     }
                 }
-                else => break,
             }
         }
 
         // Wait for process to exit
-        let status = child
-            .wait()
-            .await
-            .context("Failed to wait for child process")?;
-        let exit_code = status.code().unwrap_or(-1);
+        let status = tokio::select! {
+            status = child.wait() => status.context("Failed to wait for child process")?,
+            _ = self.cancel.cancelled() => {
+                kill_process_group(&mut child).await;
+                return Err(anyhow!("Step {} cancelled", step));
+            }
+            _ = &mut expired => {
+                kill_process_group(&mut child).await;
+                return Err(DeadlineExceeded.into());
+            }
+        };
 
-        Ok((exit_code, output_buffer))
+        Ok(status.code().unwrap_or(-1))
     }
 
-    /// Stream a log line to Redis
-    async fn log_to_redis(&self, message: &str) -> Result<()> {
-        if let Some(ref client) = self.redis_client {
-            let mut conn = client
-                .get_multiplexed_async_connection()
-                .await
-                .context("Failed to get Redis connection")?;
+    /// Get a handle to the Redis connection, connecting on first use
+    ///
+    /// Returns `None` when no Redis URL is configured.
+    async fn redis_connection(&self) -> Result<Option<MultiplexedConnection>> {
+        let Some(ref client) = self.redis_client else {
+            return Ok(None);
+        };
 
-            let stream_key = format!("raibid:logs:{}", self.config.job_id);
-            let timestamp = Utc::now().to_rfc3339();
+        let conn = self
+            .redis_conn
+            .get_or_try_init(|| client.get_multiplexed_async_connection())
+            .await
+            .context("Failed to get Redis connection")?;
+        Ok(Some(conn.clone()))
+    }
+
+    /// Stream a log line of a step to Redis
+    ///
+    /// The stream is never trimmed while the job runs, so its archive gets
+    /// every line; the consumer trims it once archived.
+    async fn log_to_redis(
+        &self,
+        step: &str,
+        stream: LogStream,
+        level: LogLevel,
+        message: &str,
+    ) -> Result<()> {
+        if let Some(mut conn) = self.redis_connection().await? {
+            let stream_key = log_stream_key(&self.config.job_id);
+            let line = self.log_line.fetch_add(1, Ordering::SeqCst) + 1;
+            let entry = JobLogEntry::new(line, Some(step), stream, level, message);
+            let entry = serde_json::to_string(&entry).context("Failed to serialize log entry")?;
 
             redis::cmd("XADD")
                 .arg(&stream_key)
                 .arg("*") // Auto-generate ID
-                .arg("timestamp")
-                .arg(&timestamp)
-                .arg("message")
-                .arg(message)
+                .arg(LOG_ENTRY_FIELD)
+                .arg(&entry)
                 .query_async::<()>(&mut conn)
                 .await
                 .context("Failed to write log to Redis")?;
@@ -531,6 +749,31 @@ impl PipelineExecutor {
         Ok(())
     }
 
+    /// Get the number of lines already in the job's log
+    ///
+    /// Trimming drops the oldest lines, so this is the number of the last line
+    /// rather than the stream's length.
+    async fn log_length(&self) -> Result<u64> {
+        let Some(mut conn) = self.redis_connection().await? else {
+            return Ok(0);
+        };
+
+        let stream_key = log_stream_key(&self.config.job_id);
+        let reply: StreamRangeReply = conn
+            .xrevrange_count(&stream_key, "+", "-", 1)
+            .await
+            .context("Failed to read log from Redis")?;
+
+        match reply.ids.first().and_then(parse_log_entry) {
+            Some(entry) if entry.line > 0 => Ok(entry.line),
+            // Lines written by older agents aren't numbered
+            _ => conn
+                .xlen(&stream_key)
+                .await
+                .context("Failed to read log length from Redis"),
+        }
+    }
+
     /// Find built binaries in target/release
     async fn find_binaries(&self) -> Vec<String> {
         let release_dir = self.config.repo_path.join("target/release");
@@ -572,12 +815,7 @@ impl PipelineExecutor {
 
     /// Update job status in Redis
     pub async fn update_job_status(&self, status: &str, exit_code: Option<i32>) -> Result<()> {
-        if let Some(ref client) = self.redis_client {
-            let mut conn = client
-                .get_multiplexed_async_connection()
-                .await
-                .context("Failed to get Redis connection")?;
-
+        if let Some(mut conn) = self.redis_connection().await? {
             let job_key = format!("raibid:job:{}", self.config.job_id);
             let timestamp = Utc::now().to_rfc3339();
 
@@ -614,12 +852,7 @@ impl PipelineExecutor {
 
     /// Store artifact metadata in Redis
     pub async fn store_artifacts(&self, artifacts: &ArtifactMetadata) -> Result<()> {
-        if let Some(ref client) = self.redis_client {
-            let mut conn = client
-                .get_multiplexed_async_connection()
-                .await
-                .context("Failed to get Redis connection")?;
-
+        if let Some(mut conn) = self.redis_connection().await? {
             let artifacts_key = format!("raibid:artifacts:{}", self.config.job_id);
             let artifacts_json = serde_json::to_string(artifacts)?;
 
@@ -791,6 +1024,8 @@ mod tests {
             }],
             total_duration_secs: 10,
             artifacts: None,
+            uploads: Vec::new(),
+            cancelled: false,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -801,6 +1036,295 @@ mod tests {
         assert!(deserialized.success);
     }
 
+    #[tokio::test]
+    async fn test_pipeline_cancelled_before_start() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let config = PipelineConfig {
+            job_id: "test-cancel".to_string(),
+            repo_path: temp_dir.path().to_path_buf(),
+            use_sccache: false,
+            registry_url: None,
+            image_tag: None,
+            redis_url: None,
+        };
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let executor = PipelineExecutor::new(config)
+            .unwrap()
+            .with_cancellation(cancel);
+        let result = executor.execute().await.unwrap();
+
+        assert!(result.cancelled);
+        assert!(!result.success);
+        assert!(result.steps.is_empty());
+    }
+
+    fn shell_definition(steps: Vec<PipelineStep>) -> PipelineDefinition {
+        PipelineDefinition {
+            env: [("GREETING".to_string(), "hello".to_string())].into(),
+            timeout_seconds: 60,
+            steps,
+            artifacts: Vec::new(),
+        }
+    }
+
+    fn shell_executor(
+        job_id: &str,
+        dir: &Path,
+        definition: PipelineDefinition,
+    ) -> PipelineExecutor {
+        let config = PipelineConfig {
+            job_id: job_id.to_string(),
+            repo_path: dir.to_path_buf(),
+            use_sccache: false,
+            registry_url: None,
+            image_tag: None,
+            redis_url: None,
+        };
+
+        PipelineExecutor::new(config)
+            .unwrap()
+            .with_definition(definition)
+    }
+
+    #[test]
+    fn test_default_pipeline() {
+        let definition = default_pipeline();
+        let names: Vec<&str> = definition.steps.iter().map(|s| s.name.as_str()).collect();
+
+        assert_eq!(
+            names,
+            vec!["check", "format", "clippy", "test", "build", "audit"]
+        );
+        assert_eq!(
+            definition.steps[0].commands,
+            vec!["cargo check --all-features"]
+        );
+        assert!(definition.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_definition_steps_with_allowed_failure() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let mut lint = PipelineStep::new("lint", vec!["exit 2".to_string()]);
+        lint.allow_failure = true;
+        let mut greet = PipelineStep::new("greet", vec!["echo $GREETING $TARGET".to_string()]);
+        greet.env.insert("TARGET".to_string(), "world".to_string());
+
+        let executor = shell_executor(
+            "test-allow-failure",
+            temp_dir.path(),
+            shell_definition(vec![lint, greet]),
+        );
+        let result = executor.execute().await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.steps.len(), 2);
+        assert!(!result.steps[0].success);
+        assert_eq!(result.steps[0].exit_code, Some(2));
+        assert!(result.steps[1].output.contains("hello world"));
+    }
+
+    #[tokio::test]
+    async fn test_failing_step_stops_pipeline() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let executor = shell_executor(
+            "test-failure",
+            temp_dir.path(),
+            shell_definition(vec![
+                PipelineStep::new("build", vec!["true".to_string(), "exit 3".to_string()]),
+                PipelineStep::new("test", vec!["true".to_string()]),
+            ]),
+        );
+        let result = executor.execute().await.unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.steps.len(), 1);
+        assert_eq!(result.steps[0].exit_code, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_failed_image_push_fails_pipeline() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Stand-in docker whose builds succeed and pushes fail
+        let bin = temp_dir.path().join("bin");
+        std::fs::create_dir(&bin).unwrap();
+        let docker = bin.join("docker");
+        std::fs::write(&docker, "#!/bin/sh\n[ \"$1\" = build ]\n").unwrap();
+        std::fs::set_permissions(&docker, std::os::unix::fs::PermissionsExt::from_mode(0o755))
+            .unwrap();
+
+        let mut definition =
+            shell_definition(vec![PipelineStep::new("build", vec!["true".to_string()])]);
+        definition.env.insert(
+            "PATH".to_string(),
+            format!("{}:{}", bin.display(), std::env::var("PATH").unwrap()),
+        );
+        let config = PipelineConfig {
+            job_id: "test-push-failure".to_string(),
+            repo_path: temp_dir.path().to_path_buf(),
+            use_sccache: false,
+            registry_url: Some("registry.example.com".to_string()),
+            image_tag: Some("registry.example.com/owner/repo:v1.0.0".to_string()),
+            redis_url: None,
+        };
+        let executor = PipelineExecutor::new(config)
+            .unwrap()
+            .with_definition(definition);
+        let result = executor.execute().await.unwrap();
+
+        assert!(!result.success);
+        let names: Vec<&str> = result.steps.iter().map(|s| s.step.as_str()).collect();
+        assert_eq!(names, vec!["build", "docker-build", "docker-push"]);
+        assert!(result.steps[1].success);
+        assert!(!result.steps[2].success);
+        assert!(result.artifacts.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_step_timeout() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // The backgrounded sleep is a grandchild of the step's shell
+        let mut slow = PipelineStep::new(
+            "slow",
+            vec!["sleep 30 & echo $! > grandchild.pid; wait".to_string()],
+        );
+        slow.timeout_seconds = 1;
+
+        let executor = shell_executor(
+            "test-timeout",
+            temp_dir.path(),
+            shell_definition(vec![slow]),
+        );
+        let started = std::time::Instant::now();
+        let result = executor.execute().await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.steps[0].output.contains("timeout"));
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+        let pid: u32 = std::fs::read_to_string(temp_dir.path().join("grandchild.pid"))
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        assert!(process_exited(pid).await);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_timeout() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let mut definition = shell_definition(vec![
+            PipelineStep::new("slow", vec!["sleep 30".to_string()]),
+            PipelineStep::new("after", vec!["true".to_string()]),
+        ]);
+        definition.timeout_seconds = 1;
+
+        let executor = shell_executor("test-pipeline-timeout", temp_dir.path(), definition);
+        let started = std::time::Instant::now();
+        let result = executor.execute().await.unwrap();
+
+        assert!(!result.success);
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        assert_eq!(result.steps[0].output, "Pipeline timeout exceeded");
+        assert_eq!(result.steps[1].step, "timeout");
+        assert_eq!(result.steps.len(), 2);
+    }
+
+    /// Wait up to a second for a process to exit (zombies count as exited)
+    async fn process_exited(pid: u32) -> bool {
+        for _ in 0..20 {
+            match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+                Err(_) => return true,
+                // The state follows the parenthesised command name
+                Ok(stat)
+                    if stat
+                        .rsplit(')')
+                        .next()
+                        .unwrap_or("")
+                        .trim_start()
+                        .starts_with('Z') =>
+                {
+                    return true
+                }
+                Ok(_) => tokio::time::sleep(std::time::Duration::from_millis(50)).await,
+            }
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_cancelled_during_step() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            trigger.cancel();
+        });
+
+        let executor = shell_executor(
+            "test-cancel-step",
+            temp_dir.path(),
+            shell_definition(vec![
+                PipelineStep::new("slow", vec!["sleep 30".to_string()]),
+                PipelineStep::new("after", vec!["true".to_string()]),
+            ]),
+        )
+        .with_cancellation(cancel);
+
+        let started = std::time::Instant::now();
+        let result = executor.execute().await.unwrap();
+
+        assert!(result.cancelled);
+        assert!(!result.success);
+        assert_eq!(result.steps.len(), 1);
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_pipeline_exit_code() {
+        let step = |name: &str, success: bool, exit_code: Option<i32>| StepResult {
+            step: name.to_string(),
+            success,
+            exit_code,
+            duration_secs: 0,
+            output: String::new(),
+        };
+        let result = |success: bool, steps: Vec<StepResult>| PipelineResult {
+            job_id: "job-1".to_string(),
+            success,
+            steps,
+            total_duration_secs: 0,
+            artifacts: None,
+            uploads: Vec::new(),
+            cancelled: false,
+        };
+
+        assert_eq!(result(true, vec![]).exit_code(), 0);
+        assert_eq!(
+            result(
+                false,
+                vec![step("lint", false, Some(2)), step("test", false, Some(101))]
+            )
+            .exit_code(),
+            101
+        );
+        assert_eq!(
+            result(false, vec![step("test", false, None)]).exit_code(),
+            1
+        );
+    }
+
     #[tokio::test]
     async fn test_artifact_metadata() {
         let metadata = ArtifactMetadata {
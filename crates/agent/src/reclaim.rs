@@ -0,0 +1,149 @@
+//! Pending entry recovery
+//!
+//! A stream entry stays in the consumer group's pending entries list (PEL) until
+//! an agent acknowledges it. If an agent dies mid-build its entries would stay
+//! pending forever, so agents periodically XAUTOCLAIM entries that have been idle
+//! longer than a threshold and re-run them. While a job runs, its agent keeps the
+//! entry's idle time low by re-claiming it for itself, so live builds aren't stolen.
+//!
+//! Jobs that keep failing to complete are moved to a dead-letter stream once they
+//! have been attempted more than `max_retries` times.
+
+use raibid_common::queue::ENVELOPE_FIELD;
+use redis::aio::MultiplexedConnection;
+use redis::streams::{StreamAutoClaimOptions, StreamAutoClaimReply, StreamClaimOptions, StreamId};
+use redis::AsyncCommands;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::config::AgentConfig;
+
+/// Claim entries that other consumers have left idle for too long
+///
+/// Scans the group's PEL from the beginning and returns at most `count` entries,
+/// now owned by this agent.
+pub async fn claim_stale_entries(
+    conn: &mut MultiplexedConnection,
+    config: &AgentConfig,
+    count: usize,
+) -> redis::RedisResult<Vec<StreamId>> {
+    let opts = StreamAutoClaimOptions::default().count(count);
+    let reply: StreamAutoClaimReply = conn
+        .xautoclaim_options(
+            &config.redis.queue_stream,
+            &config.redis.consumer_group,
+            &config.agent_id,
+            config.reclaim_idle_ms,
+            "0-0",
+            opts,
+        )
+        .await?;
+
+    if !reply.deleted_ids.is_empty() {
+        debug!(
+            "Dropped {} pending entries no longer in the stream",
+            reply.deleted_ids.len()
+        );
+    }
+
+    Ok(reply.claimed)
+}
+
+/// Keep a pending entry owned by this agent while its job runs
+///
+/// Re-claiming an entry resets its idle time without bumping its delivery count,
+/// which stops other agents from reclaiming a job that is still making progress.
+/// Abort the returned task once the entry is acknowledged.
+pub fn spawn_claim_keepalive(
+    mut conn: MultiplexedConnection,
+    config: &AgentConfig,
+    message_id: String,
+) -> JoinHandle<()> {
+    let stream = config.redis.queue_stream.clone();
+    let group = config.redis.consumer_group.clone();
+    let consumer = config.agent_id.clone();
+    let interval = keepalive_interval(config.reclaim_idle_ms);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let opts = StreamClaimOptions::default().with_justid();
+            let result: redis::RedisResult<Vec<String>> = conn
+                .xclaim_options(&stream, &group, &consumer, 0, &[&message_id], opts)
+                .await;
+
+            if let Err(e) = result {
+                warn!("Failed to refresh claim on entry {}: {}", message_id, e);
+            }
+        }
+    })
+}
+
+/// Get how often a running job refreshes its claim
+///
+/// Several refreshes fit in one idle threshold so a single slow round trip
+/// doesn't make the entry look abandoned.
+pub fn keepalive_interval(reclaim_idle_ms: u64) -> Duration {
+    Duration::from_millis((reclaim_idle_ms / 3).max(1000))
+}
+
+/// Check whether a job has used up its retries
+///
+/// `attempts` counts every time an agent started the job, so a job gets one
+/// initial attempt plus `max_retries` retries.
+pub fn retries_exhausted(attempts: u32, max_retries: u32) -> bool {
+    attempts > max_retries
+}
+
+/// Move an entry to the dead-letter stream
+///
+/// The entry keeps its envelope and records why it was dead-lettered, so it can be
+/// inspected or re-queued by hand.
+pub async fn dead_letter(
+    conn: &mut MultiplexedConnection,
+    config: &AgentConfig,
+    message_id: &str,
+    envelope_json: &str,
+    attempts: u32,
+    reason: &str,
+) -> redis::RedisResult<()> {
+    let attempts = attempts.to_string();
+    let _: String = conn
+        .xadd(
+            &config.redis.dead_letter_stream,
+            "*",
+            &[
+                (ENVELOPE_FIELD, envelope_json),
+                ("source_id", message_id),
+                ("attempts", attempts.as_str()),
+                ("reason", reason),
+            ],
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retries_exhausted() {
+        // One initial attempt plus three retries
+        assert!(!retries_exhausted(0, 3));
+        assert!(!retries_exhausted(3, 3));
+        assert!(retries_exhausted(4, 3));
+
+        // No retries: a single crashed attempt is final
+        assert!(retries_exhausted(1, 0));
+    }
+
+    #[test]
+    fn test_keepalive_interval() {
+        assert_eq!(keepalive_interval(300_000), Duration::from_secs(100));
+        assert_eq!(keepalive_interval(600), Duration::from_secs(1));
+    }
+}
@@ -4,6 +4,12 @@
 //! Rust projects.
 
 use raibid_agent::{BuildStep, PipelineConfig, PipelineExecutor};
+use raibid_common::logs::{archive_log, parse_log_entry, read_archived_log, trim_log};
+use raibid_common::pipeline::{PipelineDefinition, PipelineStep};
+use raibid_common::queue::log_stream_key;
+use raibid_common::storage::LocalObjectStore;
+use redis::streams::StreamRangeReply;
+use redis::AsyncCommands;
 use std::path::PathBuf;
 use tempfile::TempDir;
 use tokio::fs;
@@ -284,6 +290,69 @@ async fn test_pipeline_with_redis_logging() {
     // Future implementation when Redis is available in test environment
 }
 
+#[tokio::test]
+#[ignore] // Requires Redis
+async fn test_log_archive_keeps_lines_beyond_limit() {
+    const MAX_LOG_LINES: usize = 100;
+    let temp_dir = TempDir::new().unwrap();
+    let storage = TempDir::new().unwrap();
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let job_id = format!("test-log-archive-{}", uuid::Uuid::new_v4());
+
+    let config = PipelineConfig {
+        job_id: job_id.clone(),
+        repo_path: temp_dir.path().to_path_buf(),
+        use_sccache: false,
+        registry_url: None,
+        image_tag: None,
+        redis_url: Some(redis_url.clone()),
+    };
+    let definition = PipelineDefinition {
+        env: Default::default(),
+        timeout_seconds: 60,
+        steps: vec![PipelineStep {
+            name: "print".to_string(),
+            commands: vec![format!("seq 1 {}", MAX_LOG_LINES * 10)],
+            env: Default::default(),
+            timeout_seconds: 60,
+            allow_failure: false,
+            branches: Vec::new(),
+        }],
+        artifacts: Vec::new(),
+    };
+    let result = PipelineExecutor::new(config)
+        .unwrap()
+        .with_definition(definition)
+        .execute()
+        .await
+        .unwrap();
+    assert!(result.success);
+
+    // Archive and trim the log the way the consumer does once the job finishes
+    let client = redis::Client::open(redis_url).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+    let store = LocalObjectStore::new(storage.path());
+    let lines = archive_log(&mut conn, &store, &job_id).await.unwrap();
+    trim_log(&mut conn, &job_id, MAX_LOG_LINES).await.unwrap();
+
+    let archived = read_archived_log(&store, &job_id).await.unwrap().unwrap();
+    assert!(lines > MAX_LOG_LINES * 10);
+    assert_eq!(archived.len(), lines);
+    assert_eq!(archived[0].line, 1);
+    assert!(archived.iter().any(|entry| entry.message == "1"));
+    assert!(archived.iter().any(|entry| entry.message == "1000"));
+
+    // Only the stream lost its oldest lines
+    let first: StreamRangeReply = conn
+        .xrange_count(log_stream_key(&job_id), "-", "+", 1)
+        .await
+        .unwrap();
+    assert!(parse_log_entry(&first.ids[0]).unwrap().line > 1);
+
+    let _: () = conn.del(log_stream_key(&job_id)).await.unwrap();
+}
+
 #[tokio::test]
 async fn test_build_step_enum() {
     assert_eq!(BuildStep::Check.name(), "check");
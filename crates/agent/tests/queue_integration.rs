@@ -0,0 +1,119 @@
+//! End-to-end tests for the job queue
+//!
+//! These tests post a webhook through the server routes and read the resulting
+//! stream entry back the way an agent does. They require a Redis instance on
+//! localhost:6379 (or `RAIBID_REDIS_URL`).
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use raibid_agent::JobConsumer;
+use raibid_common::jobs::JobStatus;
+use raibid_common::store::{JobStore, RedisJobStore};
+use raibid_server::{AppState, ServerConfig};
+use redis::streams::{StreamReadOptions, StreamReadReply};
+use redis::AsyncCommands;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn redis_url() -> String {
+    std::env::var("RAIBID_REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string())
+}
+
+#[tokio::test]
+#[ignore] // Requires Redis
+async fn test_webhook_job_reaches_agent_consumer() {
+    let suffix = uuid::Uuid::new_v4();
+    let config = ServerConfig {
+        redis_url: redis_url(),
+        job_stream: format!("raibid:test:jobs:{}", suffix),
+        consumer_group: format!("raibid-test-{}", suffix),
+        ..ServerConfig::default()
+    };
+
+    let client = redis::Client::open(config.redis_url.as_str()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+
+    // Create the group before producing so the entry is delivered to it
+    let _: () = redis::cmd("XGROUP")
+        .arg("CREATE")
+        .arg(&config.job_stream)
+        .arg(&config.consumer_group)
+        .arg("$")
+        .arg("MKSTREAM")
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+
+    let state = AppState::from_server_config(&config).unwrap();
+    let app = raibid_server::routes::webhooks::routes().with_state(Arc::new(state));
+
+    let payload = r#"{
+        "ref": "refs/heads/main",
+        "before": "abc123",
+        "after": "def456",
+        "repository": {
+            "id": 1,
+            "name": "test-repo",
+            "full_name": "owner/test-repo",
+            "owner": {
+                "id": 1,
+                "login": "owner",
+                "avatar_url": "https://example.com/avatar.png"
+            },
+            "html_url": "https://git.example.com/owner/test-repo",
+            "clone_url": "https://git.example.com/owner/test-repo.git",
+            "ssh_url": "git@git.example.com:owner/test-repo.git",
+            "default_branch": "main"
+        },
+        "pusher": {
+            "id": 1,
+            "username": "testuser",
+            "email": "test@example.com",
+            "avatar_url": "https://example.com/avatar.png"
+        }
+    }"#;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/webhooks/gitea")
+                .header("content-type", "application/json")
+                .body(Body::from(payload))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    // Read the entry the same way the agent consumer does
+    let opts = StreamReadOptions::default()
+        .group(&config.consumer_group, "test-agent")
+        .count(1);
+    let reply: StreamReadReply = conn
+        .xread_options(&[&config.job_stream], &[">"], &opts)
+        .await
+        .unwrap();
+
+    let entry = &reply.keys[0].ids[0];
+    let msg = JobConsumer::parse_job_message(&entry.id, &entry.map).unwrap();
+
+    assert_eq!(msg.event_type, "push");
+    assert_eq!(msg.job.repo, "owner/test-repo");
+    assert_eq!(msg.job.branch, "main");
+    assert_eq!(msg.job.commit, "def456");
+    assert_eq!(msg.job.status, JobStatus::Pending);
+
+    // The server records the job before queueing it
+    let store = RedisJobStore::from_url(&config.redis_url).unwrap();
+    let record = store.get(&msg.job.id).await.unwrap().unwrap();
+    assert_eq!(record.status, JobStatus::Pending);
+
+    let running = store.mark_running(&msg.job.id, "test-agent").await.unwrap();
+    assert_eq!(running.agent_id.as_deref(), Some("test-agent"));
+
+    store.delete(&msg.job.id).await.unwrap();
+    let _: () = conn.del(&config.job_stream).await.unwrap();
+}
@@ -4,11 +4,26 @@
 //! It handles HTTP requests, error handling, and response parsing.
 
 use anyhow::{Context, Result};
-use raibid_common::{Job, JobList, JobListQuery, JobLogs, JobTrigger};
+use raibid_common::{
+    AgentList, Job, JobArtifacts, JobList, JobListQuery, JobLogEntry, JobLogs, JobStatus, JobSteps,
+    JobTrigger, PruneReport, WebhookDelivery, WebhookDeliveryList,
+};
 use reqwest::blocking::Client;
+use reqwest::header::ACCEPT;
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::io::BufRead;
 use std::time::Duration;
 
+/// How long an artifact download may take
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Data of the event that ends a job's log stream
+#[derive(Debug, Deserialize)]
+struct LogEnd {
+    status: Option<JobStatus>,
+}
+
 /// API client for raibid-ci server
 #[derive(Debug, Clone)]
 pub struct ApiClient {
@@ -72,17 +87,181 @@ impl ApiClient {
         self.get(&url)
     }
 
-    /// Get logs for a specific job
-    pub fn get_job_logs(&self, job_id: &str, tail: Option<usize>) -> Result<JobLogs> {
+    /// Get step results for a specific job
+    pub fn get_job_steps(&self, job_id: &str) -> Result<JobSteps> {
+        let url = format!("{}/api/jobs/{}/steps", self.base_url, job_id);
+        self.get(&url)
+    }
+
+    /// Get logs for a specific job, optionally only those of one step
+    pub fn get_job_logs(
+        &self,
+        job_id: &str,
+        tail: Option<usize>,
+        step: Option<&str>,
+    ) -> Result<JobLogs> {
+        let url = self.job_logs_url(job_id, tail, step);
+
+        let response = self
+            .client
+            .get(&url)
+            .header(ACCEPT, "application/json")
+            .send()
+            .context("Failed to send GET request")?;
+
+        self.handle_response(response)
+    }
+
+    /// Follow the logs of a job as they're written, until the job finishes
+    ///
+    /// Each batch of lines is passed to `on_lines`. Dropped connections are
+    /// resumed after the last line received. Returns the job's final status.
+    pub fn follow_job_logs(
+        &self,
+        job_id: &str,
+        tail: Option<usize>,
+        step: Option<&str>,
+        mut on_lines: impl FnMut(&[JobLogEntry]),
+    ) -> Result<Option<JobStatus>> {
+        let url = self.job_logs_url(job_id, tail, step);
+        let mut last_event_id: Option<String> = None;
+
+        loop {
+            let mut request = self.client.get(&url).header(ACCEPT, "text/event-stream");
+            if let Some(id) = &last_event_id {
+                request = request.header("Last-Event-ID", id);
+            }
+
+            let response = request.send().context("Failed to send GET request")?;
+            if !response.status().is_success() {
+                return self.handle_response(response);
+            }
+
+            let mut end = None;
+            read_events(std::io::BufReader::new(response), |event| {
+                match event.event.as_deref() {
+                    Some("end") => {
+                        end = Some(serde_json::from_str::<LogEnd>(&event.data)?.status);
+                    }
+                    _ => {
+                        let lines: Vec<JobLogEntry> = serde_json::from_str(&event.data)
+                            .context("Failed to parse log lines")?;
+                        on_lines(&lines);
+                    }
+                }
+                if event.id.is_some() {
+                    last_event_id = event.id;
+                }
+                Ok(())
+            })?;
+
+            if let Some(status) = end {
+                return Ok(status);
+            }
+
+            // The stream closed before the job finished; resume where it left off
+            std::thread::sleep(Duration::from_secs(1));
+        }
+    }
+
+    /// Build the URL of a job's logs
+    fn job_logs_url(&self, job_id: &str, tail: Option<usize>, step: Option<&str>) -> String {
         let mut url = format!("{}/api/jobs/{}/logs", self.base_url, job_id);
+        let mut params = Vec::new();
 
         if let Some(tail) = tail {
-            url.push_str(&format!("?tail={}", tail));
+            params.push(format!("tail={}", tail));
+        }
+        if let Some(step) = step {
+            params.push(format!("step={}", urlencoding::encode(step)));
         }
 
+        if !params.is_empty() {
+            url.push('?');
+            url.push_str(&params.join("&"));
+        }
+
+        url
+    }
+
+    /// List the artifacts of a job
+    pub fn list_artifacts(&self, job_id: &str) -> Result<JobArtifacts> {
+        let url = format!("{}/api/jobs/{}/artifacts", self.base_url, job_id);
         self.get(&url)
     }
 
+    /// Download one of a job's artifacts
+    pub fn download_artifact(&self, job_id: &str, name: &str) -> Result<Vec<u8>> {
+        let url = self.artifact_url(job_id, name);
+
+        let response = self
+            .client
+            .get(&url)
+            .timeout(DOWNLOAD_TIMEOUT)
+            .send()
+            .context("Failed to send download request")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return self.handle_response(response);
+        }
+
+        Ok(response
+            .bytes()
+            .with_context(|| format!("Failed to download artifact {}", name))?
+            .to_vec())
+    }
+
+    /// Build the URL of an artifact, keeping the `/` separators of its name
+    fn artifact_url(&self, job_id: &str, name: &str) -> String {
+        let path: Vec<_> = name.split('/').map(urlencoding::encode).collect();
+        format!(
+            "{}/api/jobs/{}/artifacts/{}",
+            self.base_url,
+            job_id,
+            path.join("/")
+        )
+    }
+
+    /// List registered agents
+    pub fn list_agents(&self) -> Result<AgentList> {
+        let url = format!("{}/api/agents", self.base_url);
+        self.get(&url)
+    }
+
+    /// List recent webhook deliveries
+    pub fn list_deliveries(&self, limit: Option<usize>) -> Result<WebhookDeliveryList> {
+        let mut url = format!("{}/api/webhooks/deliveries", self.base_url);
+
+        if let Some(limit) = limit {
+            url.push_str(&format!("?limit={}", limit));
+        }
+
+        self.get(&url)
+    }
+
+    /// Get a webhook delivery
+    pub fn get_delivery(&self, delivery_id: &str) -> Result<WebhookDelivery> {
+        let url = format!("{}/api/webhooks/deliveries/{}", self.base_url, delivery_id);
+        self.get(&url)
+    }
+
+    /// Replay a webhook delivery
+    pub fn replay_delivery(&self, delivery_id: &str) -> Result<WebhookDelivery> {
+        let url = format!(
+            "{}/api/webhooks/deliveries/{}/replay",
+            self.base_url, delivery_id
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .send()
+            .context("Failed to send replay request")?;
+
+        self.handle_response(response)
+    }
+
     /// Trigger a new job
     pub fn trigger_job(&self, trigger: &JobTrigger) -> Result<Job> {
         let url = format!("{}/api/jobs", self.base_url);
@@ -110,6 +289,19 @@ impl ApiClient {
         self.handle_response(response)
     }
 
+    /// Prune finished jobs expired by the retention policy
+    pub fn prune_jobs(&self, dry_run: bool) -> Result<PruneReport> {
+        let url = format!("{}/api/jobs/prune?dry_run={}", self.base_url, dry_run);
+
+        let response = self
+            .client
+            .post(&url)
+            .send()
+            .context("Failed to send prune request")?;
+
+        self.handle_response(response)
+    }
+
     /// Generic GET request
     fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
         let response = self
@@ -144,10 +336,106 @@ impl ApiClient {
     }
 }
 
+/// Server-Sent Event, as far as the log stream uses them
+#[derive(Debug, Default, PartialEq)]
+struct SseEvent {
+    event: Option<String>,
+    id: Option<String>,
+    data: String,
+}
+
+/// Read Server-Sent Events until the stream closes, passing each to `on_event`
+fn read_events(
+    reader: impl BufRead,
+    mut on_event: impl FnMut(SseEvent) -> Result<()>,
+) -> Result<()> {
+    let mut event = SseEvent::default();
+
+    for line in reader.lines() {
+        let line = line.context("Failed to read event stream")?;
+
+        if line.is_empty() {
+            // Comments (keepalives) leave no data and aren't dispatched
+            if !event.data.is_empty() {
+                on_event(std::mem::take(&mut event))?;
+            }
+            event = SseEvent::default();
+            continue;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((&line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event.event = Some(value.to_string()),
+            "id" => event.id = Some(value.to_string()),
+            "data" => {
+                if !event.data.is_empty() {
+                    event.data.push('\n');
+                }
+                event.data.push_str(value);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_read_events() {
+        let stream =
+            "id: 1-0\ndata: [{\"a\":1}]\n\n:\n\nevent: end\ndata: {\"status\":\"success\"}\n\n";
+
+        let mut events = Vec::new();
+        read_events(stream.as_bytes(), |event| {
+            events.push(event);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: None,
+                    id: Some("1-0".to_string()),
+                    data: r#"[{"a":1}]"#.to_string(),
+                },
+                SseEvent {
+                    event: Some("end".to_string()),
+                    id: None,
+                    data: r#"{"status":"success"}"#.to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_job_logs_url() {
+        let client = ApiClient::new("http://localhost:8080").unwrap();
+        assert_eq!(
+            client.job_logs_url("job-1", None, None),
+            "http://localhost:8080/api/jobs/job-1/logs"
+        );
+        assert_eq!(
+            client.job_logs_url("job-1", Some(50), Some("unit tests")),
+            "http://localhost:8080/api/jobs/job-1/logs?tail=50&step=unit%20tests"
+        );
+    }
+
+    #[test]
+    fn test_artifact_url() {
+        let client = ApiClient::new("http://localhost:8080").unwrap();
+        assert_eq!(
+            client.artifact_url("job-1", "dist/app 1.0.tar.gz"),
+            "http://localhost:8080/api/jobs/job-1/artifacts/dist/app%201.0.tar.gz"
+        );
+    }
+
     #[test]
     fn test_api_client_new() {
         let client = ApiClient::new("http://localhost:8080");
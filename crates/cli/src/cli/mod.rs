@@ -58,8 +58,12 @@ pub enum Commands {
     Jobs(JobsCommand),
     /// Mirror GitHub repositories to Gitea
     Mirror(MirrorCommand),
-    // Placeholder for future subcommands:
-    // - Agent
+    /// Work with repository pipeline definitions (.raibid.yml)
+    Pipeline(PipelineCommand),
+    /// Inspect the agent pool
+    Agents(AgentsCommand),
+    /// Inspect and replay webhook deliveries
+    Webhooks(WebhooksCommand),
 }
 
 /// Configuration management commands
@@ -284,6 +288,10 @@ pub enum JobsSubcommand {
         /// Job ID to show
         job_id: String,
 
+        /// Show per-step results (exit code, duration)
+        #[arg(long)]
+        steps: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -301,6 +309,10 @@ pub enum JobsSubcommand {
         /// Number of lines to show from the end
         #[arg(short, long)]
         tail: Option<usize>,
+
+        /// Only show lines of this pipeline step
+        #[arg(short, long)]
+        step: Option<String>,
     },
 
     /// Trigger a new job
@@ -331,6 +343,114 @@ pub enum JobsSubcommand {
         #[arg(long)]
         json: bool,
     },
+
+    /// Remove finished jobs the server's retention policy no longer keeps
+    Prune {
+        /// List the jobs that would be removed without removing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List or download a job's artifacts
+    Artifacts(ArtifactsCommand),
+}
+
+/// Job artifact commands
+#[derive(Args, Debug)]
+pub struct ArtifactsCommand {
+    #[command(subcommand)]
+    pub command: ArtifactsSubcommand,
+}
+
+/// Artifacts subcommands
+#[derive(Subcommand, Debug)]
+pub enum ArtifactsSubcommand {
+    /// List the artifacts a job uploaded
+    List {
+        /// Job ID to list artifacts of
+        job_id: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Download a job's artifacts and verify their checksums
+    Download {
+        /// Job ID to download artifacts of
+        job_id: String,
+
+        /// Artifacts to download (defaults to all of them)
+        names: Vec<String>,
+
+        /// Directory to download into, keeping each artifact's path
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+    },
+}
+
+/// Agent pool commands
+#[derive(Args, Debug)]
+pub struct AgentsCommand {
+    #[command(subcommand)]
+    pub command: AgentsSubcommand,
+}
+
+/// Agents subcommands
+#[derive(Subcommand, Debug)]
+pub enum AgentsSubcommand {
+    /// List registered agents and their status
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Webhook delivery commands
+#[derive(Args, Debug)]
+pub struct WebhooksCommand {
+    #[command(subcommand)]
+    pub command: WebhooksSubcommand,
+}
+
+/// Webhooks subcommands
+#[derive(Subcommand, Debug)]
+pub enum WebhooksSubcommand {
+    /// List recent webhook deliveries and their outcome
+    List {
+        /// Maximum number of deliveries to return
+        #[arg(short, long)]
+        limit: Option<usize>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show a delivery's headers and payload
+    Show {
+        /// Delivery ID to show
+        delivery_id: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Handle a recorded delivery again (e.g. after fixing a webhook secret)
+    Replay {
+        /// Delivery ID to replay
+        delivery_id: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 /// Mirror management commands
@@ -354,3 +474,24 @@ pub enum MirrorSubcommand {
         json: bool,
     },
 }
+
+/// Pipeline definition commands
+#[derive(Args, Debug)]
+pub struct PipelineCommand {
+    #[command(subcommand)]
+    pub command: PipelineSubcommand,
+}
+
+/// Pipeline subcommands
+#[derive(Subcommand, Debug)]
+pub enum PipelineSubcommand {
+    /// Validate a pipeline definition file
+    Validate {
+        /// Pipeline file to validate (defaults to ./.raibid.yml)
+        file: Option<PathBuf>,
+
+        /// Show the steps that would run on this branch
+        #[arg(short, long)]
+        branch: Option<String>,
+    },
+}
@@ -0,0 +1,87 @@
+//! Agent pool commands
+//!
+//! Provides subcommands for inspecting the agents registered with the server:
+//! - list: Show every agent with its status, running jobs and load
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Cell, CellAlignment, ContentArrangement, Table};
+use raibid_common::jobs::format_duration;
+use raibid_common::AgentStatus;
+
+use super::jobs::format_timestamp;
+use crate::api::ApiClient;
+use crate::cli::{AgentsCommand, AgentsSubcommand};
+
+/// Handle agents command
+pub fn handle(cmd: &AgentsCommand) -> Result<()> {
+    match &cmd.command {
+        AgentsSubcommand::List { json } => list_agents(*json),
+    }
+}
+
+/// List registered agents
+fn list_agents(json: bool) -> Result<()> {
+    let client = ApiClient::from_env().context("Failed to create API client")?;
+    let agent_list = client.list_agents().context("Failed to fetch agents")?;
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&agent_list)
+            .context("Failed to serialize agents to JSON")?;
+        println!("{}", json_str);
+        return Ok(());
+    }
+
+    if agent_list.agents.is_empty() {
+        println!("{}", "No agents registered.".yellow());
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("ID").set_alignment(CellAlignment::Center),
+            Cell::new("Host").set_alignment(CellAlignment::Left),
+            Cell::new("Version").set_alignment(CellAlignment::Left),
+            Cell::new("Status").set_alignment(CellAlignment::Center),
+            Cell::new("Jobs").set_alignment(CellAlignment::Right),
+            Cell::new("Load").set_alignment(CellAlignment::Right),
+            Cell::new("Uptime").set_alignment(CellAlignment::Right),
+            Cell::new("Last Seen").set_alignment(CellAlignment::Left),
+        ]);
+
+    for agent in &agent_list.agents {
+        let status = match agent.status {
+            AgentStatus::Idle => agent.status.as_str().green(),
+            AgentStatus::Busy => agent.status.as_str().blue(),
+            AgentStatus::Offline => agent.status.as_str().red(),
+        };
+        let load = agent
+            .load
+            .map(|load| format!("{:.0}%", load * 100.0))
+            .unwrap_or_else(|| "-".to_string());
+
+        table.add_row(vec![
+            Cell::new(&agent.id),
+            Cell::new(&agent.hostname),
+            Cell::new(&agent.version),
+            Cell::new(status),
+            Cell::new(format!("{}/{}", agent.current_jobs.len(), agent.capacity)),
+            Cell::new(load),
+            Cell::new(format_duration(agent.uptime_secs())),
+            Cell::new(format_timestamp(&agent.last_heartbeat)),
+        ]);
+    }
+
+    println!("{}", table);
+    println!(
+        "\n{} {} of {} agents online",
+        "Info:".cyan().bold(),
+        agent_list.online,
+        agent_list.total
+    );
+
+    Ok(())
+}
@@ -263,6 +263,9 @@ redis:
   # Job queue stream name
   job_stream: raibid:jobs
 
+  # Consumer group agents use to read the job queue
+  consumer_group: raibid-workers
+
 # UI configuration
 ui:
   # Enable TUI (terminal user interface)
@@ -1,17 +1,22 @@
 //! Job management commands
 //!
 //! This module implements CLI commands for managing CI/CD jobs including
-//! listing jobs, viewing details, triggering builds, canceling jobs, and viewing logs.
+//! listing jobs, viewing details, triggering builds, canceling jobs, viewing logs
+//! and downloading artifacts.
 
 use anyhow::{Context, Result};
 use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL, Cell, CellAlignment, ContentArrangement, Table};
-use raibid_common::{Job, JobListQuery, JobStatus, JobTrigger};
+use raibid_common::artifacts::is_relative_path;
+use raibid_common::jobs::format_duration;
+use raibid_common::{
+    Job, JobListQuery, JobLogEntry, JobStatus, JobTrigger, LogLevel, LogStream, StepResult,
+};
 use serde_json;
-use std::time::Duration;
+use std::path::Path;
 
 use crate::api::ApiClient;
-use crate::cli::{JobsCommand, JobsSubcommand};
+use crate::cli::{ArtifactsSubcommand, JobsCommand, JobsSubcommand};
 
 /// Handle jobs command
 pub fn handle(cmd: &JobsCommand) -> Result<()> {
@@ -24,12 +29,17 @@ pub fn handle(cmd: &JobsCommand) -> Result<()> {
             offset,
             json,
         } => list_jobs(status, repo, branch, *limit, *offset, *json),
-        JobsSubcommand::Show { job_id, json } => show_job(job_id, *json),
+        JobsSubcommand::Show {
+            job_id,
+            steps,
+            json,
+        } => show_job(job_id, *steps, *json),
         JobsSubcommand::Logs {
             job_id,
             follow,
             tail,
-        } => show_logs(job_id, *follow, *tail),
+            step,
+        } => show_logs(job_id, *follow, *tail, step.as_deref()),
         JobsSubcommand::Trigger {
             repo,
             branch,
@@ -37,6 +47,15 @@ pub fn handle(cmd: &JobsCommand) -> Result<()> {
             json,
         } => trigger_job(repo, branch, commit.as_deref(), *json),
         JobsSubcommand::Cancel { job_id, json } => cancel_job(job_id, *json),
+        JobsSubcommand::Prune { dry_run, json } => prune_jobs(*dry_run, *json),
+        JobsSubcommand::Artifacts(cmd) => match &cmd.command {
+            ArtifactsSubcommand::List { job_id, json } => list_artifacts(job_id, *json),
+            ArtifactsSubcommand::Download {
+                job_id,
+                names,
+                output,
+            } => download_artifacts(job_id, names, output),
+        },
     }
 }
 
@@ -80,40 +99,7 @@ fn list_jobs(
             return Ok(());
         }
 
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL)
-            .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_header(vec![
-                Cell::new("ID").set_alignment(CellAlignment::Center),
-                Cell::new("Repository").set_alignment(CellAlignment::Left),
-                Cell::new("Branch").set_alignment(CellAlignment::Left),
-                Cell::new("Status").set_alignment(CellAlignment::Center),
-                Cell::new("Started").set_alignment(CellAlignment::Left),
-                Cell::new("Duration").set_alignment(CellAlignment::Right),
-            ]);
-
-        for job in &job_list.jobs {
-            let status_cell = format!("{} {}", job.status.icon(), job.status.as_str());
-            let status_colored = match job.status {
-                JobStatus::Success => status_cell.green(),
-                JobStatus::Failed => status_cell.red(),
-                JobStatus::Running => status_cell.blue(),
-                JobStatus::Pending => status_cell.yellow(),
-                JobStatus::Cancelled => status_cell.truecolor(128, 128, 128),
-            };
-
-            table.add_row(vec![
-                Cell::new(&job.id),
-                Cell::new(&job.repo),
-                Cell::new(&job.branch),
-                Cell::new(status_colored),
-                Cell::new(format_timestamp(&job.started_at)),
-                Cell::new(job.duration_string()),
-            ]);
-        }
-
-        println!("{}", table);
+        print_jobs_table(&job_list.jobs);
         println!(
             "\n{} Showing {} of {} jobs (offset: {})",
             "Info:".cyan().bold(),
@@ -126,28 +112,127 @@ fn list_jobs(
     Ok(())
 }
 
+/// Print jobs as a table
+fn print_jobs_table(jobs: &[Job]) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("ID").set_alignment(CellAlignment::Center),
+            Cell::new("Repository").set_alignment(CellAlignment::Left),
+            Cell::new("Branch").set_alignment(CellAlignment::Left),
+            Cell::new("Status").set_alignment(CellAlignment::Center),
+            Cell::new("Started").set_alignment(CellAlignment::Left),
+            Cell::new("Duration").set_alignment(CellAlignment::Right),
+        ]);
+
+    for job in jobs {
+        let status_cell = format!("{} {}", job.status.icon(), job.status.as_str());
+        let status_colored = match job.status {
+            JobStatus::Success => status_cell.green(),
+            JobStatus::Failed => status_cell.red(),
+            JobStatus::Running => status_cell.blue(),
+            JobStatus::Pending => status_cell.yellow(),
+            JobStatus::Cancelled => status_cell.truecolor(128, 128, 128),
+        };
+
+        table.add_row(vec![
+            Cell::new(&job.id),
+            Cell::new(&job.repo),
+            Cell::new(&job.branch),
+            Cell::new(status_colored),
+            Cell::new(format_timestamp(&job.started_at)),
+            Cell::new(job.duration_string()),
+        ]);
+    }
+
+    println!("{}", table);
+}
+
 /// Show detailed information about a specific job
-fn show_job(job_id: &str, json: bool) -> Result<()> {
+fn show_job(job_id: &str, show_steps: bool, json: bool) -> Result<()> {
     let client = ApiClient::from_env().context("Failed to create API client")?;
     let job = client.get_job(job_id).context("Failed to fetch job")?;
 
+    let steps = if show_steps {
+        Some(
+            client
+                .get_job_steps(job_id)
+                .context("Failed to fetch job steps")?
+                .steps,
+        )
+    } else {
+        None
+    };
+
     if json {
+        let value = match steps {
+            Some(steps) => serde_json::json!({ "job": job, "steps": steps }),
+            None => serde_json::to_value(&job).context("Failed to serialize job to JSON")?,
+        };
         let json_str =
-            serde_json::to_string_pretty(&job).context("Failed to serialize job to JSON")?;
+            serde_json::to_string_pretty(&value).context("Failed to serialize job to JSON")?;
         println!("{}", json_str);
     } else {
         print_job_details(&job);
+        if let Some(steps) = steps {
+            print_steps(&steps);
+        }
     }
 
     Ok(())
 }
 
+/// Print a table of step results
+fn print_steps(steps: &[StepResult]) {
+    println!("\n{}", "Steps".cyan().bold().underline());
+
+    if steps.is_empty() {
+        println!("{}", "No step results recorded yet.".yellow());
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Step").set_alignment(CellAlignment::Left),
+            Cell::new("Result").set_alignment(CellAlignment::Center),
+            Cell::new("Exit Code").set_alignment(CellAlignment::Right),
+            Cell::new("Duration").set_alignment(CellAlignment::Right),
+        ]);
+
+    for step in steps {
+        let result = if step.success {
+            "✓ passed".green()
+        } else {
+            "✗ failed".red()
+        };
+        let exit_code = step
+            .exit_code
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        table.add_row(vec![
+            Cell::new(&step.step),
+            Cell::new(result),
+            Cell::new(exit_code),
+            Cell::new(format_duration(step.duration_secs)),
+        ]);
+    }
+
+    println!("{}", table);
+}
+
 /// Show logs for a specific job
-fn show_logs(job_id: &str, follow: bool, tail: Option<usize>) -> Result<()> {
+fn show_logs(job_id: &str, follow: bool, tail: Option<usize>, step: Option<&str>) -> Result<()> {
     let client = ApiClient::from_env().context("Failed to create API client")?;
+    // Lines are labelled with their step unless only one step is shown
+    let show_step = step.is_none();
 
     if follow {
-        // Follow mode: poll for new logs
         println!(
             "{} Following logs for job {}...",
             "Info:".cyan().bold(),
@@ -155,49 +240,26 @@ fn show_logs(job_id: &str, follow: bool, tail: Option<usize>) -> Result<()> {
         );
         println!("{}", "─".repeat(80).truecolor(100, 100, 100));
 
-        let mut last_line_count = 0;
-
-        loop {
-            let logs = client
-                .get_job_logs(job_id, None)
-                .context("Failed to fetch logs")?;
-
-            // Print new lines only
-            for entry in logs.entries.iter().skip(last_line_count) {
-                println!(
-                    "[{}] {}",
-                    entry
-                        .timestamp
-                        .format("%H:%M:%S")
-                        .to_string()
-                        .truecolor(150, 150, 150),
-                    entry.message
-                );
-            }
-
-            last_line_count = logs.entries.len();
-
-            // Check if job is finished
-            let job = client
-                .get_job(job_id)
-                .context("Failed to fetch job status")?;
-            if job.status.is_terminal() {
-                println!("{}", "─".repeat(80).truecolor(100, 100, 100));
-                println!(
-                    "{} Job finished with status: {}",
-                    "Info:".cyan().bold(),
-                    format_status(&job.status)
-                );
-                break;
-            }
-
-            // Wait before next poll
-            std::thread::sleep(Duration::from_secs(2));
+        let status = client
+            .follow_job_logs(job_id, tail, step, |entries| {
+                for entry in entries {
+                    print_log_entry(entry, show_step);
+                }
+            })
+            .context("Failed to follow logs")?;
+
+        println!("{}", "─".repeat(80).truecolor(100, 100, 100));
+        if let Some(status) = status {
+            println!(
+                "{} Job finished with status: {}",
+                "Info:".cyan().bold(),
+                format_status(&status)
+            );
         }
     } else {
         // One-time fetch
         let logs = client
-            .get_job_logs(job_id, tail)
+            .get_job_logs(job_id, tail, step)
             .context("Failed to fetch logs")?;
 
         if logs.entries.is_empty() {
@@ -209,21 +271,42 @@ fn show_logs(job_id: &str, follow: bool, tail: Option<usize>) -> Result<()> {
         println!("{}", "─".repeat(80).truecolor(100, 100, 100));
 
         for entry in &logs.entries {
-            println!(
-                "[{}] {}",
-                entry
-                    .timestamp
-                    .format("%H:%M:%S")
-                    .to_string()
-                    .truecolor(150, 150, 150),
-                entry.message
-            );
+            print_log_entry(entry, show_step);
         }
     }
 
     Ok(())
 }
 
+/// Print a log line, coloured by its step, origin and level
+fn print_log_entry(entry: &JobLogEntry, show_step: bool) {
+    let timestamp = entry
+        .timestamp
+        .format("%H:%M:%S")
+        .to_string()
+        .truecolor(150, 150, 150);
+
+    let step = match (&entry.step, show_step) {
+        (Some(step), true) => format!("{} ", format!("[{}]", step).cyan()),
+        _ => String::new(),
+    };
+
+    // Keep the command's own colours only when colouring our output too
+    let message = if colored::control::SHOULD_COLORIZE.should_colorize() {
+        entry.message.as_str()
+    } else {
+        entry.plain_message()
+    };
+    let message = match (entry.level, entry.stream) {
+        (LogLevel::Error, _) => entry.plain_message().red().bold(),
+        (LogLevel::Warn, _) => entry.plain_message().yellow().bold(),
+        (LogLevel::Info, LogStream::System) => entry.plain_message().bold(),
+        (LogLevel::Info, _) => message.normal(),
+    };
+
+    println!("[{}] {}{}", timestamp, step, message);
+}
+
 /// Trigger a new job
 fn trigger_job(repo: &str, branch: &str, commit: Option<&str>, json: bool) -> Result<()> {
     let client = ApiClient::from_env().context("Failed to create API client")?;
@@ -271,13 +354,158 @@ fn cancel_job(job_id: &str, json: bool) -> Result<()> {
             serde_json::to_string_pretty(&job).context("Failed to serialize job to JSON")?;
         println!("{}", json_str);
     } else {
-        println!("{} Job cancelled successfully!", "Success:".green().bold());
+        if job.status == JobStatus::Cancelled {
+            println!("{} Job cancelled successfully!", "Success:".green().bold());
+        } else {
+            // Running jobs are stopped by their agent shortly after the request
+            println!(
+                "{} Cancellation requested; the agent will stop the job shortly",
+                "Success:".green().bold()
+            );
+        }
         print_job_details(&job);
     }
 
     Ok(())
 }
 
+/// Remove finished jobs the server's retention policy no longer keeps
+fn prune_jobs(dry_run: bool, json: bool) -> Result<()> {
+    let client = ApiClient::from_env().context("Failed to create API client")?;
+    let report = client.prune_jobs(dry_run).context("Failed to prune jobs")?;
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&report)
+            .context("Failed to serialize prune report to JSON")?;
+        println!("{}", json_str);
+        return Ok(());
+    }
+
+    if report.jobs.is_empty() {
+        println!("{}", "No jobs to prune.".yellow());
+        return Ok(());
+    }
+
+    print_jobs_table(&report.jobs);
+    if report.dry_run {
+        println!(
+            "\n{} {} jobs would be pruned",
+            "Info:".cyan().bold(),
+            report.jobs.len()
+        );
+    } else {
+        println!(
+            "\n{} Pruned {} jobs",
+            "Success:".green().bold(),
+            report.jobs.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// List the artifacts of a job
+fn list_artifacts(job_id: &str, json: bool) -> Result<()> {
+    let client = ApiClient::from_env().context("Failed to create API client")?;
+    let list = client
+        .list_artifacts(job_id)
+        .context("Failed to fetch job artifacts")?;
+
+    if json {
+        let json_str =
+            serde_json::to_string_pretty(&list).context("Failed to serialize artifacts to JSON")?;
+        println!("{}", json_str);
+        return Ok(());
+    }
+
+    if list.artifacts.is_empty() {
+        println!("{}", "No artifacts uploaded.".yellow());
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Name").set_alignment(CellAlignment::Left),
+            Cell::new("Size").set_alignment(CellAlignment::Right),
+            Cell::new("SHA-256").set_alignment(CellAlignment::Left),
+        ]);
+
+    for artifact in &list.artifacts {
+        table.add_row(vec![
+            Cell::new(&artifact.name),
+            Cell::new(artifact.size),
+            Cell::new(&artifact.sha256),
+        ]);
+    }
+
+    println!("{}", table);
+    Ok(())
+}
+
+/// Download a job's artifacts into a directory, verifying their checksums
+///
+/// Downloads all of the job's artifacts unless `names` picks some.
+fn download_artifacts(job_id: &str, names: &[String], output: &Path) -> Result<()> {
+    let client = ApiClient::from_env().context("Failed to create API client")?;
+    let list = client
+        .list_artifacts(job_id)
+        .context("Failed to fetch job artifacts")?;
+
+    let artifacts = if names.is_empty() {
+        list.artifacts
+    } else {
+        names
+            .iter()
+            .map(|name| {
+                list.artifacts
+                    .iter()
+                    .find(|artifact| &artifact.name == name)
+                    .cloned()
+                    .with_context(|| format!("Job {} has no artifact {}", job_id, name))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    if artifacts.is_empty() {
+        println!("{}", "No artifacts uploaded.".yellow());
+        return Ok(());
+    }
+
+    for artifact in &artifacts {
+        // Names come from the server; never write outside the output directory
+        if !is_relative_path(&artifact.name) {
+            anyhow::bail!("Refusing to write artifact {}", artifact.name);
+        }
+
+        let data = client
+            .download_artifact(job_id, &artifact.name)
+            .with_context(|| format!("Failed to download artifact {}", artifact.name))?;
+        if !artifact.verify(&data) {
+            anyhow::bail!("Checksum mismatch for artifact {}", artifact.name);
+        }
+
+        let path = output.join(&artifact.name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, &data)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        println!("{} {}", "Downloaded".green(), path.display());
+    }
+
+    println!(
+        "\n{} Downloaded {} artifacts",
+        "Success:".green().bold(),
+        artifacts.len()
+    );
+    Ok(())
+}
+
 /// Print detailed job information
 fn print_job_details(job: &Job) {
     println!("\n{}", "Job Details".cyan().bold().underline());
@@ -285,6 +513,18 @@ fn print_job_details(job: &Job) {
     println!("{:<15} {}", "Repository:", job.repo);
     println!("{:<15} {}", "Branch:", job.branch);
     println!("{:<15} {}", "Commit:", job.commit);
+
+    if let Some(tag) = &job.tag {
+        println!("{:<15} {} (release)", "Tag:", tag);
+    }
+
+    if let Some(pr) = &job.pull_request {
+        println!(
+            "{:<15} #{} into {} (from {})",
+            "Pull Request:", pr.number, pr.base_branch, pr.head_repo
+        );
+    }
+
     println!("{:<15} {}", "Status:", format_status(&job.status));
     println!("{:<15} {}", "Started:", format_timestamp(&job.started_at));
 
@@ -298,6 +538,10 @@ fn print_job_details(job: &Job) {
         println!("{:<15} {}", "Agent:", agent_id);
     }
 
+    if job.attempts > 1 {
+        println!("{:<15} {}", "Attempts:", job.attempts);
+    }
+
     if let Some(exit_code) = job.exit_code {
         let exit_str = if exit_code == 0 {
             exit_code.to_string().green()
@@ -321,7 +565,7 @@ fn format_status(status: &JobStatus) -> colored::ColoredString {
 }
 
 /// Format timestamp as relative time
-fn format_timestamp(timestamp: &chrono::DateTime<chrono::Utc>) -> String {
+pub(crate) fn format_timestamp(timestamp: &chrono::DateTime<chrono::Utc>) -> String {
     let now = chrono::Utc::now();
     let duration = now.signed_duration_since(*timestamp);
 
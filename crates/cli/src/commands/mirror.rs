@@ -5,6 +5,8 @@
 use anyhow::{Context, Result};
 use raibid_common::config::Config;
 use raibid_common::mirroring::MirroringService;
+use raibid_common::store::RedisWebhookSecretStore;
+use std::sync::Arc;
 use tracing::{error, info};
 
 use crate::cli::{MirrorCommand, MirrorSubcommand};
@@ -63,9 +65,16 @@ async fn execute_mirror(dry_run: bool, json: bool) -> Result<()> {
         return Ok(());
     }
 
+    // Per-repository webhook secrets are stored where the server looks them up
+    let redis_url =
+        std::env::var("RAIBID_REDIS_URL").unwrap_or_else(|_| config.redis.connection_url());
+    let secret_store = RedisWebhookSecretStore::from_url(&redis_url)
+        .context("Failed to create webhook secret store")?;
+
     // Create mirroring service
     let service = MirroringService::new(config.mirroring.clone(), config.gitea.clone())
-        .context("Failed to create mirroring service")?;
+        .context("Failed to create mirroring service")?
+        .with_secret_store(Arc::new(secret_store));
 
     // Execute mirroring
     let result = service.mirror_all().await.context("Mirroring failed")?;
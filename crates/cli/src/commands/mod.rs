@@ -3,16 +3,15 @@
 //! This module contains the actual implementation of CLI commands.
 //! Each command is implemented as a separate module.
 
+pub mod agents;
 pub mod config;
 pub mod destroy;
 pub mod health;
 pub mod init;
 pub mod jobs;
 pub mod mirror;
+pub mod pipeline;
 pub mod setup;
 pub mod status;
 pub mod teardown;
-
-// Placeholder for future command implementations
-// Command modules will be added in future issues:
-// - pub mod agent;
+pub mod webhooks;
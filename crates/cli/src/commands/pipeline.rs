@@ -0,0 +1,73 @@
+//! Pipeline definition commands
+//!
+//! Provides subcommands for working with a repository's `.raibid.yml`:
+//! - validate: Check a pipeline definition and list its steps
+
+use crate::cli::{PipelineCommand, PipelineSubcommand};
+use anyhow::Result;
+use colored::Colorize;
+use raibid_common::pipeline::{PipelineDefinition, PIPELINE_FILE_NAME};
+use std::path::PathBuf;
+
+/// Handle pipeline command and its subcommands
+pub fn handle(cmd: &PipelineCommand) -> Result<()> {
+    match &cmd.command {
+        PipelineSubcommand::Validate { file, branch } => {
+            validate_pipeline_file(file.as_ref(), branch.as_deref())
+        }
+    }
+}
+
+/// Validate a pipeline definition file
+fn validate_pipeline_file(file: Option<&PathBuf>, branch: Option<&str>) -> Result<()> {
+    let path = file
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(PIPELINE_FILE_NAME));
+
+    println!("Validating pipeline file: {}", path.display());
+    let definition = PipelineDefinition::load(&path)?;
+
+    println!("{} Pipeline is valid!", "✓".green().bold());
+    println!();
+
+    let definition = match branch {
+        Some(branch) => {
+            println!("Steps on branch {}:", branch.cyan());
+            definition.for_branch(branch)
+        }
+        None => {
+            println!("Steps:");
+            definition
+        }
+    };
+
+    for (i, step) in definition.steps.iter().enumerate() {
+        let mut notes = vec![format!("timeout {}s", step.timeout_seconds)];
+        if step.allow_failure {
+            notes.push("allow failure".to_string());
+        }
+        if !step.branches.is_empty() {
+            notes.push(format!("branches: {}", step.branches.join(", ")));
+        }
+
+        println!(
+            "  {}. {} ({})",
+            i + 1,
+            step.name.bold(),
+            notes.join(", ").dimmed()
+        );
+        for command in &step.commands {
+            println!("       $ {}", command);
+        }
+    }
+
+    if !definition.artifacts.is_empty() {
+        println!();
+        println!("Artifacts:");
+        for pattern in &definition.artifacts {
+            println!("  {}", pattern);
+        }
+    }
+
+    Ok(())
+}
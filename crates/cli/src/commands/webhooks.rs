@@ -0,0 +1,185 @@
+//! Webhook delivery commands
+//!
+//! Provides subcommands for inspecting the server's webhook delivery log:
+//! - list: Show recent deliveries and what came of them
+//! - show: Show a delivery's headers and payload
+//! - replay: Handle a recorded delivery again
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Cell, CellAlignment, ContentArrangement, Table};
+use raibid_common::{DeliveryOutcome, WebhookDelivery};
+
+use super::jobs::format_timestamp;
+use crate::api::ApiClient;
+use crate::cli::{WebhooksCommand, WebhooksSubcommand};
+
+/// Handle webhooks command
+pub fn handle(cmd: &WebhooksCommand) -> Result<()> {
+    match &cmd.command {
+        WebhooksSubcommand::List { limit, json } => list_deliveries(*limit, *json),
+        WebhooksSubcommand::Show { delivery_id, json } => show_delivery(delivery_id, *json),
+        WebhooksSubcommand::Replay { delivery_id, json } => replay_delivery(delivery_id, *json),
+    }
+}
+
+/// List recent webhook deliveries
+fn list_deliveries(limit: Option<usize>, json: bool) -> Result<()> {
+    let client = ApiClient::from_env().context("Failed to create API client")?;
+    let delivery_list = client
+        .list_deliveries(limit)
+        .context("Failed to fetch webhook deliveries")?;
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&delivery_list)
+            .context("Failed to serialize deliveries to JSON")?;
+        println!("{}", json_str);
+        return Ok(());
+    }
+
+    if delivery_list.deliveries.is_empty() {
+        println!("{}", "No webhook deliveries recorded.".yellow());
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("ID").set_alignment(CellAlignment::Center),
+            Cell::new("Provider").set_alignment(CellAlignment::Left),
+            Cell::new("Event").set_alignment(CellAlignment::Left),
+            Cell::new("Outcome").set_alignment(CellAlignment::Center),
+            Cell::new("Status").set_alignment(CellAlignment::Right),
+            Cell::new("Job").set_alignment(CellAlignment::Left),
+            Cell::new("Received").set_alignment(CellAlignment::Left),
+        ]);
+
+    for delivery in &delivery_list.deliveries {
+        table.add_row(vec![
+            Cell::new(&delivery.id),
+            Cell::new(&delivery.provider),
+            Cell::new(delivery.event.as_deref().unwrap_or("-")),
+            Cell::new(format_outcome(delivery.outcome)),
+            Cell::new(delivery.status),
+            Cell::new(delivery.job_id.as_deref().unwrap_or("-")),
+            Cell::new(format_timestamp(&delivery.received_at)),
+        ]);
+    }
+
+    println!("{}", table);
+    println!(
+        "\n{} Showing {} deliveries; use 'raibid webhooks show <id>' for details",
+        "Info:".cyan().bold(),
+        delivery_list.total
+    );
+
+    Ok(())
+}
+
+/// Show a webhook delivery
+fn show_delivery(delivery_id: &str, json: bool) -> Result<()> {
+    let client = ApiClient::from_env().context("Failed to create API client")?;
+    let delivery = client
+        .get_delivery(delivery_id)
+        .context("Failed to fetch webhook delivery")?;
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&delivery)
+            .context("Failed to serialize delivery to JSON")?;
+        println!("{}", json_str);
+        return Ok(());
+    }
+
+    print_delivery_details(&delivery);
+
+    println!("\n{}", "Headers".cyan().bold().underline());
+    for (name, value) in &delivery.headers {
+        println!("{}: {}", name, value);
+    }
+
+    println!("\n{}", "Payload".cyan().bold().underline());
+    // Pretty-print JSON payloads, show anything else as received
+    match serde_json::from_str::<serde_json::Value>(&delivery.body) {
+        Ok(value) => println!(
+            "{}",
+            serde_json::to_string_pretty(&value).unwrap_or(delivery.body)
+        ),
+        Err(_) => println!("{}", delivery.body),
+    }
+
+    Ok(())
+}
+
+/// Replay a webhook delivery
+fn replay_delivery(delivery_id: &str, json: bool) -> Result<()> {
+    let client = ApiClient::from_env().context("Failed to create API client")?;
+
+    println!(
+        "{} Replaying delivery {}...",
+        "Info:".cyan().bold(),
+        delivery_id
+    );
+
+    let replay = client
+        .replay_delivery(delivery_id)
+        .context("Failed to replay webhook delivery")?;
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&replay)
+            .context("Failed to serialize delivery to JSON")?;
+        println!("{}", json_str);
+    } else {
+        print_delivery_details(&replay);
+    }
+
+    Ok(())
+}
+
+/// Print a delivery's outcome
+fn print_delivery_details(delivery: &WebhookDelivery) {
+    println!("\n{}", "Delivery Details".cyan().bold().underline());
+    println!("{:<15} {}", "ID:", delivery.id);
+    println!("{:<15} {}", "Provider:", delivery.provider);
+    println!(
+        "{:<15} {}",
+        "Event:",
+        delivery.event.as_deref().unwrap_or("-")
+    );
+
+    if let Some(provider_id) = &delivery.delivery_id {
+        println!("{:<15} {}", "Provider ID:", provider_id);
+    }
+
+    if let Some(original) = &delivery.replay_of {
+        println!("{:<15} {}", "Replay Of:", original);
+    }
+
+    println!(
+        "{:<15} {}",
+        "Received:",
+        format_timestamp(&delivery.received_at)
+    );
+    println!(
+        "{:<15} {} ({})",
+        "Outcome:",
+        format_outcome(delivery.outcome),
+        delivery.status
+    );
+    println!("{:<15} {}", "Message:", delivery.message);
+
+    if let Some(job_id) = &delivery.job_id {
+        println!("{:<15} {}", "Job:", job_id);
+    }
+}
+
+/// Format a delivery outcome with color
+fn format_outcome(outcome: DeliveryOutcome) -> colored::ColoredString {
+    match outcome {
+        DeliveryOutcome::Queued => outcome.as_str().green(),
+        DeliveryOutcome::Ignored => outcome.as_str().truecolor(128, 128, 128),
+        DeliveryOutcome::Unauthorized | DeliveryOutcome::Invalid => outcome.as_str().yellow(),
+        DeliveryOutcome::Error => outcome.as_str().red(),
+    }
+}
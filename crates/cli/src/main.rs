@@ -71,6 +71,18 @@ fn main() -> Result<()> {
             // Handle mirror subcommands (async)
             tokio::runtime::Runtime::new()?.block_on(async { commands::mirror::handle(&cmd).await })
         }
+        Some(cli::Commands::Pipeline(cmd)) => {
+            // Handle pipeline subcommands
+            commands::pipeline::handle(&cmd)
+        }
+        Some(cli::Commands::Agents(cmd)) => {
+            // Handle agents subcommands
+            commands::agents::handle(&cmd)
+        }
+        Some(cli::Commands::Webhooks(cmd)) => {
+            // Handle webhooks subcommands
+            commands::webhooks::handle(&cmd)
+        }
     }
 }
 
@@ -0,0 +1,42 @@
+//! Integration tests for agents commands
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Test that the agents command shows help when no subcommand is provided
+#[test]
+fn test_agents_no_subcommand() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("raibid"));
+    cmd.arg("agents");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage: raibid agents"));
+}
+
+/// Test that agents list command help
+#[test]
+fn test_agents_list_help() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("raibid"));
+    cmd.arg("agents").arg("list").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "List registered agents and their status",
+        ))
+        .stdout(predicate::str::contains("--json"));
+}
+
+/// Test that agents list fails cleanly when the server is unreachable
+#[test]
+fn test_agents_list_server_unreachable() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("raibid"));
+    cmd.env("RAIBID_API_URL", "http://127.0.0.1:1")
+        .arg("agents")
+        .arg("list");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to fetch agents"));
+}
@@ -54,7 +54,8 @@ fn test_jobs_show_help() {
         .stdout(predicate::str::contains(
             "Show detailed information about a specific job",
         ))
-        .stdout(predicate::str::contains("<JOB_ID>"));
+        .stdout(predicate::str::contains("<JOB_ID>"))
+        .stdout(predicate::str::contains("--steps"));
 }
 
 /// Test that jobs logs command requires job ID
@@ -143,6 +144,34 @@ fn test_jobs_cancel_help() {
         .stdout(predicate::str::contains("<JOB_ID>"));
 }
 
+/// Test that jobs prune command help
+#[test]
+fn test_jobs_prune_help() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("raibid"));
+    cmd.arg("jobs").arg("prune").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("retention policy"))
+        .stdout(predicate::str::contains("--dry-run"));
+}
+
+/// Test that jobs artifacts download command help
+#[test]
+fn test_jobs_artifacts_download_help() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("raibid"));
+    cmd.arg("jobs")
+        .arg("artifacts")
+        .arg("download")
+        .arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("checksums"))
+        .stdout(predicate::str::contains("<JOB_ID>"))
+        .stdout(predicate::str::contains("--output"));
+}
+
 /// Test that jobs list with invalid status filter fails gracefully
 /// Note: This will fail due to API not being available, but we can verify
 /// the error message is helpful
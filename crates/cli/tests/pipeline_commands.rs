@@ -0,0 +1,68 @@
+//! Integration tests for pipeline commands
+//!
+//! These tests validate pipeline definition files through the CLI binary.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+const PIPELINE: &str = r#"
+steps:
+  - name: test
+    commands:
+      - cargo test
+  - name: publish
+    commands:
+      - cargo publish --dry-run
+    branches: [main]
+"#;
+
+/// Test that pipeline validate shows help
+#[test]
+fn test_pipeline_validate_help() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("raibid"));
+    cmd.arg("pipeline").arg("validate").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Validate a pipeline definition file",
+        ))
+        .stdout(predicate::str::contains("--branch"));
+}
+
+/// Test that a valid pipeline file is accepted
+#[test]
+fn test_pipeline_validate_valid_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join(".raibid.yml");
+    std::fs::write(&path, PIPELINE).unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("raibid"));
+    cmd.arg("pipeline")
+        .arg("validate")
+        .arg(&path)
+        .arg("--branch")
+        .arg("feature/x");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Pipeline is valid"))
+        .stdout(predicate::str::contains("test"))
+        .stdout(predicate::str::contains("publish").not());
+}
+
+/// Test that an invalid pipeline file is rejected
+#[test]
+fn test_pipeline_validate_invalid_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join(".raibid.yml");
+    std::fs::write(&path, "steps: []\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("raibid"));
+    cmd.arg("pipeline").arg("validate").arg(&path);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("steps cannot be empty"));
+}
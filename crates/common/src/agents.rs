@@ -0,0 +1,192 @@
+//! Agent registry types
+//!
+//! Agents announce themselves by periodically writing a heartbeat to the agent
+//! registry. The server lists the registry to show the agent pool and reports an
+//! agent as offline once its last heartbeat is older than a threshold.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Default interval between agent heartbeats (10 seconds)
+pub const DEFAULT_HEARTBEAT_INTERVAL_SECS: u64 = 10;
+
+/// Default heartbeat age after which an agent is considered offline (30 seconds)
+pub const DEFAULT_AGENT_OFFLINE_SECS: u64 = 30;
+
+/// Agent status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentStatus {
+    /// Agent is waiting for jobs
+    Idle,
+    /// Agent is running at least one job
+    Busy,
+    /// Agent missed its heartbeats
+    Offline,
+}
+
+impl AgentStatus {
+    /// Get a display string for the status
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgentStatus::Idle => "Idle",
+            AgentStatus::Busy => "Busy",
+            AgentStatus::Offline => "Offline",
+        }
+    }
+}
+
+impl fmt::Display for AgentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Agent registration, refreshed by every heartbeat
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AgentInfo {
+    /// Unique agent identifier
+    pub id: String,
+
+    /// Host the agent runs on
+    pub hostname: String,
+
+    /// Agent version
+    pub version: String,
+
+    /// Current status
+    pub status: AgentStatus,
+
+    /// IDs of the jobs the agent is running
+    #[serde(default)]
+    pub current_jobs: Vec<String>,
+
+    /// Maximum number of jobs the agent runs at once
+    pub capacity: u32,
+
+    /// One-minute load average divided by the number of CPUs, if known
+    #[serde(default)]
+    pub load: Option<f64>,
+
+    /// When the agent started
+    pub started_at: DateTime<Utc>,
+
+    /// When the agent last sent a heartbeat
+    pub last_heartbeat: DateTime<Utc>,
+}
+
+impl AgentInfo {
+    /// Check whether the agent missed its heartbeats
+    pub fn is_stale(&self, now: DateTime<Utc>, offline_after: Duration) -> bool {
+        let age = now.signed_duration_since(self.last_heartbeat);
+        age.to_std().is_ok_and(|age| age > offline_after)
+    }
+
+    /// Mark the agent offline if it missed its heartbeats
+    pub fn with_liveness(mut self, now: DateTime<Utc>, offline_after: Duration) -> Self {
+        if self.is_stale(now, offline_after) {
+            self.status = AgentStatus::Offline;
+        }
+        self
+    }
+
+    /// Get how long the agent has been running, in seconds
+    pub fn uptime_secs(&self) -> u64 {
+        let uptime = self.last_heartbeat.signed_duration_since(self.started_at);
+        uptime.num_seconds().max(0) as u64
+    }
+}
+
+/// Agent listing returned by the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentList {
+    /// Registered agents, sorted by ID
+    pub agents: Vec<AgentInfo>,
+
+    /// Number of registered agents
+    pub total: usize,
+
+    /// Number of agents that are not offline
+    pub online: usize,
+}
+
+impl AgentList {
+    /// Build a listing, sorting agents by ID
+    pub fn new(mut agents: Vec<AgentInfo>) -> Self {
+        agents.sort_by(|a, b| a.id.cmp(&b.id));
+        let online = agents
+            .iter()
+            .filter(|agent| agent.status != AgentStatus::Offline)
+            .count();
+
+        Self {
+            total: agents.len(),
+            online,
+            agents,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_agent(id: &str, last_heartbeat: DateTime<Utc>) -> AgentInfo {
+        AgentInfo {
+            id: id.to_string(),
+            hostname: "dgx-01".to_string(),
+            version: "0.1.0".to_string(),
+            status: AgentStatus::Idle,
+            current_jobs: Vec::new(),
+            capacity: 2,
+            load: Some(0.25),
+            started_at: last_heartbeat - chrono::Duration::seconds(90),
+            last_heartbeat,
+        }
+    }
+
+    #[test]
+    fn test_liveness() {
+        let now = Utc::now();
+        let offline_after = Duration::from_secs(30);
+
+        let fresh = sample_agent("a", now - chrono::Duration::seconds(5));
+        assert!(!fresh.is_stale(now, offline_after));
+        assert_eq!(
+            fresh.with_liveness(now, offline_after).status,
+            AgentStatus::Idle
+        );
+
+        let stale = sample_agent("b", now - chrono::Duration::seconds(60));
+        assert!(stale.is_stale(now, offline_after));
+        assert_eq!(
+            stale.with_liveness(now, offline_after).status,
+            AgentStatus::Offline
+        );
+
+        // Clock skew: a heartbeat from the future is not stale
+        let ahead = sample_agent("c", now + chrono::Duration::seconds(5));
+        assert!(!ahead.is_stale(now, offline_after));
+    }
+
+    #[test]
+    fn test_agent_list() {
+        let now = Utc::now();
+        let mut offline = sample_agent("b", now);
+        offline.status = AgentStatus::Offline;
+
+        let list = AgentList::new(vec![offline, sample_agent("a", now)]);
+        assert_eq!(list.total, 2);
+        assert_eq!(list.online, 1);
+        assert_eq!(list.agents[0].id, "a");
+        assert_eq!(list.agents[0].uptime_secs(), 90);
+    }
+
+    #[test]
+    fn test_status_serialization() {
+        let json = serde_json::to_string(&AgentStatus::Busy).unwrap();
+        assert_eq!(json, "\"busy\"");
+    }
+}
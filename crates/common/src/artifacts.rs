@@ -0,0 +1,91 @@
+//! Job artifacts
+//!
+//! Files matching the `artifacts` patterns of a repository's pipeline are
+//! uploaded by the agent to the object store under `artifacts/{job_id}/{name}`,
+//! where the name is the file's path within the repository. The list of
+//! uploaded files, with their sizes and SHA-256 checksums, is recorded with the
+//! job and served by the jobs API.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Component, Path};
+
+/// A file produced by a job and kept in the object store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    /// Path of the file within the repository, using `/` separators
+    pub name: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Hex-encoded SHA-256 checksum of the contents
+    pub sha256: String,
+}
+
+impl Artifact {
+    /// Describe a file's contents under a name
+    pub fn new(name: impl Into<String>, data: &[u8]) -> Self {
+        Self {
+            name: name.into(),
+            size: data.len() as u64,
+            sha256: sha256_hex(data),
+        }
+    }
+
+    /// Check that data matches the artifact's checksum
+    pub fn verify(&self, data: &[u8]) -> bool {
+        data.len() as u64 == self.size && sha256_hex(data) == self.sha256
+    }
+}
+
+/// Job artifacts response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobArtifacts {
+    /// Job ID
+    pub job_id: String,
+    /// Uploaded artifacts, sorted by name
+    pub artifacts: Vec<Artifact>,
+}
+
+/// Get the object store key an artifact is uploaded under
+pub fn artifact_key(job_id: &str, name: &str) -> String {
+    format!("artifacts/{}/{}", job_id, name)
+}
+
+/// Check that an artifact name or pattern stays within the repository
+///
+/// Absolute paths and `..` components are rejected.
+pub fn is_relative_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Compute the hex-encoded SHA-256 checksum of data
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifact_checksum() {
+        let artifact = Artifact::new("target/release/raibid", b"binary");
+        assert_eq!(artifact.size, 6);
+        assert_eq!(artifact.sha256, sha256_hex(b"binary"));
+        assert!(artifact.verify(b"binary"));
+        assert!(!artifact.verify(b"binarx"));
+    }
+
+    #[test]
+    fn test_is_relative_path() {
+        assert!(is_relative_path("target/release/raibid"));
+        assert!(is_relative_path("./dist/*.tar.gz"));
+        assert!(!is_relative_path(""));
+        assert!(!is_relative_path("/etc/passwd"));
+        assert!(!is_relative_path("../secrets"));
+        assert!(!is_relative_path("dist/../../secrets"));
+    }
+}
@@ -0,0 +1,310 @@
+//! Commit status reporting
+//!
+//! Jobs report their progress back to the forge as a commit status on the commit
+//! they build, so developers see whether a push passed next to the commit or pull
+//! request instead of in the TUI. Statuses are posted to Gitea; jobs building a
+//! Gitea mirror of a GitHub repository also report to the mirror's GitHub source.
+//!
+//! The server reports jobs it queues or cancels before they start; agents report
+//! the jobs they run. Reporting is best effort: failures are logged, never raised.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, warn};
+
+use crate::config::GiteaConfig;
+use crate::gitea_api::GiteaClient;
+use crate::github::GitHubClient;
+use crate::jobs::{Job, JobStatus};
+
+/// Context statuses are posted under, identifying raibid-ci among other checks
+pub const STATUS_CONTEXT: &str = "raibid-ci";
+
+/// Commit status state, as understood by both GitHub and Gitea
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitState {
+    /// The job is queued or running
+    Pending,
+    /// The job succeeded
+    Success,
+    /// The job failed
+    Failure,
+    /// The job didn't complete (e.g. it was cancelled)
+    Error,
+}
+
+/// Commit status posted for a job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommitStatus {
+    /// Status state
+    pub state: CommitState,
+
+    /// Link to the job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_url: Option<String>,
+
+    /// Short description of the job's state
+    pub description: String,
+
+    /// Context the status is posted under
+    pub context: String,
+}
+
+impl CommitStatus {
+    /// Build the status describing a job's current state
+    pub fn for_job(job: &Job, target_url: Option<String>) -> Self {
+        let (state, description) = match job.status {
+            JobStatus::Pending => (CommitState::Pending, "Queued".to_string()),
+            JobStatus::Running => (CommitState::Pending, "Running".to_string()),
+            JobStatus::Success => (
+                CommitState::Success,
+                format!("Succeeded in {}", job.duration_string()),
+            ),
+            JobStatus::Failed => (
+                CommitState::Failure,
+                format!("Failed after {}", job.duration_string()),
+            ),
+            JobStatus::Cancelled => (CommitState::Error, "Cancelled".to_string()),
+        };
+
+        Self {
+            state,
+            target_url,
+            description,
+            context: STATUS_CONTEXT.to_string(),
+        }
+    }
+}
+
+/// Get the URL of a job in the API, given the server's public URL
+pub fn job_url(public_url: &str, job_id: &str) -> String {
+    format!("{}/api/jobs/{}", public_url.trim_end_matches('/'), job_id)
+}
+
+/// Get the full name of a GitHub repository from its clone or web URL
+///
+/// Returns `None` for repositories hosted anywhere else.
+pub fn github_repository(url: &str) -> Option<String> {
+    let path = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))
+        .or_else(|| url.strip_prefix("git@github.com:"))?;
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    match path.split('/').collect::<Vec<_>>()[..] {
+        [owner, repo] if !owner.is_empty() && !repo.is_empty() => Some(path.to_string()),
+        _ => None,
+    }
+}
+
+/// Posts job commit statuses to Gitea and GitHub
+///
+/// The GitHub source of each Gitea repository is looked up once and cached for
+/// the reporter's lifetime.
+#[derive(Clone, Default)]
+pub struct CommitStatusReporter {
+    gitea: Option<GiteaClient>,
+    github: Option<GitHubClient>,
+    public_url: Option<String>,
+    github_sources: Arc<Mutex<HashMap<String, Option<String>>>>,
+}
+
+impl CommitStatusReporter {
+    /// Create a reporter
+    ///
+    /// Without a Gitea client nothing is reported, since GitHub sources are found
+    /// through their Gitea mirrors. Without a public URL statuses carry no link.
+    pub fn new(
+        gitea: Option<GiteaClient>,
+        github: Option<GitHubClient>,
+        public_url: Option<String>,
+    ) -> Self {
+        Self {
+            gitea,
+            github,
+            public_url,
+            github_sources: Arc::default(),
+        }
+    }
+
+    /// Create a reporter from environment variables
+    ///
+    /// Reports to Gitea at `RAIBID_GITEA_URL` with `GITEA_TOKEN`, and to GitHub
+    /// with `GITHUB_TOKEN`. Jobs are linked under `RAIBID_PUBLIC_URL`.
+    pub fn from_env() -> Result<Self> {
+        let gitea = match (
+            std::env::var("RAIBID_GITEA_URL"),
+            std::env::var("GITEA_TOKEN"),
+        ) {
+            (Ok(url), Ok(token)) => Some(GiteaClient::new(
+                GiteaConfig {
+                    url,
+                    ..Default::default()
+                },
+                Some(token),
+            )?),
+            _ => None,
+        };
+
+        let github = match std::env::var("GITHUB_TOKEN") {
+            Ok(token) => Some(GitHubClient::from_token(token)?),
+            Err(_) => None,
+        };
+
+        Ok(Self::new(
+            gitea,
+            github,
+            std::env::var("RAIBID_PUBLIC_URL").ok(),
+        ))
+    }
+
+    /// Check whether statuses are reported anywhere
+    pub fn is_enabled(&self) -> bool {
+        self.gitea.is_some()
+    }
+
+    /// Post the status of a job to the forges hosting its repository
+    pub async fn report(&self, job: &Job) {
+        let Some(gitea) = &self.gitea else {
+            return;
+        };
+        if job.commit.is_empty() {
+            debug!("Not reporting status of job {}: no commit", job.id);
+            return;
+        }
+        let Some((owner, repo)) = job.repo.split_once('/') else {
+            debug!("Not reporting status of job {}: invalid repo", job.id);
+            return;
+        };
+
+        let target_url = self.public_url.as_deref().map(|url| job_url(url, &job.id));
+        let status = CommitStatus::for_job(job, target_url);
+
+        if let Err(e) = gitea
+            .create_commit_status(owner, repo, &job.commit, &status)
+            .await
+        {
+            warn!(
+                "Failed to report status of job {} to Gitea: {:#}",
+                job.id, e
+            );
+        }
+
+        let Some(github) = &self.github else {
+            return;
+        };
+        let source = self.github_source(gitea, &job.repo).await;
+
+        if let Some((owner, repo)) = source.as_deref().and_then(|s| s.split_once('/')) {
+            if let Err(e) = github
+                .create_commit_status(owner, repo, &job.commit, &status)
+                .await
+            {
+                warn!(
+                    "Failed to report status of job {} to GitHub: {:#}",
+                    job.id, e
+                );
+            }
+        }
+    }
+
+    /// Get the GitHub repository a Gitea repository mirrors, if any
+    ///
+    /// Failed lookups aren't cached, so they're retried on the next report.
+    async fn github_source(&self, gitea: &GiteaClient, full_name: &str) -> Option<String> {
+        if let Some(source) = self.github_sources.lock().unwrap().get(full_name) {
+            return source.clone();
+        }
+
+        let (owner, repo) = full_name.split_once('/')?;
+        let source = match gitea.get_repository(owner, repo).await {
+            Ok(repository) if repository.mirror => repository
+                .original_url
+                .as_deref()
+                .and_then(github_repository),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Failed to look up repository {}: {:#}", full_name, e);
+                return None;
+            }
+        };
+
+        self.github_sources
+            .lock()
+            .unwrap()
+            .insert(full_name.to_string(), source.clone());
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(status: JobStatus) -> Job {
+        Job {
+            status,
+            ..Job::new("job-1", "owner/repo", "main", "abc123")
+        }
+    }
+
+    #[test]
+    fn test_status_for_job() {
+        for (status, state) in [
+            (JobStatus::Pending, CommitState::Pending),
+            (JobStatus::Running, CommitState::Pending),
+            (JobStatus::Success, CommitState::Success),
+            (JobStatus::Failed, CommitState::Failure),
+            (JobStatus::Cancelled, CommitState::Error),
+        ] {
+            let commit_status = CommitStatus::for_job(&job(status), None);
+            assert_eq!(commit_status.state, state, "job {}", status);
+            assert_eq!(commit_status.context, STATUS_CONTEXT);
+        }
+
+        let commit_status = CommitStatus::for_job(
+            &job(JobStatus::Pending),
+            Some(job_url("https://ci.example.com/", "job-1")),
+        );
+        assert_eq!(commit_status.description, "Queued");
+        assert_eq!(
+            serde_json::to_value(&commit_status).unwrap(),
+            serde_json::json!({
+                "state": "pending",
+                "target_url": "https://ci.example.com/api/jobs/job-1",
+                "description": "Queued",
+                "context": "raibid-ci"
+            })
+        );
+    }
+
+    #[test]
+    fn test_github_repository() {
+        for url in [
+            "https://github.com/owner/repo.git",
+            "https://github.com/owner/repo",
+            "git@github.com:owner/repo.git",
+        ] {
+            assert_eq!(
+                github_repository(url).as_deref(),
+                Some("owner/repo"),
+                "{}",
+                url
+            );
+        }
+
+        assert!(github_repository("https://gitlab.com/owner/repo.git").is_none());
+        assert!(github_repository("https://github.com/owner").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_report_without_gitea_is_noop() {
+        let reporter = CommitStatusReporter::default();
+        assert!(!reporter.is_enabled());
+        reporter.report(&job(JobStatus::Success)).await;
+    }
+}
@@ -176,10 +176,16 @@ pub fn substitute_env_vars(mut config: Config) -> Result<Config> {
         config.redis.password = Some(substitute(password)?);
     }
     config.redis.job_stream = substitute(config.redis.job_stream)?;
+    config.redis.consumer_group = substitute(config.redis.consumer_group)?;
 
     // Substitute in UI config
     config.ui.color_scheme = substitute(config.ui.color_scheme)?;
 
+    // Substitute in webhook secrets
+    for secret in config.mirroring.webhooks.repository_secrets.values_mut() {
+        *secret = substitute(std::mem::take(secret))?;
+    }
+
     Ok(config)
 }
 
@@ -260,6 +266,12 @@ pub fn apply_env_overrides(mut config: Config) -> Result<Config> {
     if let Ok(val) = env::var("RAIBID_REDIS_DATABASE") {
         config.redis.database = val.parse().context("Invalid RAIBID_REDIS_DATABASE")?;
     }
+    if let Ok(val) = env::var("RAIBID_REDIS_JOB_STREAM") {
+        config.redis.job_stream = val;
+    }
+    if let Ok(val) = env::var("RAIBID_REDIS_CONSUMER_GROUP") {
+        config.redis.consumer_group = val;
+    }
 
     // UI overrides
     if let Ok(val) = env::var("RAIBID_UI_TUI_ENABLED") {
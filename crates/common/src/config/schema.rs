@@ -4,6 +4,7 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// Main configuration structure
@@ -184,6 +185,23 @@ pub struct RedisConfig {
     /// Job queue stream name
     #[serde(default = "default_job_stream")]
     pub job_stream: String,
+
+    /// Consumer group agents use to read the job queue
+    #[serde(default = "default_consumer_group")]
+    pub consumer_group: String,
+}
+
+impl RedisConfig {
+    /// Get Redis connection URL
+    pub fn connection_url(&self) -> String {
+        match self.password {
+            Some(ref pwd) => format!(
+                "redis://:{}@{}:{}/{}",
+                pwd, self.host, self.port, self.database
+            ),
+            None => format!("redis://{}:{}/{}", self.host, self.port, self.database),
+        }
+    }
 }
 
 /// UI configuration
@@ -309,6 +327,11 @@ pub struct WebhookConfig {
     #[serde(default)]
     pub secret: Option<String>,
 
+    /// Per-repository webhook secrets keyed by full name (e.g. "owner/repo"),
+    /// taking precedence over `secret`
+    #[serde(default)]
+    pub repository_secrets: BTreeMap<String, String>,
+
     /// Webhook endpoint URL (will be auto-configured if not specified)
     #[serde(default)]
     pub endpoint_url: Option<String>,
@@ -392,7 +415,11 @@ fn default_redis_port() -> u16 {
 }
 
 fn default_job_stream() -> String {
-    "raibid:jobs".to_string()
+    crate::queue::DEFAULT_JOB_STREAM.to_string()
+}
+
+fn default_consumer_group() -> String {
+    crate::queue::DEFAULT_CONSUMER_GROUP.to_string()
 }
 
 fn default_refresh_rate_ms() -> u32 {
@@ -478,6 +505,7 @@ impl Default for RedisConfig {
             password: None,
             database: 0,
             job_stream: default_job_stream(),
+            consumer_group: default_consumer_group(),
         }
     }
 }
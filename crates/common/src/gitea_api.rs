@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
+use crate::commit_status::CommitStatus;
 use crate::config::GiteaConfig;
 use crate::github::GitHubRepository;
 
@@ -29,6 +30,22 @@ pub struct GiteaRepository {
     pub mirror: bool,
     pub clone_url: String,
     pub html_url: String,
+    /// URL the repository is mirrored from, if it's a mirror
+    #[serde(default)]
+    pub original_url: Option<String>,
+}
+
+/// Gitea branch information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiteaBranch {
+    pub name: String,
+    pub commit: GiteaBranchCommit,
+}
+
+/// Head commit of a Gitea branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiteaBranchCommit {
+    pub id: String,
 }
 
 /// Gitea organization information
@@ -136,6 +153,93 @@ impl GiteaClient {
         Ok(repo)
     }
 
+    /// Get branch information, or `None` if the branch does not exist
+    pub async fn get_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Option<GiteaBranch>> {
+        let url = format!(
+            "{}/repos/{}/{}/branches/{}",
+            self.api_url(),
+            owner,
+            repo,
+            branch
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch branch")?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Gitea API error: {} - {}",
+                response.status(),
+                response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "unknown error".to_string())
+            ));
+        }
+
+        let branch: GiteaBranch = response
+            .json()
+            .await
+            .context("Failed to parse branch response")?;
+
+        Ok(Some(branch))
+    }
+
+    /// Set the status of a commit
+    pub async fn create_commit_status(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+        status: &CommitStatus,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/statuses/{}",
+            self.api_url(),
+            owner,
+            repo,
+            sha
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .json(status)
+            .send()
+            .await
+            .context("Failed to create commit status")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Gitea API error: {} - {}",
+                response.status(),
+                response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "unknown error".to_string())
+            ));
+        }
+
+        debug!(
+            "Set status of {}/{}@{} to {:?}",
+            owner, repo, sha, status.state
+        );
+        Ok(())
+    }
+
     /// Create a repository mirror
     pub async fn create_mirror(
         &self,
@@ -10,8 +10,12 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
+use crate::commit_status::CommitStatus;
 use crate::config::{GitHubConfig, OrganizationMirrorConfig};
 
+/// github.com API URL
+const GITHUB_API_URL: &str = "https://api.github.com";
+
 /// GitHub API client
 #[derive(Clone)]
 pub struct GitHubClient {
@@ -70,7 +74,22 @@ struct CreateWebhookRequest {
     config: WebhookConfig,
 }
 
+/// Request to update a webhook's configuration
+#[derive(Debug, Clone, Serialize)]
+struct UpdateWebhookRequest {
+    config: WebhookConfig,
+}
+
 impl GitHubClient {
+    /// Create a client for github.com authenticated with a token
+    pub fn from_token(token: String) -> Result<Self> {
+        Self::new(GitHubConfig {
+            api_url: GITHUB_API_URL.to_string(),
+            token: Some(token),
+            rate_limit_threshold: 100,
+        })
+    }
+
     /// Create a new GitHub API client
     pub fn new(config: GitHubConfig) -> Result<Self> {
         let mut headers = HeaderMap::new();
@@ -395,6 +414,101 @@ impl GitHubClient {
         Ok(webhook)
     }
 
+    /// Replace the secret of an existing webhook
+    pub async fn update_webhook_secret(
+        &self,
+        owner: &str,
+        repo: &str,
+        hook_id: u64,
+        webhook_url: &str,
+        secret: String,
+    ) -> Result<()> {
+        info!(
+            "Updating secret of webhook {} for {}/{}",
+            hook_id, owner, repo
+        );
+
+        let request = UpdateWebhookRequest {
+            config: WebhookConfig {
+                url: webhook_url.to_string(),
+                content_type: "json".to_string(),
+                secret: Some(secret),
+                insecure_ssl: Some("0".to_string()),
+            },
+        };
+
+        let url = format!(
+            "{}/repos/{}/{}/hooks/{}",
+            self.config.api_url, owner, repo, hook_id
+        );
+
+        let response = self
+            .client
+            .patch(&url)
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to update webhook")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "unknown error".to_string());
+
+            return Err(anyhow!(
+                "Failed to update webhook {} for {}/{}: {} - {}",
+                hook_id,
+                owner,
+                repo,
+                status,
+                error_text
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Set the status of a commit
+    pub async fn create_commit_status(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+        status: &CommitStatus,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/statuses/{}",
+            self.config.api_url, owner, repo, sha
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .json(status)
+            .send()
+            .await
+            .context("Failed to create commit status")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "GitHub API error: {} - {}",
+                response.status(),
+                response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "unknown error".to_string())
+            ));
+        }
+
+        debug!(
+            "Set status of {}/{}@{} to {:?}",
+            owner, repo, sha, status.state
+        );
+        Ok(())
+    }
+
     /// Check if a webhook already exists for a given URL
     pub async fn webhook_exists(&self, owner: &str, repo: &str, webhook_url: &str) -> Result<bool> {
         let webhooks = self.list_webhooks(owner, repo).await?;
@@ -403,6 +517,9 @@ impl GitHubClient {
     }
 
     /// Ensure a webhook exists (create if needed)
+    ///
+    /// When a secret is given, an existing webhook for the URL has its secret
+    /// replaced, so a rotated secret reaches GitHub.
     pub async fn ensure_webhook(
         &self,
         owner: &str,
@@ -410,13 +527,23 @@ impl GitHubClient {
         webhook_url: &str,
         secret: Option<String>,
     ) -> Result<()> {
-        if self.webhook_exists(owner, repo, webhook_url).await? {
-            debug!("Webhook already exists for {}/{}", owner, repo);
-            return Ok(());
+        let webhooks = self.list_webhooks(owner, repo).await?;
+        let existing = webhooks.iter().find(|w| w.config.url == webhook_url);
+
+        match (existing, secret) {
+            (Some(webhook), Some(secret)) => {
+                self.update_webhook_secret(owner, repo, webhook.id, webhook_url, secret)
+                    .await?;
+            }
+            (Some(_), None) => {
+                debug!("Webhook already exists for {}/{}", owner, repo);
+            }
+            (None, secret) => {
+                self.create_webhook(owner, repo, webhook_url, secret)
+                    .await?;
+            }
         }
 
-        self.create_webhook(owner, repo, webhook_url, secret)
-            .await?;
         Ok(())
     }
 }
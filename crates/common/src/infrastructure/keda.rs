@@ -56,8 +56,8 @@ impl Default for ScaledObjectConfig {
         Self {
             name: "raibid-ci-agent-scaler".to_string(),
             namespace: "raibid-ci".to_string(),
-            stream_name: "raibid:jobs".to_string(),
-            consumer_group: "raibid-workers".to_string(),
+            stream_name: crate::queue::DEFAULT_JOB_STREAM.to_string(),
+            consumer_group: crate::queue::DEFAULT_CONSUMER_GROUP.to_string(),
             redis_address: "raibid-redis-master.raibid-redis.svc.cluster.local:6379".to_string(),
             pending_entries_count: "1".to_string(),
             min_replica_count: 0, // Scale to zero
@@ -24,16 +24,15 @@ pub struct RedisStreamsConfig {
     /// Consumer group name
     pub consumer_group: String,
     /// Max length of stream (0 = unlimited)
-    #[allow(dead_code)]
     pub max_length: u64,
 }
 
 impl Default for RedisStreamsConfig {
     fn default() -> Self {
         Self {
-            queue_stream: "raibid:jobs".to_string(),
-            consumer_group: "raibid-workers".to_string(),
-            max_length: 10000, // Keep last 10k jobs
+            queue_stream: crate::queue::DEFAULT_JOB_STREAM.to_string(),
+            consumer_group: crate::queue::DEFAULT_CONSUMER_GROUP.to_string(),
+            max_length: crate::queue::DEFAULT_JOB_STREAM_MAX_LENGTH as u64,
         }
     }
 }
@@ -369,34 +368,17 @@ commonConfiguration: |-
 
         let conn_info = self.get_connection_info()?;
         let pod_name = self.get_master_pod_name()?;
-
-        // Create consumer group using kubectl exec
-        let group_cmd = if self.config.auth_enabled {
-            format!(
-                "redis-cli -a {} XGROUP CREATE {} {} $ MKSTREAM",
-                conn_info.password.as_ref().unwrap_or(&String::new()),
-                self.config.streams_config.queue_stream,
-                self.config.streams_config.consumer_group,
-            )
-        } else {
-            format!(
-                "redis-cli XGROUP CREATE {} {} $ MKSTREAM",
-                self.config.streams_config.queue_stream, self.config.streams_config.consumer_group,
-            )
-        };
-
-        let output = Command::new("kubectl")
-            .arg("exec")
-            .arg("-n")
-            .arg(&self.config.namespace)
-            .arg(&pod_name)
-            .arg("--")
-            .arg("sh")
-            .arg("-c")
-            .arg(&group_cmd)
-            .env("KUBECONFIG", &self.config.kubeconfig_path)
-            .output()
-            .context("Failed to create consumer group")?;
+        let streams = &self.config.streams_config;
+
+        // Create consumer group
+        let output = self.redis_cli(
+            &pod_name,
+            conn_info.password.as_deref(),
+            &format!(
+                "XGROUP CREATE {} {} $ MKSTREAM",
+                streams.queue_stream, streams.consumer_group
+            ),
+        )?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -407,10 +389,56 @@ commonConfiguration: |-
             debug!("Consumer group already exists");
         }
 
+        // Cap a stream left over from an earlier installation
+        if streams.max_length > 0 {
+            let output = self.redis_cli(
+                &pod_name,
+                conn_info.password.as_deref(),
+                &format!(
+                    "XTRIM {} MAXLEN ~ {}",
+                    streams.queue_stream, streams.max_length
+                ),
+            )?;
+            if !output.status.success() {
+                warn!(
+                    "Failed to trim job stream: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+        }
+
         info!("Redis Streams initialized");
         Ok(())
     }
 
+    /// Run a redis-cli command in the Redis pod using kubectl exec
+    fn redis_cli(
+        &self,
+        pod_name: &str,
+        password: Option<&str>,
+        command: &str,
+    ) -> Result<std::process::Output> {
+        let cli_cmd = match password {
+            Some(password) if self.config.auth_enabled => {
+                format!("redis-cli -a {} {}", password, command)
+            }
+            _ => format!("redis-cli {}", command),
+        };
+
+        Command::new("kubectl")
+            .arg("exec")
+            .arg("-n")
+            .arg(&self.config.namespace)
+            .arg(pod_name)
+            .arg("--")
+            .arg("sh")
+            .arg("-c")
+            .arg(&cli_cmd)
+            .env("KUBECONFIG", &self.config.kubeconfig_path)
+            .output()
+            .with_context(|| format!("Failed to run redis-cli {}", command))
+    }
+
     /// Get master pod name
     fn get_master_pod_name(&self) -> Result<String> {
         let output = Command::new("kubectl")
@@ -446,13 +474,11 @@ commonConfiguration: |-
         let pod_name = self.get_master_pod_name()?;
 
         // Test connection with PING
-        let ping_cmd = if self.config.auth_enabled && self.config.password.is_some() {
-            format!(
-                "redis-cli -a {} PING",
-                self.config.password.as_ref().unwrap()
-            )
-        } else {
-            "redis-cli PING".to_string()
+        let ping_cmd = match self.config.password.as_ref() {
+            Some(password) if self.config.auth_enabled => {
+                format!("redis-cli -a {} PING", password)
+            }
+            _ => "redis-cli PING".to_string(),
         };
 
         let output = Command::new("kubectl")
@@ -7,7 +7,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Job execution status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum JobStatus {
     /// Job is waiting to be executed
@@ -98,15 +98,103 @@ pub struct Job {
     pub agent_id: Option<String>,
     /// Exit code (if finished)
     pub exit_code: Option<i32>,
+    /// Number of times an agent has started the job
+    #[serde(default)]
+    pub attempts: u32,
+    /// Pull request the job builds, if it was triggered by one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_request: Option<PullRequest>,
+    /// Git tag the job builds; tag builds are release builds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+/// Pull request built by a job
+///
+/// The job's `branch` and `commit` are the pull request's head; `repo` is the
+/// repository the pull request was opened against.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PullRequest {
+    /// Pull request number
+    pub number: u64,
+    /// Branch the pull request merges into
+    pub base_branch: String,
+    /// Repository the head branch lives in (differs from the job's repo for forks)
+    pub head_repo: String,
+    /// Ref the job's repo publishes the head commit under (e.g. `refs/pull/42/head`)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub head_ref: String,
+}
+
+impl PullRequest {
+    /// Ref to fetch the head commit from in the job's repo
+    ///
+    /// Falls back to GitHub/Gitea's `refs/pull/{number}/head` for jobs queued
+    /// before the ref was recorded.
+    pub fn fetch_ref(&self) -> String {
+        if self.head_ref.is_empty() {
+            format!("refs/pull/{}/head", self.number)
+        } else {
+            self.head_ref.clone()
+        }
+    }
 }
 
 impl Job {
+    /// Create a pending job for a repository revision
+    pub fn new(
+        id: impl Into<String>,
+        repo: impl Into<String>,
+        branch: impl Into<String>,
+        commit: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            repo: repo.into(),
+            branch: branch.into(),
+            commit: commit.into(),
+            status: JobStatus::Pending,
+            started_at: Utc::now(),
+            finished_at: None,
+            duration: None,
+            agent_id: None,
+            exit_code: None,
+            attempts: 0,
+            pull_request: None,
+            tag: None,
+        }
+    }
+
+    /// Check whether the job is a release build (triggered by a tag push)
+    pub fn is_release(&self) -> bool {
+        self.tag.is_some()
+    }
+
     /// Calculate duration in seconds
     pub fn calculate_duration(&self) -> Option<u64> {
         self.finished_at
             .map(|finished| (finished - self.started_at).num_seconds() as u64)
     }
 
+    /// Record that an agent has picked up the job
+    pub fn mark_running(&mut self, agent_id: impl Into<String>) {
+        self.attempts += 1;
+        self.status = JobStatus::Running;
+        self.agent_id = Some(agent_id.into());
+        self.started_at = Utc::now();
+        self.finished_at = None;
+        self.duration = None;
+        self.exit_code = None;
+    }
+
+    /// Record that the job reached a terminal state
+    pub fn mark_finished(&mut self, status: JobStatus, exit_code: Option<i32>) {
+        self.status = status;
+        self.finished_at = Some(Utc::now());
+        self.exit_code = exit_code;
+        self.duration = self.calculate_duration();
+    }
+
     /// Get human-readable duration string
     pub fn duration_string(&self) -> String {
         match self.calculate_duration().or(self.duration) {
@@ -124,7 +212,7 @@ impl Job {
 }
 
 /// Format duration in seconds to human-readable string
-fn format_duration(secs: u64) -> String {
+pub fn format_duration(secs: u64) -> String {
     if secs < 60 {
         format!("{}s", secs)
     } else if secs < 3600 {
@@ -149,6 +237,18 @@ pub struct JobListQuery {
     pub offset: Option<usize>,
 }
 
+impl JobListQuery {
+    /// Check whether a job matches the query filters
+    pub fn matches(&self, job: &Job) -> bool {
+        self.status.is_none_or(|status| job.status == status)
+            && self.repo.as_ref().is_none_or(|repo| &job.repo == repo)
+            && self
+                .branch
+                .as_ref()
+                .is_none_or(|branch| &job.branch == branch)
+    }
+}
+
 /// Job list response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobList {
@@ -173,13 +273,154 @@ pub struct JobTrigger {
     pub commit: Option<String>,
 }
 
-/// Job log entry
+/// Result of a single pipeline step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepResult {
+    /// Step name
+    pub step: String,
+    /// Whether the step succeeded
+    pub success: bool,
+    /// Exit code
+    pub exit_code: Option<i32>,
+    /// Duration in seconds
+    pub duration_secs: u64,
+    /// Captured stdout/stderr (first 10KB)
+    pub output: String,
+}
+
+/// Job step results response
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSteps {
+    /// Job ID
+    pub job_id: String,
+    /// Step results in execution order
+    pub steps: Vec<StepResult>,
+}
+
+/// Output a log line came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    /// Standard output of a step's command
+    Stdout,
+    /// Standard error of a step's command
+    Stderr,
+    /// Written by the agent itself (step boundaries, commands being run)
+    #[default]
+    System,
+}
+
+/// Severity of a log line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// Regular output
+    #[default]
+    Info,
+    /// Something went wrong but the job carries on (e.g. a step allowed to fail)
+    Warn,
+    /// A step failed or timed out
+    Error,
+}
+
+/// Job log entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct JobLogEntry {
+    /// Stream ID of the entry, set when read back from the log stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Line number within the job's log, counting from 1
+    #[serde(default)]
+    pub line: u64,
     /// Timestamp of the log entry
     pub timestamp: DateTime<Utc>,
-    /// Log message
+    /// Pipeline step that produced the line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+    /// Output the line came from
+    #[serde(default)]
+    pub stream: LogStream,
+    /// Severity of the line
+    #[serde(default)]
+    pub level: LogLevel,
+    /// Log message, as written by the command
     pub message: String,
+    /// Message without ANSI escape sequences, if it contained any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plain: Option<String>,
+}
+
+impl JobLogEntry {
+    /// Create a log entry timestamped now
+    pub fn new(
+        line: u64,
+        step: Option<&str>,
+        stream: LogStream,
+        level: LogLevel,
+        message: impl Into<String>,
+    ) -> Self {
+        let message = message.into();
+        Self {
+            id: None,
+            line,
+            timestamp: Utc::now(),
+            step: step.map(str::to_string),
+            stream,
+            level,
+            plain: strip_ansi(&message),
+            message,
+        }
+    }
+
+    /// Get the message without ANSI escape sequences
+    pub fn plain_message(&self) -> &str {
+        self.plain.as_deref().unwrap_or(&self.message)
+    }
+}
+
+/// Remove ANSI escape sequences (colours, cursor movement) from a line
+///
+/// Returns `None` if the line contains none, so plain lines aren't stored twice.
+pub fn strip_ansi(line: &str) -> Option<String> {
+    if !line.contains('\x1b') {
+        return None;
+    }
+
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            plain.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: parameters and intermediates up to a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC (e.g. hyperlinks): up to BEL or ST (ESC \)
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Other escapes are two characters long
+            _ => {}
+        }
+    }
+
+    Some(plain)
 }
 
 /// Job logs response
@@ -225,10 +466,94 @@ mod tests {
         assert!(JobStatus::Cancelled.is_terminal());
     }
 
+    fn sample_job() -> Job {
+        Job::new("job-1", "owner/repo", "main", "abc123")
+    }
+
+    #[test]
+    fn test_job_transitions() {
+        let mut job = sample_job();
+
+        job.mark_running("agent-1");
+        assert_eq!(job.status, JobStatus::Running);
+        assert_eq!(job.agent_id.as_deref(), Some("agent-1"));
+        assert_eq!(job.attempts, 1);
+        assert!(job.finished_at.is_none());
+
+        // A reclaimed job records another attempt
+        job.mark_running("agent-2");
+        assert_eq!(job.attempts, 2);
+
+        job.mark_finished(JobStatus::Success, Some(0));
+        assert_eq!(job.status, JobStatus::Success);
+        assert_eq!(job.exit_code, Some(0));
+        assert!(job.finished_at.is_some());
+        assert!(job.duration.is_some());
+    }
+
+    #[test]
+    fn test_job_list_query_matches() {
+        let job = sample_job();
+
+        assert!(JobListQuery::default().matches(&job));
+        assert!(JobListQuery {
+            status: Some(JobStatus::Pending),
+            repo: Some("owner/repo".to_string()),
+            ..Default::default()
+        }
+        .matches(&job));
+        assert!(!JobListQuery {
+            branch: Some("develop".to_string()),
+            ..Default::default()
+        }
+        .matches(&job));
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(format_duration(30), "30s");
         assert_eq!(format_duration(90), "1m 30s");
         assert_eq!(format_duration(3661), "1h 1m");
     }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("plain output"), None);
+        assert_eq!(
+            strip_ansi("\x1b[1m\x1b[32m   Compiling\x1b[0m raibid v0.1.0").as_deref(),
+            Some("   Compiling raibid v0.1.0")
+        );
+        assert_eq!(
+            strip_ansi("see \x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x07").as_deref(),
+            Some("see docs")
+        );
+    }
+
+    #[test]
+    fn test_log_entry_serialization() {
+        let entry = JobLogEntry::new(
+            3,
+            Some("clippy"),
+            LogStream::Stderr,
+            LogLevel::Info,
+            "\x1b[33mwarning\x1b[0m: unused variable",
+        );
+        assert_eq!(entry.plain_message(), "warning: unused variable");
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["line"], 3);
+        assert_eq!(json["step"], "clippy");
+        assert_eq!(json["stream"], "stderr");
+        assert_eq!(json["level"], "info");
+        assert!(json.get("id").is_none());
+
+        // Entries written before lines had metadata
+        let entry: JobLogEntry = serde_json::from_str(
+            r#"{"timestamp": "2025-11-03T12:00:01Z", "message": "Starting build..."}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.step, None);
+        assert_eq!(entry.stream, LogStream::System);
+        assert_eq!(entry.plain_message(), "Starting build...");
+    }
 }
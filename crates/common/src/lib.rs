@@ -5,17 +5,51 @@
 //! - Configuration management
 //! - Infrastructure deployment and management (k3s, Gitea, Flux, Redis, KEDA)
 //! - Job types and data structures
+//! - Agent registry types
+//! - Job queue wire format
+//! - Job queue metrics
+//! - Repository pipeline definitions (`.raibid.yml`)
+//! - Job record storage and retention
+//! - Job log archival and object storage
+//! - Job artifact types
+//! - Commit status reporting
+//! - Webhook delivery log types
 //! - Shared error types
 //! - Utility functions
 
+pub mod agents;
+pub mod artifacts;
+pub mod commit_status;
 pub mod config;
 pub mod gitea_api;
 pub mod github;
 pub mod infrastructure;
 pub mod jobs;
+pub mod logs;
+pub mod metrics;
 pub mod mirroring;
+pub mod pipeline;
+pub mod queue;
+pub mod retention;
+pub mod storage;
+pub mod store;
+pub mod webhooks;
 
 // Re-export commonly used types
+pub use agents::{AgentInfo, AgentList, AgentStatus};
+pub use artifacts::{Artifact, JobArtifacts};
 pub use config::Config;
 pub use infrastructure::error::InfraError;
-pub use jobs::{Job, JobList, JobListQuery, JobLogEntry, JobLogs, JobStatus, JobTrigger};
+pub use jobs::{
+    Job, JobList, JobListQuery, JobLogEntry, JobLogs, JobStatus, JobSteps, JobTrigger, LogLevel,
+    LogStream, PullRequest, StepResult,
+};
+pub use metrics::QueueMetrics;
+pub use pipeline::{PipelineDefinition, PipelineStep};
+pub use queue::JobEnvelope;
+pub use retention::{PruneReport, RetentionPolicy};
+pub use store::{
+    AgentRegistry, DeliveryLog, InMemoryAgentRegistry, InMemoryDeliveryLog, InMemoryJobStore,
+    JobStore, RedisAgentRegistry, RedisDeliveryLog, RedisJobStore,
+};
+pub use webhooks::{DeliveryOutcome, WebhookDelivery, WebhookDeliveryList};
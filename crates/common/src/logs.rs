@@ -0,0 +1,237 @@
+//! Job log storage
+//!
+//! While a job runs its log lives in a Redis stream (see [`log_stream_key`]).
+//! Once it has finished, the agent archives the complete log to the object
+//! store as gzip-compressed JSON lines, so it survives Redis being lost, and
+//! only then trims the stream.
+
+use anyhow::{Context, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use redis::aio::MultiplexedConnection;
+use redis::streams::{StreamId, StreamRangeReply};
+use redis::AsyncCommands;
+use std::io::{BufRead, BufReader, Write};
+
+use crate::jobs::{JobLogEntry, LogLevel, LogStream};
+use crate::queue::{log_stream_key, LOG_ENTRY_FIELD};
+use crate::storage::ObjectStore;
+
+/// Maximum number of entries read from a log stream at once
+const READ_PAGE_SIZE: usize = 1000;
+
+/// Get the object store key a job's log is archived under
+pub fn log_archive_key(job_id: &str) -> String {
+    format!("logs/{}.jsonl.gz", job_id)
+}
+
+/// Parse a log stream entry, with its stream ID as `id`
+///
+/// Returns `None` for malformed entries.
+pub fn parse_log_entry(entry: &StreamId) -> Option<JobLogEntry> {
+    let mut log_entry = match entry.get::<String>(LOG_ENTRY_FIELD) {
+        Some(data) => serde_json::from_str(&data).ok()?,
+        None => {
+            // Written by an agent from before lines had metadata
+            let mut log_entry = JobLogEntry::new(
+                0,
+                None,
+                LogStream::System,
+                LogLevel::Info,
+                entry.get::<String>("message")?,
+            );
+            log_entry.timestamp = entry.get::<String>("timestamp")?.parse().ok()?;
+            log_entry
+        }
+    };
+
+    log_entry.id = Some(entry.id.clone());
+    Some(log_entry)
+}
+
+/// Read a job's whole log from its Redis stream
+pub async fn read_log(conn: &mut MultiplexedConnection, job_id: &str) -> Result<Vec<JobLogEntry>> {
+    let stream_key = log_stream_key(job_id);
+    let mut entries = Vec::new();
+    let mut start = "-".to_string();
+
+    loop {
+        let reply: StreamRangeReply = conn
+            .xrange_count(&stream_key, &start, "+", READ_PAGE_SIZE)
+            .await
+            .with_context(|| format!("Failed to read log of job {}", job_id))?;
+
+        let Some(last) = reply.ids.last() else {
+            break;
+        };
+        start = format!("({}", last.id);
+
+        let exhausted = reply.ids.len() < READ_PAGE_SIZE;
+        entries.extend(reply.ids.iter().filter_map(parse_log_entry));
+        if exhausted {
+            break;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Compress log entries into an archive
+pub fn encode_archive(entries: &[JobLogEntry]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for entry in entries {
+        serde_json::to_writer(&mut encoder, entry).context("Failed to serialize log entry")?;
+        encoder.write_all(b"\n")?;
+    }
+
+    encoder.finish().context("Failed to compress log")
+}
+
+/// Read log entries back from an archive
+pub fn decode_archive(data: &[u8]) -> Result<Vec<JobLogEntry>> {
+    BufReader::new(GzDecoder::new(data))
+        .lines()
+        .map(|line| {
+            let line = line.context("Failed to decompress log")?;
+            serde_json::from_str(&line).context("Invalid archived log entry")
+        })
+        .collect()
+}
+
+/// Archive a job's log from its Redis stream to the object store
+///
+/// Returns the number of lines archived.
+pub async fn archive_log(
+    conn: &mut MultiplexedConnection,
+    store: &dyn ObjectStore,
+    job_id: &str,
+) -> Result<usize> {
+    let entries = read_log(conn, job_id).await?;
+    store
+        .put(&log_archive_key(job_id), encode_archive(&entries)?)
+        .await
+        .with_context(|| format!("Failed to archive log of job {}", job_id))?;
+
+    Ok(entries.len())
+}
+
+/// Trim a job's log stream to about `max_length` lines (0 = unlimited)
+///
+/// Trimming drops the oldest lines, so only trim a log once it's archived.
+pub async fn trim_log(
+    conn: &mut MultiplexedConnection,
+    job_id: &str,
+    max_length: usize,
+) -> Result<()> {
+    if max_length > 0 {
+        redis::cmd("XTRIM")
+            .arg(log_stream_key(job_id))
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(max_length)
+            .query_async::<()>(conn)
+            .await
+            .with_context(|| format!("Failed to trim log of job {}", job_id))?;
+    }
+
+    Ok(())
+}
+
+/// Read a job's archived log, if it has been archived
+pub async fn read_archived_log(
+    store: &dyn ObjectStore,
+    job_id: &str,
+) -> Result<Option<Vec<JobLogEntry>>> {
+    match store.get_bytes(&log_archive_key(job_id)).await? {
+        Some(data) => Ok(Some(decode_archive(&data)?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::LocalObjectStore;
+
+    fn stream_entry(id: &str, fields: &[(&str, &str)]) -> StreamId {
+        StreamId {
+            id: id.to_string(),
+            map: fields
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.to_string(),
+                        redis::Value::BulkString(value.as_bytes().to_vec()),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_log_entry() {
+        let line = JobLogEntry::new(
+            7,
+            Some("clippy"),
+            LogStream::Stderr,
+            LogLevel::Info,
+            "warning: unused variable",
+        );
+        let data = serde_json::to_string(&line).unwrap();
+
+        let entry = parse_log_entry(&stream_entry(
+            "1700000000000-3",
+            &[(LOG_ENTRY_FIELD, data.as_str())],
+        ))
+        .unwrap();
+        assert_eq!(entry.id.as_deref(), Some("1700000000000-3"));
+        assert_eq!(entry.line, 7);
+        assert_eq!(entry.step.as_deref(), Some("clippy"));
+        assert_eq!(entry.stream, LogStream::Stderr);
+
+        let entry = parse_log_entry(&stream_entry(
+            "1700000000000-4",
+            &[
+                ("timestamp", "2025-11-03T12:00:01+00:00"),
+                ("message", "Compiling raibid"),
+            ],
+        ))
+        .unwrap();
+        assert_eq!(entry.id.as_deref(), Some("1700000000000-4"));
+        assert_eq!(entry.message, "Compiling raibid");
+        assert_eq!(entry.step, None);
+
+        assert!(
+            parse_log_entry(&stream_entry("1700000000000-5", &[(LOG_ENTRY_FIELD, "{")])).is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_archive_round_trip() {
+        let entries: Vec<JobLogEntry> = (1..=3)
+            .map(|line| {
+                let mut entry = JobLogEntry::new(
+                    line,
+                    Some("test"),
+                    LogStream::Stdout,
+                    LogLevel::Info,
+                    format!("\x1b[32mline {}\x1b[0m", line),
+                );
+                entry.id = Some(format!("1700000000000-{}", line));
+                entry
+            })
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalObjectStore::new(dir.path());
+        assert!(read_archived_log(&store, "job-1").await.unwrap().is_none());
+
+        store
+            .put(&log_archive_key("job-1"), encode_archive(&entries).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            read_archived_log(&store, "job-1").await.unwrap(),
+            Some(entries)
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Job queue metrics
+//!
+//! Snapshot of the Redis Streams job queue as reported by `GET /metrics/queue`.
+//! The server fills it from `XINFO` and `XPENDING`; the TUI and capacity planning
+//! read it back.
+
+use serde::{Deserialize, Serialize};
+
+/// Default window for throughput figures (15 minutes)
+pub const DEFAULT_THROUGHPUT_WINDOW_MINUTES: u64 = 15;
+
+/// Longest accepted throughput window (one day)
+pub const MAX_THROUGHPUT_WINDOW_MINUTES: u64 = 24 * 60;
+
+/// State of the job queue
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueueMetrics {
+    /// Job queue stream
+    pub stream: String,
+
+    /// Consumer group agents read from
+    pub consumer_group: String,
+
+    /// Entries in the stream, including acknowledged ones not yet trimmed
+    pub length: u64,
+
+    /// Entries not yet delivered to any agent, if known
+    pub lag: Option<u64>,
+
+    /// Entries delivered to an agent but not yet acknowledged
+    pub pending: u64,
+
+    /// Pending entries per consumer
+    pub consumers: Vec<ConsumerMetrics>,
+
+    /// Age in seconds of the oldest pending entry
+    pub oldest_pending_age_secs: Option<u64>,
+
+    /// Jobs enqueued and completed in the throughput window
+    pub throughput: Throughput,
+}
+
+/// Pending entries held by one consumer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConsumerMetrics {
+    /// Consumer (agent) name
+    pub name: String,
+
+    /// Entries delivered to this consumer but not yet acknowledged
+    pub pending: u64,
+
+    /// Milliseconds since the consumer last interacted with the group
+    pub idle_ms: u64,
+}
+
+/// Queue throughput over a recent window
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Throughput {
+    /// Window length in minutes
+    pub window_minutes: u64,
+
+    /// Entries added to the stream during the window
+    pub enqueued: u64,
+
+    /// Entries added during the window that agents have acknowledged
+    pub completed: u64,
+
+    /// Completed entries per minute
+    pub per_minute: f64,
+}
+
+impl QueueMetrics {
+    /// Create metrics for an empty queue
+    pub fn empty(
+        stream: impl Into<String>,
+        consumer_group: impl Into<String>,
+        window_minutes: u64,
+    ) -> Self {
+        Self {
+            stream: stream.into(),
+            consumer_group: consumer_group.into(),
+            length: 0,
+            lag: Some(0),
+            pending: 0,
+            consumers: Vec::new(),
+            oldest_pending_age_secs: None,
+            throughput: Throughput::new(window_minutes, 0, 0),
+        }
+    }
+
+    /// Get the number of jobs waiting for an agent
+    pub fn depth(&self) -> u64 {
+        self.lag.unwrap_or(0)
+    }
+}
+
+impl Throughput {
+    /// Create throughput figures for a window
+    pub fn new(window_minutes: u64, enqueued: u64, completed: u64) -> Self {
+        let per_minute = if window_minutes == 0 {
+            0.0
+        } else {
+            completed as f64 / window_minutes as f64
+        };
+
+        Self {
+            window_minutes,
+            enqueued,
+            completed,
+            per_minute,
+        }
+    }
+}
+
+/// Parse a stream entry ID (`<millis>-<seq>`) into comparable parts
+pub fn parse_stream_id(id: &str) -> Option<(u64, u64)> {
+    let (millis, seq) = id.split_once('-').unwrap_or((id, "0"));
+    Some((millis.parse().ok()?, seq.parse().ok()?))
+}
+
+/// Count window entries that have been delivered and acknowledged
+///
+/// An entry is complete once the group has read past it and it is no longer in the
+/// pending entries list.
+pub fn count_completed<'a>(
+    window_ids: impl IntoIterator<Item = &'a str>,
+    last_delivered_id: &str,
+    pending_ids: &[&str],
+) -> u64 {
+    let Some(last_delivered) = parse_stream_id(last_delivered_id) else {
+        return 0;
+    };
+
+    window_ids
+        .into_iter()
+        .filter(|id| parse_stream_id(id).is_some_and(|parsed| parsed <= last_delivered))
+        .filter(|id| !pending_ids.contains(id))
+        .count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream_id() {
+        assert_eq!(parse_stream_id("1700000000000-3"), Some((1700000000000, 3)));
+        assert_eq!(parse_stream_id("1700000000000"), Some((1700000000000, 0)));
+        assert_eq!(parse_stream_id("0-0"), Some((0, 0)));
+        assert_eq!(parse_stream_id("bogus"), None);
+    }
+
+    #[test]
+    fn test_count_completed() {
+        let window = ["100-0", "100-1", "200-0", "300-0"];
+
+        // 300-0 not delivered yet, 100-1 still running
+        assert_eq!(count_completed(window, "200-0", &["100-1"]), 2);
+        assert_eq!(count_completed(window, "0-0", &[]), 0);
+        assert_eq!(count_completed(window, "300-0", &[]), 4);
+    }
+
+    #[test]
+    fn test_throughput() {
+        let throughput = Throughput::new(15, 40, 30);
+        assert_eq!(throughput.per_minute, 2.0);
+        assert_eq!(Throughput::new(0, 1, 1).per_minute, 0.0);
+    }
+
+    #[test]
+    fn test_depth() {
+        let mut metrics = QueueMetrics::empty("raibid:jobs", "raibid-workers", 15);
+        assert_eq!(metrics.depth(), 0);
+
+        metrics.lag = Some(7);
+        metrics.pending = 2;
+        assert_eq!(metrics.depth(), 7);
+    }
+}
@@ -4,20 +4,38 @@
 //! - Fetching repositories from GitHub organizations
 //! - Creating mirrors in Gitea
 //! - Setting up webhooks for continuous sync
+//!
+//! With a [`WebhookSecretStore`], each repository's webhook delivered to the
+//! server is registered with its own generated secret, which the server looks up
+//! to verify deliveries.
 
 use anyhow::{Context, Result};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
 use std::env;
+use std::sync::Arc;
 use tracing::{info, warn};
 
 use crate::config::{MirroringConfig, OrganizationMirrorConfig};
 use crate::gitea_api::GiteaClient;
 use crate::github::GitHubClient;
+use crate::store::WebhookSecretStore;
+
+/// Length of generated per-repository webhook secrets
+const WEBHOOK_SECRET_LENGTH: usize = 40;
+
+/// Provider webhooks are registered with, as the server names it
+const WEBHOOK_PROVIDER: &str = "github";
+
+/// Path of the server's endpoint for GitHub webhooks
+const SERVER_WEBHOOK_PATH: &str = "/webhooks/github";
 
 /// Mirroring service orchestrator
 pub struct MirroringService {
     github_client: GitHubClient,
     gitea_client: GiteaClient,
     config: MirroringConfig,
+    secret_store: Option<Arc<dyn WebhookSecretStore>>,
 }
 
 /// Mirroring result summary
@@ -61,9 +79,16 @@ impl MirroringService {
             github_client,
             gitea_client,
             config,
+            secret_store: None,
         })
     }
 
+    /// Generate and store a webhook secret per repository in `secret_store`
+    pub fn with_secret_store(mut self, secret_store: Arc<dyn WebhookSecretStore>) -> Self {
+        self.secret_store = Some(secret_store);
+        self
+    }
+
     /// Mirror all configured GitHub organizations to Gitea
     pub async fn mirror_all_organizations(&self) -> Result<MirroringResult> {
         info!("Starting organization mirroring");
@@ -345,16 +370,15 @@ impl MirroringService {
             }
 
             match self
-                .github_client
-                .ensure_webhook(owner, repo, &webhook_url, webhook_secret.clone())
+                .register_webhook(owner, repo, &webhook_url, webhook_secret.as_ref())
                 .await
             {
-                Ok(_) => {
+                Ok(()) => {
                     info!("Webhook registered for {}/{}", owner, repo);
                     registered += 1;
                 }
                 Err(e) => {
-                    warn!("Failed to register webhook for {}/{}: {}", owner, repo, e);
+                    warn!("Failed to register webhook for {}/{}: {:#}", owner, repo, e);
                     failed += 1;
                 }
             }
@@ -402,16 +426,15 @@ impl MirroringService {
 
         for (owner, repo, _gitea_repo) in mirrored_repos {
             match self
-                .github_client
-                .ensure_webhook(owner, repo, &webhook_url, webhook_secret.clone())
+                .register_webhook(owner, repo, &webhook_url, webhook_secret.as_ref())
                 .await
             {
-                Ok(_) => {
+                Ok(()) => {
                     info!("Webhook registered for {}/{}", owner, repo);
                     registered += 1;
                 }
                 Err(e) => {
-                    warn!("Failed to register webhook for {}/{}: {}", owner, repo, e);
+                    warn!("Failed to register webhook for {}/{}: {:#}", owner, repo, e);
                     failed += 1;
                 }
             }
@@ -424,6 +447,107 @@ impl MirroringService {
 
         Ok(())
     }
+
+    /// Register a repository's webhook with its secret
+    ///
+    /// A newly generated secret is only stored once the webhook has been
+    /// registered with it, so a failed registration doesn't leave the server
+    /// verifying deliveries against a secret GitHub never received.
+    async fn register_webhook(
+        &self,
+        owner: &str,
+        repo: &str,
+        webhook_url: &str,
+        global_secret: Option<&String>,
+    ) -> Result<()> {
+        let full_name = format!("{}/{}", owner, repo);
+        let secret = self
+            .repository_webhook_secret(&full_name, webhook_url, global_secret)
+            .await
+            .context("Failed to get webhook secret")?;
+
+        self.github_client
+            .ensure_webhook(owner, repo, webhook_url, secret.value())
+            .await?;
+
+        if let (WebhookSecret::Generated(secret), Some(store)) = (&secret, &self.secret_store) {
+            store
+                .set(WEBHOOK_PROVIDER, &full_name, secret)
+                .await
+                .context("Failed to store generated webhook secret")?;
+            info!("Generated webhook secret for {}", full_name);
+        }
+
+        Ok(())
+    }
+
+    /// Get the secret to register a repository's webhook with
+    ///
+    /// A secret configured for the repository wins. Otherwise the secret stored for
+    /// it is reused, or a new one is generated for the caller to store once the
+    /// webhook is registered. Secrets are only generated for webhooks delivered to
+    /// the raibid server, which is the only receiver that can look them up; other
+    /// webhooks, and any without a secret store, use the global secret.
+    async fn repository_webhook_secret(
+        &self,
+        full_name: &str,
+        webhook_url: &str,
+        global_secret: Option<&String>,
+    ) -> Result<WebhookSecret> {
+        if let Some(secret) = self.config.webhooks.repository_secrets.get(full_name) {
+            return Ok(WebhookSecret::Existing(Some(secret.clone())));
+        }
+
+        let Some(store) = self
+            .secret_store
+            .as_ref()
+            .filter(|_| targets_server(webhook_url))
+        else {
+            return Ok(WebhookSecret::Existing(global_secret.cloned()));
+        };
+
+        match store.get(WEBHOOK_PROVIDER, full_name).await? {
+            Some(secret) => Ok(WebhookSecret::Existing(Some(secret))),
+            None => Ok(WebhookSecret::Generated(generate_webhook_secret())),
+        }
+    }
+}
+
+/// Secret a repository's webhook is registered with
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WebhookSecret {
+    /// Configured or previously stored secret, if any
+    Existing(Option<String>),
+    /// Newly generated secret, not stored yet
+    Generated(String),
+}
+
+impl WebhookSecret {
+    /// Get the secret's value
+    fn value(&self) -> Option<String> {
+        match self {
+            WebhookSecret::Existing(secret) => secret.clone(),
+            WebhookSecret::Generated(secret) => Some(secret.clone()),
+        }
+    }
+}
+
+/// Check whether a webhook URL points at the raibid server's GitHub endpoint
+fn targets_server(webhook_url: &str) -> bool {
+    reqwest::Url::parse(webhook_url).is_ok_and(|url| {
+        url.path()
+            .trim_end_matches('/')
+            .ends_with(SERVER_WEBHOOK_PATH)
+    })
+}
+
+/// Generate a random webhook secret
+fn generate_webhook_secret() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(WEBHOOK_SECRET_LENGTH)
+        .map(char::from)
+        .collect()
 }
 
 #[cfg(test)]
@@ -453,4 +577,82 @@ mod tests {
         assert_eq!(parts[0], "owner");
         assert_eq!(parts[1], "repo");
     }
+
+    #[test]
+    fn test_generate_webhook_secret() {
+        let secret = generate_webhook_secret();
+        assert_eq!(secret.len(), WEBHOOK_SECRET_LENGTH);
+        assert!(secret.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(secret, generate_webhook_secret());
+    }
+
+    #[test]
+    fn test_targets_server() {
+        assert!(targets_server("https://ci.example.com/webhooks/github"));
+        assert!(targets_server(
+            "https://ci.example.com/raibid/webhooks/github/"
+        ));
+        assert!(!targets_server(
+            "http://gitea.example.com:3000/api/v1/repos/webhook"
+        ));
+        assert!(!targets_server("/webhooks/github"));
+    }
+
+    #[tokio::test]
+    async fn test_repository_webhook_secret() {
+        let mut config = MirroringConfig::default();
+        config
+            .webhooks
+            .repository_secrets
+            .insert("org/pinned".to_string(), "pinned-secret".to_string());
+        let global = "global-secret".to_string();
+        let server_url = "https://ci.example.com/webhooks/github";
+        let gitea_url = "http://gitea.example.com/api/v1/repos/webhook";
+
+        let service = MirroringService::new(config, crate::config::GiteaConfig::default()).unwrap();
+        assert_eq!(
+            service
+                .repository_webhook_secret("org/repo", server_url, Some(&global))
+                .await
+                .unwrap(),
+            WebhookSecret::Existing(Some(global.clone()))
+        );
+
+        let store = Arc::new(crate::store::InMemoryWebhookSecretStore::new());
+        let service = service.with_secret_store(store.clone());
+
+        // Generated secrets are left for the caller to store
+        let secret = service
+            .repository_webhook_secret("org/repo", server_url, Some(&global))
+            .await
+            .unwrap();
+        assert!(matches!(secret, WebhookSecret::Generated(ref s) if *s != global));
+        assert_eq!(store.get("github", "org/repo").await.unwrap(), None);
+
+        // Stored secrets are reused rather than regenerated
+        store.set("github", "org/repo", "stored").await.unwrap();
+        assert_eq!(
+            service
+                .repository_webhook_secret("org/repo", server_url, Some(&global))
+                .await
+                .unwrap(),
+            WebhookSecret::Existing(Some("stored".to_string()))
+        );
+        assert_eq!(
+            service
+                .repository_webhook_secret("org/pinned", server_url, Some(&global))
+                .await
+                .unwrap(),
+            WebhookSecret::Existing(Some("pinned-secret".to_string()))
+        );
+
+        // Only the server can look generated secrets up
+        assert_eq!(
+            service
+                .repository_webhook_secret("org/other", gitea_url, Some(&global))
+                .await
+                .unwrap(),
+            WebhookSecret::Existing(Some(global))
+        );
+    }
 }
@@ -0,0 +1,378 @@
+//! Repository pipeline definitions
+//!
+//! A repository declares its build pipeline in a checked-in `.raibid.yml`. Agents
+//! read the file after cloning; the CLI uses the same schema to validate it.
+//!
+//! ```yaml
+//! env:
+//!   CARGO_TERM_COLOR: always
+//! steps:
+//!   - name: test
+//!     commands:
+//!       - cargo test --all-features
+//!     timeout_seconds: 600
+//!   - name: audit
+//!     commands:
+//!       - cargo audit
+//!     allow_failure: true
+//!   - name: publish
+//!     commands:
+//!       - cargo publish --dry-run
+//!     branches: [main, "release/*"]
+//! artifacts:
+//!   - target/release/raibid
+//!   - "dist/*.tar.gz"
+//! ```
+//!
+//! Files matching the `artifacts` patterns are uploaded once the pipeline
+//! succeeds (see [`crate::artifacts`]).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::artifacts::is_relative_path;
+
+/// File name of the pipeline definition at the repository root
+pub const PIPELINE_FILE_NAME: &str = ".raibid.yml";
+
+/// Default timeout for a single step (5 minutes)
+pub const DEFAULT_STEP_TIMEOUT_SECS: u64 = 5 * 60;
+
+/// Default timeout for the whole pipeline (30 minutes)
+pub const DEFAULT_PIPELINE_TIMEOUT_SECS: u64 = 30 * 60;
+
+/// Pipeline declared by a repository
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineDefinition {
+    /// Environment variables set for every step
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    /// Timeout for the whole pipeline in seconds
+    #[serde(default = "default_pipeline_timeout")]
+    pub timeout_seconds: u64,
+
+    /// Steps, executed in order
+    pub steps: Vec<PipelineStep>,
+
+    /// Glob patterns, relative to the repository root, of files uploaded as
+    /// artifacts once the pipeline succeeds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+}
+
+/// A named step of a pipeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineStep {
+    /// Step name, unique within the pipeline
+    pub name: String,
+
+    /// Shell commands, run in order; the step fails at the first failing command
+    pub commands: Vec<String>,
+
+    /// Environment variables for this step (override pipeline-level ones)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    /// Step timeout in seconds
+    #[serde(default = "default_step_timeout")]
+    pub timeout_seconds: u64,
+
+    /// Don't fail the pipeline when this step fails
+    #[serde(default)]
+    pub allow_failure: bool,
+
+    /// Only run on branches matching one of these patterns (`*` matches any
+    /// characters); runs on every branch when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<String>,
+}
+
+fn default_pipeline_timeout() -> u64 {
+    DEFAULT_PIPELINE_TIMEOUT_SECS
+}
+
+fn default_step_timeout() -> u64 {
+    DEFAULT_STEP_TIMEOUT_SECS
+}
+
+impl PipelineDefinition {
+    /// Parse and validate a pipeline definition from YAML
+    pub fn from_yaml(contents: &str) -> Result<Self> {
+        // Use serde_path_to_error for better error messages
+        let deserializer = serde_yaml::Deserializer::from_str(contents);
+        let definition: Self = serde_path_to_error::deserialize(deserializer)
+            .context("Failed to parse pipeline definition")?;
+
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Load and validate a pipeline definition from a file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read pipeline file: {}", path.display()))?;
+
+        Self::from_yaml(&contents).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Load the pipeline definition checked into a repository
+    ///
+    /// Returns `None` when the repository has no `.raibid.yml`.
+    pub fn load_from_repo(repo_path: &Path) -> Result<Option<Self>> {
+        let path = repo_path.join(PIPELINE_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Self::load(&path).map(Some)
+    }
+
+    /// Validate the pipeline definition
+    pub fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            anyhow::bail!("steps cannot be empty");
+        }
+
+        if self.timeout_seconds == 0 {
+            anyhow::bail!("timeout_seconds must be greater than 0");
+        }
+
+        let mut names = HashSet::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            if step.name.trim().is_empty() {
+                anyhow::bail!("steps[{}].name cannot be empty", i);
+            }
+
+            if !names.insert(step.name.as_str()) {
+                anyhow::bail!("Duplicate step name: {}", step.name);
+            }
+
+            if step.commands.is_empty() {
+                anyhow::bail!("Step '{}' must have at least one command", step.name);
+            }
+
+            if step.commands.iter().any(|c| c.trim().is_empty()) {
+                anyhow::bail!("Step '{}' has an empty command", step.name);
+            }
+
+            if step.timeout_seconds == 0 {
+                anyhow::bail!(
+                    "Step '{}' timeout_seconds must be greater than 0",
+                    step.name
+                );
+            }
+
+            if step.branches.iter().any(|b| b.trim().is_empty()) {
+                anyhow::bail!("Step '{}' has an empty branch pattern", step.name);
+            }
+        }
+
+        for pattern in &self.artifacts {
+            if !is_relative_path(pattern) {
+                anyhow::bail!(
+                    "Artifact pattern '{}' must be relative to the repository",
+                    pattern
+                );
+            }
+
+            glob::Pattern::new(pattern)
+                .with_context(|| format!("Invalid artifact pattern '{}'", pattern))?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the pipeline with only the steps that run on `branch`
+    pub fn for_branch(&self, branch: &str) -> Self {
+        Self {
+            steps: self
+                .steps
+                .iter()
+                .filter(|step| step.runs_on(branch))
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Get the pipeline timeout
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_seconds)
+    }
+}
+
+impl PipelineStep {
+    /// Create a step with default timeout and no conditions
+    pub fn new(name: impl Into<String>, commands: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            commands,
+            env: BTreeMap::new(),
+            timeout_seconds: DEFAULT_STEP_TIMEOUT_SECS,
+            allow_failure: false,
+            branches: Vec::new(),
+        }
+    }
+
+    /// Check whether the step runs on a branch
+    pub fn runs_on(&self, branch: &str) -> bool {
+        self.branches.is_empty()
+            || self
+                .branches
+                .iter()
+                .any(|pattern| branch_matches(pattern, branch))
+    }
+
+    /// Get the step timeout
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_seconds)
+    }
+}
+
+/// Match a branch name against a pattern where `*` matches any characters
+fn branch_matches(pattern: &str, branch: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+
+    let Some(mut rest) = branch.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: exact match
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const EXAMPLE: &str = r#"
+env:
+  CARGO_TERM_COLOR: always
+steps:
+  - name: test
+    commands:
+      - cargo test
+    env:
+      RUST_BACKTRACE: "1"
+    timeout_seconds: 600
+  - name: audit
+    commands:
+      - cargo audit
+    allow_failure: true
+  - name: publish
+    commands:
+      - cargo publish --dry-run
+    branches: [main, "release/*"]
+artifacts:
+  - target/release/raibid
+"#;
+
+    #[test]
+    fn test_parse_definition() {
+        let definition = PipelineDefinition::from_yaml(EXAMPLE).unwrap();
+
+        assert_eq!(definition.steps.len(), 3);
+        assert_eq!(definition.env["CARGO_TERM_COLOR"], "always");
+        assert_eq!(definition.timeout_seconds, DEFAULT_PIPELINE_TIMEOUT_SECS);
+
+        let test = &definition.steps[0];
+        assert_eq!(test.name, "test");
+        assert_eq!(test.commands, vec!["cargo test"]);
+        assert_eq!(test.env["RUST_BACKTRACE"], "1");
+        assert_eq!(test.timeout(), Duration::from_secs(600));
+
+        let audit = &definition.steps[1];
+        assert!(audit.allow_failure);
+        assert_eq!(audit.timeout_seconds, DEFAULT_STEP_TIMEOUT_SECS);
+
+        assert_eq!(definition.artifacts, vec!["target/release/raibid"]);
+    }
+
+    #[test]
+    fn test_unknown_field_rejected() {
+        let yaml = "steps:\n  - name: test\n    command: cargo test\n";
+        assert!(PipelineDefinition::from_yaml(yaml).is_err());
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let cases = [
+            "steps: []",
+            "steps:\n  - name: test\n    commands: []\n",
+            "steps:\n  - name: test\n    commands: [\"\"]\n",
+            "steps:\n  - name: a\n    commands: [true]\n  - name: a\n    commands: [true]\n",
+            "steps:\n  - name: test\n    commands: [true]\n    timeout_seconds: 0\n",
+            "steps:\n  - name: test\n    commands: [true]\nartifacts: [\"../out\"]\n",
+            "steps:\n  - name: test\n    commands: [true]\nartifacts: [\"dist/[\"]\n",
+        ];
+
+        for yaml in cases {
+            assert!(PipelineDefinition::from_yaml(yaml).is_err(), "{}", yaml);
+        }
+    }
+
+    #[test]
+    fn test_for_branch() {
+        let definition = PipelineDefinition::from_yaml(EXAMPLE).unwrap();
+
+        let names = |branch: &str| -> Vec<String> {
+            definition
+                .for_branch(branch)
+                .steps
+                .into_iter()
+                .map(|s| s.name)
+                .collect()
+        };
+
+        assert_eq!(names("main"), vec!["test", "audit", "publish"]);
+        assert_eq!(names("release/1.0"), vec!["test", "audit", "publish"]);
+        assert_eq!(names("feature/x"), vec!["test", "audit"]);
+    }
+
+    #[test]
+    fn test_branch_matches() {
+        assert!(branch_matches("main", "main"));
+        assert!(!branch_matches("main", "main2"));
+        assert!(branch_matches("release/*", "release/1.0"));
+        assert!(!branch_matches("release/*", "hotfix/1.0"));
+        assert!(branch_matches("*", "anything"));
+        assert!(branch_matches("feat*-wip", "feature/x-wip"));
+        assert!(!branch_matches("feat*-wip", "feature/x"));
+        assert!(branch_matches("a*b*c", "axxbyyc"));
+    }
+
+    #[test]
+    fn test_load_from_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(PipelineDefinition::load_from_repo(temp_dir.path())
+            .unwrap()
+            .is_none());
+
+        fs::write(temp_dir.path().join(PIPELINE_FILE_NAME), EXAMPLE).unwrap();
+        let definition = PipelineDefinition::load_from_repo(temp_dir.path())
+            .unwrap()
+            .unwrap();
+        assert_eq!(definition.steps.len(), 3);
+    }
+}
@@ -0,0 +1,139 @@
+//! Job queue wire format
+//!
+//! This module defines the envelope that the server writes to the Redis Streams
+//! job queue and that agents read back. Keeping the format in one place ensures
+//! producers and consumers agree on stream names, field names and payload shape.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::jobs::Job;
+
+/// Current version of the queue envelope format
+pub const QUEUE_ENVELOPE_VERSION: u32 = 1;
+
+/// Stream entry field holding the serialized envelope
+pub const ENVELOPE_FIELD: &str = "envelope";
+
+/// Default job queue stream name
+pub const DEFAULT_JOB_STREAM: &str = "raibid:jobs";
+
+/// Default consumer group name used by agents
+pub const DEFAULT_CONSUMER_GROUP: &str = "raibid-workers";
+
+/// Default stream for jobs that exhausted their retries
+pub const DEFAULT_DEAD_LETTER_STREAM: &str = "raibid:jobs:dead";
+
+/// Default number of entries the job queue stream is trimmed to (approximately,
+/// acknowledged entries only)
+pub const DEFAULT_JOB_STREAM_MAX_LENGTH: usize = 10000;
+
+/// Default number of lines a job's log stream is trimmed to once archived (approximately)
+pub const DEFAULT_LOG_STREAM_MAX_LENGTH: usize = 50000;
+
+/// How long a cancellation request stays visible to agents
+pub const CANCEL_KEY_TTL_SECS: u64 = 86400;
+
+/// Key an agent watches to learn that a job has been cancelled
+pub fn cancel_key(job_id: &str) -> String {
+    format!("raibid:job:{}:cancel", job_id)
+}
+
+/// Log stream entry field holding the serialized [`JobLogEntry`](crate::JobLogEntry)
+///
+/// Older agents wrote plain `timestamp` and `message` fields instead.
+pub const LOG_ENTRY_FIELD: &str = "entry";
+
+/// Stream agents write a job's build log to, read back by the server
+pub fn log_stream_key(job_id: &str) -> String {
+    format!("job:{}:logs", job_id)
+}
+
+/// Versioned job envelope written to the job queue stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobEnvelope {
+    /// Envelope format version
+    pub version: u32,
+    /// Job to execute
+    pub job: Job,
+    /// Event that produced the job (e.g. "push", "manual")
+    pub event_type: String,
+    /// User that triggered the job, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Time the job was enqueued
+    pub enqueued_at: DateTime<Utc>,
+}
+
+impl JobEnvelope {
+    /// Create a new envelope for a job using the current format version
+    pub fn new(job: Job, event_type: impl Into<String>, author: Option<String>) -> Self {
+        Self {
+            version: QUEUE_ENVELOPE_VERSION,
+            job,
+            event_type: event_type.into(),
+            author,
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    /// Serialize the envelope for storage in a stream entry
+    pub fn encode(&self) -> Result<String> {
+        serde_json::to_string(self).context("Failed to serialize job envelope")
+    }
+
+    /// Parse an envelope read from a stream entry
+    ///
+    /// Envelopes written by a newer producer than this build understands are rejected
+    /// rather than being partially interpreted.
+    pub fn decode(data: &str) -> Result<Self> {
+        let envelope: JobEnvelope =
+            serde_json::from_str(data).context("Invalid job envelope JSON")?;
+
+        if envelope.version == 0 || envelope.version > QUEUE_ENVELOPE_VERSION {
+            anyhow::bail!(
+                "Unsupported job envelope version {} (supported: 1..={})",
+                envelope.version,
+                QUEUE_ENVELOPE_VERSION
+            );
+        }
+
+        Ok(envelope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_job() -> Job {
+        Job::new("job-1", "owner/repo", "main", "abc123")
+    }
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let envelope = JobEnvelope::new(sample_job(), "push", Some("alice".to_string()));
+        let encoded = envelope.encode().unwrap();
+
+        let decoded = JobEnvelope::decode(&encoded).unwrap();
+        assert_eq!(decoded.version, QUEUE_ENVELOPE_VERSION);
+        assert_eq!(decoded.job.id, "job-1");
+        assert_eq!(decoded.event_type, "push");
+        assert_eq!(decoded.author.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn test_envelope_rejects_future_version() {
+        let mut envelope = JobEnvelope::new(sample_job(), "push", None);
+        envelope.version = QUEUE_ENVELOPE_VERSION + 1;
+        let encoded = serde_json::to_string(&envelope).unwrap();
+
+        assert!(JobEnvelope::decode(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_envelope_rejects_invalid_json() {
+        assert!(JobEnvelope::decode("not json").is_err());
+    }
+}
@@ -0,0 +1,135 @@
+//! Job retention policy
+//!
+//! Redis only keeps recent jobs. Finished jobs beyond the newest
+//! [`RetentionPolicy::keep_per_repo`] of their repository, or older than
+//! [`RetentionPolicy::max_age_secs`], are pruned by the server together with
+//! their log streams, archived logs and artifacts. Queued and running jobs are
+//! never pruned.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::jobs::Job;
+
+/// Default number of finished jobs kept per repository
+pub const DEFAULT_KEEP_JOBS_PER_REPO: usize = 100;
+
+/// Default age after which finished jobs are pruned (30 days)
+pub const DEFAULT_MAX_JOB_AGE_SECS: u64 = 30 * 86400;
+
+/// Which finished jobs are kept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Finished jobs kept per repository, newest first (0 = unlimited)
+    pub keep_per_repo: usize,
+    /// Seconds after finishing at which a job is pruned (0 = never)
+    pub max_age_secs: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_per_repo: DEFAULT_KEEP_JOBS_PER_REPO,
+            max_age_secs: DEFAULT_MAX_JOB_AGE_SECS,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Select the jobs the policy no longer keeps, newest first
+    pub fn expired<'a>(&self, jobs: &'a [Job], now: DateTime<Utc>) -> Vec<&'a Job> {
+        let mut finished: Vec<&Job> = jobs.iter().filter(|job| job.status.is_terminal()).collect();
+        finished.sort_by_key(|job| Reverse(job.started_at));
+
+        let cutoff = (self.max_age_secs > 0)
+            .then(|| now - Duration::seconds(self.max_age_secs.min(i64::MAX as u64) as i64));
+        let mut kept: HashMap<&str, usize> = HashMap::new();
+
+        finished
+            .into_iter()
+            .filter(|job| {
+                let count = kept.entry(job.repo.as_str()).or_default();
+                *count += 1;
+
+                let too_many = self.keep_per_repo > 0 && *count > self.keep_per_repo;
+                let too_old =
+                    cutoff.is_some_and(|cutoff| job.finished_at.unwrap_or(job.started_at) < cutoff);
+                too_many || too_old
+            })
+            .collect()
+    }
+}
+
+/// Jobs removed by pruning, or that would be removed by a dry run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PruneReport {
+    /// Whether the jobs were left in place
+    pub dry_run: bool,
+    /// Pruned jobs, newest first
+    pub jobs: Vec<Job>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::JobStatus;
+
+    fn job(id: &str, repo: &str, status: JobStatus, age_secs: i64) -> Job {
+        let started_at = Utc::now() - Duration::seconds(age_secs);
+        Job {
+            status,
+            started_at,
+            finished_at: status.is_terminal().then_some(started_at),
+            ..Job::new(id, repo, "main", "abc123")
+        }
+    }
+
+    fn ids(jobs: Vec<&Job>) -> Vec<&str> {
+        jobs.iter().map(|job| job.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_keep_per_repo() {
+        let jobs = vec![
+            job("a1", "owner/a", JobStatus::Success, 10),
+            job("a2", "owner/a", JobStatus::Failed, 20),
+            job("a3", "owner/a", JobStatus::Success, 30),
+            job("a4", "owner/a", JobStatus::Running, 40),
+            job("b1", "owner/b", JobStatus::Success, 50),
+        ];
+        let policy = RetentionPolicy {
+            keep_per_repo: 2,
+            max_age_secs: 0,
+        };
+
+        assert_eq!(ids(policy.expired(&jobs, Utc::now())), ["a3"]);
+    }
+
+    #[test]
+    fn test_max_age() {
+        let jobs = vec![
+            job("new", "owner/a", JobStatus::Success, 60),
+            job("old", "owner/a", JobStatus::Cancelled, 7200),
+            job("queued", "owner/a", JobStatus::Pending, 7200),
+        ];
+        let policy = RetentionPolicy {
+            keep_per_repo: 0,
+            max_age_secs: 3600,
+        };
+
+        assert_eq!(ids(policy.expired(&jobs, Utc::now())), ["old"]);
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let jobs = vec![job("a1", "owner/a", JobStatus::Success, 365 * 86400)];
+        let policy = RetentionPolicy {
+            keep_per_repo: 0,
+            max_age_secs: 0,
+        };
+
+        assert!(policy.expired(&jobs, Utc::now()).is_empty());
+    }
+}
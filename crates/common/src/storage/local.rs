@@ -0,0 +1,137 @@
+//! Object store in a local directory
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use std::path::{Component, Path, PathBuf};
+use tokio::io::AsyncReadExt;
+
+use super::{ObjectStore, ObjectStream};
+
+/// Size of the chunks objects are read in
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Stores objects as files under a directory, keys being relative paths
+#[derive(Debug, Clone)]
+pub struct LocalObjectStore {
+    root: PathBuf,
+}
+
+impl LocalObjectStore {
+    /// Create a store keeping objects under `root`, created on first write
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Get the path of an object, refusing keys that escape the root
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        let relative = Path::new(key);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            bail!("Invalid object key: {}", key);
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LocalObjectStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        tokio::fs::write(&path, data)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<ObjectStream>> {
+        let path = self.path(key)?;
+        let file = match tokio::fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        let chunks = futures::stream::try_unfold((file, path), |(mut file, path)| async move {
+            let mut chunk = vec![0; CHUNK_SIZE];
+            let read = file
+                .read(&mut chunk)
+                .await
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            if read == 0 {
+                return Ok(None);
+            }
+
+            chunk.truncate(read);
+            Ok(Some((Bytes::from(chunk), (file, path))))
+        });
+        Ok(Some(chunks.boxed()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let path = self.path(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete {}", path.display())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalObjectStore::new(dir.path());
+
+        assert!(store.get("logs/job-1.jsonl.gz").await.unwrap().is_none());
+
+        store
+            .put("logs/job-1.jsonl.gz", b"log".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get_bytes("logs/job-1.jsonl.gz").await.unwrap(),
+            Some(b"log".to_vec())
+        );
+
+        store.delete("logs/job-1.jsonl.gz").await.unwrap();
+        assert!(store.get("logs/job-1.jsonl.gz").await.unwrap().is_none());
+        store.delete("logs/job-1.jsonl.gz").await.unwrap();
+
+        assert!(store.put("../escape", Vec::new()).await.is_err());
+        assert!(store.get("/etc/passwd").await.is_err());
+        assert!(store.delete("../escape").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_streams_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalObjectStore::new(dir.path());
+        let data: Vec<u8> = (0..CHUNK_SIZE * 2 + 1).map(|i| i as u8).collect();
+        store.put("artifacts/big", data.clone()).await.unwrap();
+
+        let chunks: Vec<Bytes> = store
+            .get("artifacts/big")
+            .await
+            .unwrap()
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), data);
+    }
+}
@@ -0,0 +1,105 @@
+//! Object storage
+//!
+//! Job output that must outlive Redis and agent workspaces (archived build logs
+//! and artifacts) is kept in an object store: a directory on the local
+//! filesystem, or an S3-compatible bucket such as MinIO.
+//!
+//! Both agents and the server pick the store from the environment:
+//! - `RAIBID_STORAGE_PATH`: directory to store objects in
+//! - `RAIBID_S3_BUCKET`: bucket to store objects in, together with
+//!   `RAIBID_S3_ENDPOINT` (default `https://s3.amazonaws.com`), `RAIBID_S3_REGION`
+//!   (default `us-east-1`) and credentials from `RAIBID_S3_ACCESS_KEY` and
+//!   `RAIBID_S3_SECRET_KEY` (or `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`)
+
+mod local;
+mod s3;
+
+pub use local::LocalObjectStore;
+pub use s3::{S3Config, S3ObjectStore};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::{BoxStream, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// An object's contents, read in chunks as they arrive
+pub type ObjectStream = BoxStream<'static, Result<Bytes>>;
+
+/// Stores objects by key
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Store an object, replacing any object with the same key
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()>;
+
+    /// Get an object as a stream of chunks, if it exists
+    async fn get(&self, key: &str) -> Result<Option<ObjectStream>>;
+
+    /// Delete an object; deleting an object that doesn't exist succeeds
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// Get an object read fully into memory, if it exists
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let Some(stream) = self.get(key).await? else {
+            return Ok(None);
+        };
+
+        let data = stream
+            .try_fold(Vec::new(), |mut data, chunk| async move {
+                data.extend_from_slice(&chunk);
+                Ok(data)
+            })
+            .await?;
+        Ok(Some(data))
+    }
+}
+
+/// Where objects are stored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageConfig {
+    /// A directory on the local filesystem
+    Local(PathBuf),
+    /// An S3-compatible bucket
+    S3(S3Config),
+}
+
+impl StorageConfig {
+    /// Read the storage configuration from environment variables
+    ///
+    /// Returns `None` if no storage is configured.
+    pub fn from_env() -> Result<Option<Self>> {
+        if let Ok(path) = std::env::var("RAIBID_STORAGE_PATH") {
+            return Ok(Some(Self::Local(PathBuf::from(path))));
+        }
+
+        let Ok(bucket) = std::env::var("RAIBID_S3_BUCKET") else {
+            return Ok(None);
+        };
+
+        let env = |name: &str, fallback: &str| {
+            std::env::var(name)
+                .or_else(|_| std::env::var(fallback))
+                .with_context(|| format!("{} must be set to use RAIBID_S3_BUCKET", name))
+        };
+
+        Ok(Some(Self::S3(S3Config {
+            endpoint: std::env::var("RAIBID_S3_ENDPOINT")
+                .unwrap_or_else(|_| "https://s3.amazonaws.com".to_string()),
+            bucket,
+            region: std::env::var("RAIBID_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            access_key: env("RAIBID_S3_ACCESS_KEY", "AWS_ACCESS_KEY_ID")?,
+            secret_key: env("RAIBID_S3_SECRET_KEY", "AWS_SECRET_ACCESS_KEY")?,
+        })))
+    }
+
+    /// Open the configured store
+    pub fn open(&self) -> Result<Arc<dyn ObjectStore>> {
+        Ok(match self {
+            Self::Local(path) => Arc::new(LocalObjectStore::new(path)),
+            Self::S3(config) => Arc::new(S3ObjectStore::new(config.clone())?),
+        })
+    }
+}
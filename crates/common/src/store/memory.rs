@@ -0,0 +1,506 @@
+//! In-memory job store, agent registry and delivery log
+//!
+//! Useful for tests and for running the server without Redis. Records are lost
+//! when the process exits.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use super::{
+    build_job_list, webhook_secret_key, AgentRegistry, DeliveryLog, JobStore, WebhookSecretStore,
+};
+use crate::agents::AgentInfo;
+use crate::artifacts::Artifact;
+use crate::jobs::{Job, JobList, JobListQuery, JobStatus, StepResult};
+use crate::webhooks::{WebhookDelivery, DEFAULT_DELIVERY_LOG_SIZE};
+
+/// Job store backed by a process-local map
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryJobStore {
+    jobs: Arc<RwLock<HashMap<String, Job>>>,
+    steps: Arc<RwLock<HashMap<String, Vec<StepResult>>>>,
+    artifacts: Arc<RwLock<HashMap<String, Vec<Artifact>>>>,
+    outcomes: Arc<RwLock<HashMap<JobStatus, u64>>>,
+}
+
+impl InMemoryJobStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl JobStore for InMemoryJobStore {
+    async fn save(&self, job: &Job) -> Result<()> {
+        self.jobs.write().await.insert(job.id.clone(), job.clone());
+        Ok(())
+    }
+
+    async fn save_if_status(&self, job: &Job, expected: JobStatus) -> Result<bool> {
+        let mut jobs = self.jobs.write().await;
+        match jobs.get_mut(&job.id) {
+            Some(stored) if stored.status == expected => {
+                *stored = job.clone();
+                if job.status.is_terminal() && !expected.is_terminal() {
+                    *self.outcomes.write().await.entry(job.status).or_default() += 1;
+                }
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<Job>> {
+        Ok(self.jobs.read().await.get(id).cloned())
+    }
+
+    async fn list(&self, query: &JobListQuery) -> Result<JobList> {
+        let jobs = self.jobs.read().await;
+        Ok(build_job_list(jobs.values().cloned(), query))
+    }
+
+    async fn list_all(&self) -> Result<Vec<Job>> {
+        Ok(self.jobs.read().await.values().cloned().collect())
+    }
+
+    async fn outcome_counts(&self) -> Result<HashMap<JobStatus, u64>> {
+        Ok(self.outcomes.read().await.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool> {
+        self.steps.write().await.remove(id);
+        self.artifacts.write().await.remove(id);
+        Ok(self.jobs.write().await.remove(id).is_some())
+    }
+
+    async fn save_steps(&self, id: &str, steps: &[StepResult]) -> Result<()> {
+        self.steps
+            .write()
+            .await
+            .insert(id.to_string(), steps.to_vec());
+        Ok(())
+    }
+
+    async fn get_steps(&self, id: &str) -> Result<Vec<StepResult>> {
+        Ok(self.steps.read().await.get(id).cloned().unwrap_or_default())
+    }
+
+    async fn save_artifacts(&self, id: &str, artifacts: &[Artifact]) -> Result<()> {
+        self.artifacts
+            .write()
+            .await
+            .insert(id.to_string(), artifacts.to_vec());
+        Ok(())
+    }
+
+    async fn get_artifacts(&self, id: &str) -> Result<Vec<Artifact>> {
+        Ok(self
+            .artifacts
+            .read()
+            .await
+            .get(id)
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+/// Agent registry backed by a process-local map
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryAgentRegistry {
+    agents: Arc<RwLock<HashMap<String, AgentInfo>>>,
+}
+
+impl InMemoryAgentRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AgentRegistry for InMemoryAgentRegistry {
+    async fn heartbeat(&self, agent: &AgentInfo) -> Result<()> {
+        self.agents
+            .write()
+            .await
+            .insert(agent.id.clone(), agent.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> Result<bool> {
+        Ok(self.agents.write().await.remove(id).is_some())
+    }
+
+    async fn list(&self) -> Result<Vec<AgentInfo>> {
+        Ok(self.agents.read().await.values().cloned().collect())
+    }
+}
+
+/// Delivery log backed by a process-local queue
+#[derive(Debug, Clone)]
+pub struct InMemoryDeliveryLog {
+    deliveries: Arc<RwLock<VecDeque<WebhookDelivery>>>,
+    capacity: usize,
+}
+
+impl InMemoryDeliveryLog {
+    /// Create an empty log keeping up to `capacity` deliveries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            deliveries: Arc::default(),
+            capacity,
+        }
+    }
+}
+
+impl Default for InMemoryDeliveryLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_DELIVERY_LOG_SIZE)
+    }
+}
+
+#[async_trait]
+impl DeliveryLog for InMemoryDeliveryLog {
+    async fn record(&self, delivery: &WebhookDelivery) -> Result<()> {
+        let mut deliveries = self.deliveries.write().await;
+        deliveries.push_front(delivery.clone());
+        deliveries.truncate(self.capacity);
+        Ok(())
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<WebhookDelivery>> {
+        let deliveries = self.deliveries.read().await;
+        Ok(deliveries.iter().take(limit).cloned().collect())
+    }
+}
+
+/// Webhook secret store backed by a process-local map
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryWebhookSecretStore {
+    secrets: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl InMemoryWebhookSecretStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl WebhookSecretStore for InMemoryWebhookSecretStore {
+    async fn get(&self, provider: &str, repo: &str) -> Result<Option<String>> {
+        let key = webhook_secret_key(provider, repo);
+        Ok(self.secrets.read().await.get(&key).cloned())
+    }
+
+    async fn set(&self, provider: &str, repo: &str, secret: &str) -> Result<()> {
+        self.secrets
+            .write()
+            .await
+            .insert(webhook_secret_key(provider, repo), secret.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::JobStatus;
+    use chrono::{Duration, Utc};
+
+    fn job(id: &str, repo: &str, age_secs: i64) -> Job {
+        Job {
+            started_at: Utc::now() - Duration::seconds(age_secs),
+            ..Job::new(id, repo, "main", "abc123")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_and_get() {
+        let store = InMemoryJobStore::new();
+        store.save(&job("job-1", "owner/repo", 0)).await.unwrap();
+
+        let fetched = store.get("job-1").await.unwrap().unwrap();
+        assert_eq!(fetched.repo, "owner/repo");
+        assert!(store.get("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_and_orders() {
+        let store = InMemoryJobStore::new();
+        store.save(&job("old", "owner/repo", 60)).await.unwrap();
+        store.save(&job("new", "owner/repo", 0)).await.unwrap();
+        store.save(&job("other", "owner/other", 30)).await.unwrap();
+
+        let list = store
+            .list(&JobListQuery {
+                repo: Some("owner/repo".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(list.total, 2);
+        assert_eq!(list.jobs[0].id, "new");
+        assert_eq!(list.jobs[1].id, "old");
+    }
+
+    #[tokio::test]
+    async fn test_list_pagination() {
+        let store = InMemoryJobStore::new();
+        for i in 0..5 {
+            store
+                .save(&job(&format!("job-{}", i), "owner/repo", i))
+                .await
+                .unwrap();
+        }
+
+        let list = store
+            .list(&JobListQuery {
+                limit: Some(2),
+                offset: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(list.total, 5);
+        assert_eq!(list.jobs.len(), 2);
+        assert_eq!(list.jobs[0].id, "job-1");
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_transitions() {
+        let store = InMemoryJobStore::new();
+        store.save(&job("job-1", "owner/repo", 0)).await.unwrap();
+
+        let running = store.mark_running("job-1", "agent-1").await.unwrap();
+        assert_eq!(running.status, JobStatus::Running);
+        assert_eq!(running.agent_id.as_deref(), Some("agent-1"));
+
+        let finished = store
+            .mark_finished("job-1", JobStatus::Failed, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(finished.status, JobStatus::Failed);
+        assert_eq!(finished.exit_code, Some(1));
+
+        let stored = store.get("job-1").await.unwrap().unwrap();
+        assert_eq!(stored.status, JobStatus::Failed);
+        assert!(stored.finished_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_transitions_keep_terminal_status() {
+        let store = InMemoryJobStore::new();
+        store.save(&job("job-1", "owner/repo", 0)).await.unwrap();
+
+        let cancelled = store.cancel_pending("job-1").await.unwrap();
+        assert_eq!(cancelled.status, JobStatus::Cancelled);
+
+        // An agent picking the job up late doesn't resurrect it
+        let job = store.mark_running("job-1", "agent-1").await.unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert_eq!(job.agent_id, None);
+
+        let job = store
+            .mark_finished("job-1", JobStatus::Failed, None)
+            .await
+            .unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_status_and_outcome_counts() {
+        let store = InMemoryJobStore::new();
+        store.save(&job("job-1", "owner/repo", 0)).await.unwrap();
+        store.save(&job("job-2", "owner/repo", 0)).await.unwrap();
+        store.mark_running("job-1", "agent-1").await.unwrap();
+        store
+            .mark_finished("job-1", JobStatus::Success, Some(0))
+            .await
+            .unwrap();
+        // Already finished, so not counted again
+        store
+            .mark_finished("job-1", JobStatus::Failed, Some(1))
+            .await
+            .unwrap();
+
+        let counts = store.count_by_status().await.unwrap();
+        assert_eq!(counts.get(&JobStatus::Pending), Some(&1));
+        assert_eq!(counts.get(&JobStatus::Success), Some(&1));
+
+        // Outcomes outlive the records they counted
+        store.delete("job-1").await.unwrap();
+        let outcomes = store.outcome_counts().await.unwrap();
+        assert_eq!(outcomes.get(&JobStatus::Success), Some(&1));
+        assert_eq!(outcomes.get(&JobStatus::Failed), None);
+        assert_eq!(
+            store
+                .count_by_status()
+                .await
+                .unwrap()
+                .get(&JobStatus::Success),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancel_pending_after_start() {
+        let store = InMemoryJobStore::new();
+        store.save(&job("job-1", "owner/repo", 0)).await.unwrap();
+        let running = store.mark_running("job-1", "agent-1").await.unwrap();
+
+        let job = store.cancel_pending("job-1").await.unwrap();
+        assert_eq!(job.status, JobStatus::Running);
+
+        // A write based on the pending record is rejected
+        let mut stale = running.clone();
+        stale.status = JobStatus::Pending;
+        stale.mark_finished(JobStatus::Cancelled, None);
+        assert!(!store
+            .save_if_status(&stale, JobStatus::Pending)
+            .await
+            .unwrap());
+        assert!(store
+            .save_if_status(&stale, JobStatus::Running)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_transition_unknown_job() {
+        let store = InMemoryJobStore::new();
+        assert!(store.mark_running("missing", "agent-1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        let store = InMemoryJobStore::new();
+        store.save(&job("job-1", "owner/repo", 0)).await.unwrap();
+
+        assert!(store.delete("job-1").await.unwrap());
+        assert!(!store.delete("job-1").await.unwrap());
+        assert!(store.get("job-1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_steps() {
+        let store = InMemoryJobStore::new();
+        store.save(&job("job-1", "owner/repo", 0)).await.unwrap();
+        assert!(store.get_steps("job-1").await.unwrap().is_empty());
+
+        let steps = vec![StepResult {
+            step: "clippy".to_string(),
+            success: false,
+            exit_code: Some(101),
+            duration_secs: 12,
+            output: "error: unused variable".to_string(),
+        }];
+        store.save_steps("job-1", &steps).await.unwrap();
+        assert_eq!(store.get_steps("job-1").await.unwrap(), steps);
+
+        store.delete("job-1").await.unwrap();
+        assert!(store.get_steps("job-1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_artifacts() {
+        let store = InMemoryJobStore::new();
+        store.save(&job("job-1", "owner/repo", 0)).await.unwrap();
+        assert!(store.get_artifacts("job-1").await.unwrap().is_empty());
+
+        let artifacts = vec![Artifact::new("target/release/raibid", b"binary")];
+        store.save_artifacts("job-1", &artifacts).await.unwrap();
+        assert_eq!(store.get_artifacts("job-1").await.unwrap(), artifacts);
+
+        store.delete("job-1").await.unwrap();
+        assert!(store.get_artifacts("job-1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_agent_registry() {
+        use crate::agents::AgentStatus;
+
+        let registry = InMemoryAgentRegistry::new();
+        let mut agent = AgentInfo {
+            id: "agent-1".to_string(),
+            hostname: "dgx-01".to_string(),
+            version: "0.1.0".to_string(),
+            status: AgentStatus::Idle,
+            current_jobs: Vec::new(),
+            capacity: 1,
+            load: None,
+            started_at: Utc::now(),
+            last_heartbeat: Utc::now(),
+        };
+        registry.heartbeat(&agent).await.unwrap();
+
+        agent.status = AgentStatus::Busy;
+        agent.current_jobs = vec!["job-1".to_string()];
+        registry.heartbeat(&agent).await.unwrap();
+
+        let agents = registry.list().await.unwrap();
+        assert_eq!(agents, vec![agent]);
+
+        assert!(registry.remove("agent-1").await.unwrap());
+        assert!(!registry.remove("agent-1").await.unwrap());
+        assert!(registry.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delivery_log_is_capped() {
+        use crate::webhooks::DeliveryOutcome;
+
+        let log = InMemoryDeliveryLog::new(2);
+        for id in ["d-1", "d-2", "d-3"] {
+            let delivery = WebhookDelivery {
+                id: id.to_string(),
+                provider: "github".to_string(),
+                event: Some("push".to_string()),
+                delivery_id: None,
+                received_at: Utc::now(),
+                headers: Default::default(),
+                body: "{}".to_string(),
+                outcome: DeliveryOutcome::Ignored,
+                status: 200,
+                message: "pong".to_string(),
+                job_id: None,
+                replay_of: None,
+            };
+            log.record(&delivery).await.unwrap();
+        }
+
+        let ids: Vec<String> = log
+            .list(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|d| d.id)
+            .collect();
+        assert_eq!(ids, vec!["d-3", "d-2"]);
+        assert_eq!(log.list(1).await.unwrap().len(), 1);
+        assert!(log.get("d-2").await.unwrap().is_some());
+        assert!(log.get("d-1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_secret_store() {
+        let store = InMemoryWebhookSecretStore::new();
+        assert!(store.get("github", "owner/repo").await.unwrap().is_none());
+
+        store.set("github", "owner/repo", "first").await.unwrap();
+        store.set("github", "owner/repo", "second").await.unwrap();
+        assert_eq!(
+            store.get("github", "owner/repo").await.unwrap().as_deref(),
+            Some("second")
+        );
+        assert!(store.get("github", "owner/other").await.unwrap().is_none());
+        assert!(store.get("gitlab", "owner/repo").await.unwrap().is_none());
+    }
+}
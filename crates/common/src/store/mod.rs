@@ -0,0 +1,238 @@
+//! Job record storage
+//!
+//! This module provides the stores shared by the server and agents:
+//! - [`JobStore`]: job records, written by the server as it enqueues jobs and by
+//!   agents as they pick up and finish them, and read back by the jobs API
+//! - [`AgentRegistry`]: agents' latest heartbeats, listed by the agents API
+//! - [`DeliveryLog`]: recent webhook deliveries recorded by the server
+//! - [`WebhookSecretStore`]: per-repository webhook secrets generated when
+//!   webhooks are registered, keyed by provider and repository
+
+pub mod memory;
+pub mod redis;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::agents::AgentInfo;
+use crate::artifacts::Artifact;
+use crate::jobs::{Job, JobList, JobListQuery, JobStatus, StepResult};
+use crate::webhooks::WebhookDelivery;
+
+pub use memory::{
+    InMemoryAgentRegistry, InMemoryDeliveryLog, InMemoryJobStore, InMemoryWebhookSecretStore,
+};
+pub use redis::{RedisAgentRegistry, RedisDeliveryLog, RedisJobStore, RedisWebhookSecretStore};
+
+/// Default page size for job listings
+pub const DEFAULT_LIST_LIMIT: usize = 20;
+
+/// Maximum page size for job listings
+pub const MAX_LIST_LIMIT: usize = 100;
+
+/// Persistent storage for job records
+#[async_trait]
+pub trait JobStore: Send + Sync {
+    /// Insert or replace a job record
+    async fn save(&self, job: &Job) -> Result<()>;
+
+    /// Replace a job record if its stored status is still `expected`
+    ///
+    /// Returns whether the record was replaced: `false` if the job's status has
+    /// changed or the job no longer exists.
+    async fn save_if_status(&self, job: &Job, expected: JobStatus) -> Result<bool>;
+
+    /// Get a job by ID
+    async fn get(&self, id: &str) -> Result<Option<Job>>;
+
+    /// List jobs matching a query, newest first
+    async fn list(&self, query: &JobListQuery) -> Result<JobList>;
+
+    /// List every job, in no particular order
+    async fn list_all(&self) -> Result<Vec<Job>>;
+
+    /// Count job records by status
+    async fn count_by_status(&self) -> Result<HashMap<JobStatus, usize>> {
+        let mut counts = HashMap::new();
+        for job in self.list_all().await? {
+            *counts.entry(job.status).or_default() += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Count the jobs that finished with each terminal status
+    ///
+    /// Jobs are counted as they transition, so unlike
+    /// [`count_by_status`](Self::count_by_status) these never decrease when
+    /// finished records are pruned.
+    async fn outcome_counts(&self) -> Result<HashMap<JobStatus, u64>>;
+
+    /// Delete a job record, its step results and its artifact list, returning
+    /// whether it existed
+    async fn delete(&self, id: &str) -> Result<bool>;
+
+    /// Replace the step results recorded for a job
+    async fn save_steps(&self, id: &str, steps: &[StepResult]) -> Result<()>;
+
+    /// Get the step results recorded for a job (empty if none yet)
+    async fn get_steps(&self, id: &str) -> Result<Vec<StepResult>>;
+
+    /// Replace the artifacts recorded for a job
+    async fn save_artifacts(&self, id: &str, artifacts: &[Artifact]) -> Result<()>;
+
+    /// Get the artifacts recorded for a job (empty if none)
+    async fn get_artifacts(&self, id: &str) -> Result<Vec<Artifact>>;
+
+    /// Update a job record without losing concurrent status changes
+    ///
+    /// `update` is applied to the stored job and returns whether to save the
+    /// result. If another writer changes the job's status first, the update is
+    /// retried on the new record. Returns the job as stored afterwards, or `None`
+    /// if it doesn't exist.
+    async fn update(
+        &self,
+        id: &str,
+        update: &(dyn for<'j> Fn(&'j mut Job) -> bool + Send + Sync),
+    ) -> Result<Option<Job>> {
+        // Statuses only move forward, so this settles after a few rounds
+        loop {
+            let Some(job) = self.get(id).await? else {
+                return Ok(None);
+            };
+
+            let mut updated = job.clone();
+            if !update(&mut updated) {
+                return Ok(Some(job));
+            }
+            if self.save_if_status(&updated, job.status).await? {
+                return Ok(Some(updated));
+            }
+        }
+    }
+
+    /// Record that an agent has started a job
+    ///
+    /// Jobs that already reached a terminal state (e.g. cancelled while queued)
+    /// are returned unchanged.
+    async fn mark_running(&self, id: &str, agent_id: &str) -> Result<Job> {
+        self.update(id, &|job| {
+            if job.status.is_terminal() {
+                return false;
+            }
+            job.mark_running(agent_id);
+            true
+        })
+        .await?
+        .ok_or_else(|| anyhow!("Job not found: {}", id))
+    }
+
+    /// Record that a job reached a terminal state
+    ///
+    /// Jobs that already reached one are returned unchanged, so a late writer
+    /// can't overwrite e.g. a cancellation.
+    async fn mark_finished(
+        &self,
+        id: &str,
+        status: JobStatus,
+        exit_code: Option<i32>,
+    ) -> Result<Job> {
+        self.update(id, &|job| {
+            if job.status.is_terminal() {
+                return false;
+            }
+            job.mark_finished(status, exit_code);
+            true
+        })
+        .await?
+        .ok_or_else(|| anyhow!("Job not found: {}", id))
+    }
+
+    /// Cancel a job if it's still pending
+    ///
+    /// Returns the job as stored afterwards: cancelled, or in whatever state an
+    /// agent moved it to first.
+    async fn cancel_pending(&self, id: &str) -> Result<Job> {
+        self.update(id, &|job| {
+            if job.status != JobStatus::Pending {
+                return false;
+            }
+            job.mark_finished(JobStatus::Cancelled, None);
+            true
+        })
+        .await?
+        .ok_or_else(|| anyhow!("Job not found: {}", id))
+    }
+}
+
+/// Registry of agents and their latest heartbeat
+#[async_trait]
+pub trait AgentRegistry: Send + Sync {
+    /// Insert or refresh an agent's registration
+    async fn heartbeat(&self, agent: &AgentInfo) -> Result<()>;
+
+    /// Remove an agent, returning whether it was registered
+    async fn remove(&self, id: &str) -> Result<bool>;
+
+    /// List every registered agent as last reported
+    async fn list(&self) -> Result<Vec<AgentInfo>>;
+}
+
+/// Capped log of recent webhook deliveries
+///
+/// Once the log is full, recording a delivery drops the oldest one.
+#[async_trait]
+pub trait DeliveryLog: Send + Sync {
+    /// Record a delivery
+    async fn record(&self, delivery: &WebhookDelivery) -> Result<()>;
+
+    /// List up to `limit` deliveries, newest first
+    async fn list(&self, limit: usize) -> Result<Vec<WebhookDelivery>>;
+
+    /// Get a delivery by record ID
+    async fn get(&self, id: &str) -> Result<Option<WebhookDelivery>> {
+        let deliveries = self.list(usize::MAX).await?;
+        Ok(deliveries.into_iter().find(|delivery| delivery.id == id))
+    }
+}
+
+/// Webhook secrets keyed by provider (e.g. "github") and repository full name
+/// (e.g. "owner/repo")
+///
+/// Repositories with the same name on different providers are unrelated, so each
+/// has its own secret.
+#[async_trait]
+pub trait WebhookSecretStore: Send + Sync {
+    /// Get the secret stored for a provider's repository
+    async fn get(&self, provider: &str, repo: &str) -> Result<Option<String>>;
+
+    /// Store a provider's repository's secret, replacing any previous one
+    async fn set(&self, provider: &str, repo: &str, secret: &str) -> Result<()>;
+}
+
+/// Key a repository's webhook secret is stored under
+pub(crate) fn webhook_secret_key(provider: &str, repo: &str) -> String {
+    format!("{}:{}", provider, repo)
+}
+
+/// Filter, sort and paginate jobs according to a query
+pub(crate) fn build_job_list(jobs: impl IntoIterator<Item = Job>, query: &JobListQuery) -> JobList {
+    let mut jobs: Vec<Job> = jobs.into_iter().filter(|job| query.matches(job)).collect();
+
+    // Newest first
+    jobs.sort_by_key(|job| std::cmp::Reverse(job.started_at));
+
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LIST_LIMIT)
+        .min(MAX_LIST_LIMIT);
+    let total = jobs.len();
+
+    JobList {
+        jobs: jobs.into_iter().skip(offset).take(limit).collect(),
+        total,
+        offset,
+        limit,
+    }
+}
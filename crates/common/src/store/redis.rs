@@ -0,0 +1,672 @@
+//! Redis-backed job store, agent registry and delivery log
+//!
+//! Each job is stored as a hash under `job:{id}`. A sorted set indexes job IDs by
+//! creation time so listings page through it instead of scanning the keyspace.
+//! Status transitions are compare-and-set on the hash's `status` field, done in a
+//! Lua script so they can't interleave with other writers; the same script counts
+//! finished jobs by outcome in the `jobs:outcomes` hash. Step results are
+//! stored as a JSON array under `job:{id}:steps`, and uploaded artifacts under
+//! `job:{id}:artifacts`. Agents are stored as JSON in a
+//! single `agents:registry` hash keyed by agent ID. Webhook deliveries are pushed as
+//! JSON onto the `webhooks:deliveries` list, which is trimmed to the log's capacity.
+//! Per-repository webhook secrets live in the `webhooks:secrets` hash keyed by
+//! `{provider}:{repo}`.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use std::collections::HashMap;
+
+use super::{
+    webhook_secret_key, AgentRegistry, DeliveryLog, JobStore, WebhookSecretStore,
+    DEFAULT_LIST_LIMIT, MAX_LIST_LIMIT,
+};
+use crate::agents::AgentInfo;
+use crate::artifacts::Artifact;
+use crate::jobs::{Job, JobList, JobListQuery, JobStatus, PullRequest, StepResult};
+use crate::webhooks::WebhookDelivery;
+
+/// Key prefix for job hashes
+pub const JOB_KEY_PREFIX: &str = "job:";
+
+/// Sorted set indexing all job IDs by creation time
+pub const JOB_INDEX_KEY: &str = "jobs:index";
+
+/// Hash counting finished jobs by terminal status
+pub const JOB_OUTCOMES_KEY: &str = "jobs:outcomes";
+
+/// Hash holding every agent's latest heartbeat
+pub const AGENT_REGISTRY_KEY: &str = "agents:registry";
+
+/// List holding recent webhook deliveries, newest first
+pub const DELIVERY_LOG_KEY: &str = "webhooks:deliveries";
+
+/// Hash holding per-repository webhook secrets, keyed by `{provider}:{repo}`
+pub const WEBHOOK_SECRETS_KEY: &str = "webhooks:secrets";
+
+/// Number of job IDs read from the index at once
+const INDEX_PAGE_SIZE: isize = 500;
+
+/// Replace a job hash if its status is still `ARGV[1]`
+///
+/// `KEYS` are the job hash, the index and the outcome counts; `ARGV` the
+/// expected status, the index score, the job ID, the outcome to count (empty
+/// unless the job just finished), then the hash's field/value pairs.
+const SAVE_IF_STATUS_SCRIPT: &str = r#"
+if redis.call('HGET', KEYS[1], 'status') ~= ARGV[1] then
+    return 0
+end
+redis.call('DEL', KEYS[1])
+redis.call('HSET', KEYS[1], unpack(ARGV, 5))
+redis.call('ZADD', KEYS[2], 'NX', ARGV[2], ARGV[3])
+if ARGV[4] ~= '' then
+    redis.call('HINCRBY', KEYS[3], ARGV[4], 1)
+end
+return 1
+"#;
+
+/// Job store backed by Redis hashes
+#[derive(Debug, Clone)]
+pub struct RedisJobStore {
+    client: redis::Client,
+}
+
+impl RedisJobStore {
+    /// Create a store using an existing Redis client
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+
+    /// Create a store from a Redis connection URL
+    pub fn from_url(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).context("Failed to create Redis client")?;
+        Ok(Self::new(client))
+    }
+
+    /// Get the hash key for a job
+    pub fn job_key(id: &str) -> String {
+        format!("{}{}", JOB_KEY_PREFIX, id)
+    }
+
+    /// Get the key holding a job's step results
+    pub fn steps_key(id: &str) -> String {
+        format!("{}{}:steps", JOB_KEY_PREFIX, id)
+    }
+
+    /// Get the key holding a job's artifact list
+    pub fn artifacts_key(id: &str) -> String {
+        format!("{}{}:artifacts", JOB_KEY_PREFIX, id)
+    }
+
+    async fn connection(&self) -> Result<redis::aio::MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .context("Failed to connect to Redis")
+    }
+
+    /// Read a range of the job index, newest first, with the jobs it lists
+    ///
+    /// Entries whose hash has expired or been removed are skipped.
+    async fn index_page(
+        conn: &mut redis::aio::MultiplexedConnection,
+        start: isize,
+        stop: isize,
+    ) -> Result<(usize, Vec<Job>)> {
+        let ids: Vec<String> = conn
+            .zrevrange(JOB_INDEX_KEY, start, stop)
+            .await
+            .context("Failed to read job index")?;
+        if ids.is_empty() {
+            return Ok((0, Vec::new()));
+        }
+
+        let mut pipe = redis::pipe();
+        for id in &ids {
+            pipe.hgetall(Self::job_key(id));
+        }
+        let hashes: Vec<HashMap<String, String>> = pipe
+            .query_async(conn)
+            .await
+            .context("Failed to fetch jobs")?;
+
+        let jobs = hashes
+            .iter()
+            .filter(|fields| !fields.is_empty())
+            .filter_map(|fields| job_from_fields(fields).ok())
+            .collect();
+        Ok((ids.len(), jobs))
+    }
+}
+
+#[async_trait]
+impl JobStore for RedisJobStore {
+    async fn save(&self, job: &Job) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let key = Self::job_key(&job.id);
+
+        // Replace the hash so cleared optional fields don't linger
+        let _: () = redis::pipe()
+            .atomic()
+            .del(&key)
+            .hset_multiple(&key, &job_to_fields(job))
+            .cmd("ZADD")
+            .arg(JOB_INDEX_KEY)
+            .arg("NX")
+            .arg(job.started_at.timestamp_millis())
+            .arg(&job.id)
+            .query_async(&mut conn)
+            .await
+            .with_context(|| format!("Failed to save job {}", job.id))?;
+
+        Ok(())
+    }
+
+    async fn save_if_status(&self, job: &Job, expected: JobStatus) -> Result<bool> {
+        let mut conn = self.connection().await?;
+        let script = redis::Script::new(SAVE_IF_STATUS_SCRIPT);
+        let mut invocation = script.key(Self::job_key(&job.id));
+        let outcome = if job.status.is_terminal() && !expected.is_terminal() {
+            job.status.as_str().to_lowercase()
+        } else {
+            String::new()
+        };
+        invocation
+            .key(JOB_INDEX_KEY)
+            .key(JOB_OUTCOMES_KEY)
+            .arg(expected.as_str().to_lowercase())
+            .arg(job.started_at.timestamp_millis())
+            .arg(&job.id)
+            .arg(outcome);
+        for (field, value) in job_to_fields(job) {
+            invocation.arg(field).arg(value);
+        }
+
+        let saved: u32 = invocation
+            .invoke_async(&mut conn)
+            .await
+            .with_context(|| format!("Failed to save job {}", job.id))?;
+
+        Ok(saved > 0)
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<Job>> {
+        let mut conn = self.connection().await?;
+        let fields: HashMap<String, String> = conn
+            .hgetall(Self::job_key(id))
+            .await
+            .with_context(|| format!("Failed to get job {}", id))?;
+
+        if fields.is_empty() {
+            return Ok(None);
+        }
+
+        job_from_fields(&fields).map(Some)
+    }
+
+    async fn list(&self, query: &JobListQuery) -> Result<JobList> {
+        let mut conn = self.connection().await?;
+        let offset = query.offset.unwrap_or(0);
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_LIST_LIMIT)
+            .min(MAX_LIST_LIMIT);
+
+        // Without filters the page is a range of the index
+        if query.status.is_none() && query.repo.is_none() && query.branch.is_none() {
+            let total: usize = conn
+                .zcard(JOB_INDEX_KEY)
+                .await
+                .context("Failed to read job index")?;
+            let jobs = if limit == 0 {
+                Vec::new()
+            } else {
+                let start = offset as isize;
+                Self::index_page(&mut conn, start, start + limit as isize - 1)
+                    .await?
+                    .1
+            };
+
+            return Ok(JobList {
+                jobs,
+                total,
+                offset,
+                limit,
+            });
+        }
+
+        // Otherwise page through the index, keeping only the requested matches
+        let mut jobs = Vec::new();
+        let mut total = 0;
+        let mut start = 0;
+        loop {
+            let (read, page) =
+                Self::index_page(&mut conn, start, start + INDEX_PAGE_SIZE - 1).await?;
+            for job in page.into_iter().filter(|job| query.matches(job)) {
+                if total >= offset && jobs.len() < limit {
+                    jobs.push(job);
+                }
+                total += 1;
+            }
+
+            if read < INDEX_PAGE_SIZE as usize {
+                break;
+            }
+            start += INDEX_PAGE_SIZE;
+        }
+
+        Ok(JobList {
+            jobs,
+            total,
+            offset,
+            limit,
+        })
+    }
+
+    async fn list_all(&self) -> Result<Vec<Job>> {
+        let mut conn = self.connection().await?;
+        let mut jobs = Vec::new();
+        let mut start = 0;
+        loop {
+            let (read, page) =
+                Self::index_page(&mut conn, start, start + INDEX_PAGE_SIZE - 1).await?;
+            jobs.extend(page);
+
+            if read < INDEX_PAGE_SIZE as usize {
+                return Ok(jobs);
+            }
+            start += INDEX_PAGE_SIZE;
+        }
+    }
+
+    async fn outcome_counts(&self) -> Result<HashMap<JobStatus, u64>> {
+        let mut conn = self.connection().await?;
+        let counts: HashMap<String, u64> = conn
+            .hgetall(JOB_OUTCOMES_KEY)
+            .await
+            .context("Failed to read job outcome counts")?;
+
+        counts
+            .into_iter()
+            .map(|(status, count)| Ok((status.parse::<JobStatus>()?, count)))
+            .collect()
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool> {
+        let mut conn = self.connection().await?;
+        let (deleted, _, _, _): (u32, u32, u32, u32) = redis::pipe()
+            .atomic()
+            .del(Self::job_key(id))
+            .zrem(JOB_INDEX_KEY, id)
+            .del(Self::steps_key(id))
+            .del(Self::artifacts_key(id))
+            .query_async(&mut conn)
+            .await
+            .with_context(|| format!("Failed to delete job {}", id))?;
+
+        Ok(deleted > 0)
+    }
+
+    async fn save_steps(&self, id: &str, steps: &[StepResult]) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let json = serde_json::to_string(steps).context("Failed to serialize steps")?;
+
+        let _: () = conn
+            .set(Self::steps_key(id), json)
+            .await
+            .with_context(|| format!("Failed to save steps for job {}", id))?;
+
+        Ok(())
+    }
+
+    async fn get_steps(&self, id: &str) -> Result<Vec<StepResult>> {
+        let mut conn = self.connection().await?;
+        let json: Option<String> = conn
+            .get(Self::steps_key(id))
+            .await
+            .with_context(|| format!("Failed to get steps for job {}", id))?;
+
+        match json {
+            Some(json) => {
+                serde_json::from_str(&json).with_context(|| format!("Invalid steps for job {}", id))
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn save_artifacts(&self, id: &str, artifacts: &[Artifact]) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let json = serde_json::to_string(artifacts).context("Failed to serialize artifacts")?;
+
+        let _: () = conn
+            .set(Self::artifacts_key(id), json)
+            .await
+            .with_context(|| format!("Failed to save artifacts for job {}", id))?;
+
+        Ok(())
+    }
+
+    async fn get_artifacts(&self, id: &str) -> Result<Vec<Artifact>> {
+        let mut conn = self.connection().await?;
+        let json: Option<String> = conn
+            .get(Self::artifacts_key(id))
+            .await
+            .with_context(|| format!("Failed to get artifacts for job {}", id))?;
+
+        match json {
+            Some(json) => serde_json::from_str(&json)
+                .with_context(|| format!("Invalid artifacts for job {}", id)),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Agent registry backed by a Redis hash
+#[derive(Debug, Clone)]
+pub struct RedisAgentRegistry {
+    client: redis::Client,
+}
+
+impl RedisAgentRegistry {
+    /// Create a registry using an existing Redis client
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+
+    async fn connection(&self) -> Result<redis::aio::MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .context("Failed to connect to Redis")
+    }
+}
+
+#[async_trait]
+impl AgentRegistry for RedisAgentRegistry {
+    async fn heartbeat(&self, agent: &AgentInfo) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let json = serde_json::to_string(agent).context("Failed to serialize agent")?;
+
+        let _: () = conn
+            .hset(AGENT_REGISTRY_KEY, &agent.id, json)
+            .await
+            .with_context(|| format!("Failed to record heartbeat for agent {}", agent.id))?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> Result<bool> {
+        let mut conn = self.connection().await?;
+        let removed: u32 = conn
+            .hdel(AGENT_REGISTRY_KEY, id)
+            .await
+            .with_context(|| format!("Failed to remove agent {}", id))?;
+
+        Ok(removed > 0)
+    }
+
+    async fn list(&self) -> Result<Vec<AgentInfo>> {
+        let mut conn = self.connection().await?;
+        let entries: HashMap<String, String> = conn
+            .hgetall(AGENT_REGISTRY_KEY)
+            .await
+            .context("Failed to read agent registry")?;
+
+        // Entries written by an incompatible agent version are skipped
+        Ok(entries
+            .values()
+            .filter_map(|json| serde_json::from_str(json).ok())
+            .collect())
+    }
+}
+
+/// Delivery log backed by a capped Redis list
+#[derive(Debug, Clone)]
+pub struct RedisDeliveryLog {
+    client: redis::Client,
+    capacity: usize,
+}
+
+impl RedisDeliveryLog {
+    /// Create a log keeping up to `capacity` deliveries, using an existing Redis client
+    pub fn new(client: redis::Client, capacity: usize) -> Self {
+        Self { client, capacity }
+    }
+
+    async fn connection(&self) -> Result<redis::aio::MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .context("Failed to connect to Redis")
+    }
+}
+
+#[async_trait]
+impl DeliveryLog for RedisDeliveryLog {
+    async fn record(&self, delivery: &WebhookDelivery) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let json = serde_json::to_string(delivery).context("Failed to serialize delivery")?;
+
+        let _: () = redis::pipe()
+            .atomic()
+            .lpush(DELIVERY_LOG_KEY, json)
+            .ltrim(DELIVERY_LOG_KEY, 0, self.capacity as isize - 1)
+            .query_async(&mut conn)
+            .await
+            .with_context(|| format!("Failed to record webhook delivery {}", delivery.id))?;
+
+        Ok(())
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<WebhookDelivery>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.connection().await?;
+        let stop = limit.min(self.capacity).saturating_sub(1) as isize;
+        let entries: Vec<String> = conn
+            .lrange(DELIVERY_LOG_KEY, 0, stop)
+            .await
+            .context("Failed to read webhook deliveries")?;
+
+        // Entries written by an incompatible server version are skipped
+        Ok(entries
+            .iter()
+            .filter_map(|json| serde_json::from_str(json).ok())
+            .collect())
+    }
+}
+
+/// Webhook secret store backed by a Redis hash
+#[derive(Debug, Clone)]
+pub struct RedisWebhookSecretStore {
+    client: redis::Client,
+}
+
+impl RedisWebhookSecretStore {
+    /// Create a store using an existing Redis client
+    pub fn new(client: redis::Client) -> Self {
+        Self { client }
+    }
+
+    /// Create a store from a Redis connection URL
+    pub fn from_url(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).context("Failed to create Redis client")?;
+        Ok(Self::new(client))
+    }
+
+    async fn connection(&self) -> Result<redis::aio::MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .context("Failed to connect to Redis")
+    }
+}
+
+#[async_trait]
+impl WebhookSecretStore for RedisWebhookSecretStore {
+    async fn get(&self, provider: &str, repo: &str) -> Result<Option<String>> {
+        let mut conn = self.connection().await?;
+        conn.hget(WEBHOOK_SECRETS_KEY, webhook_secret_key(provider, repo))
+            .await
+            .with_context(|| format!("Failed to read {} webhook secret for {}", provider, repo))
+    }
+
+    async fn set(&self, provider: &str, repo: &str, secret: &str) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let _: () = conn
+            .hset(
+                WEBHOOK_SECRETS_KEY,
+                webhook_secret_key(provider, repo),
+                secret,
+            )
+            .await
+            .with_context(|| format!("Failed to store {} webhook secret for {}", provider, repo))?;
+
+        Ok(())
+    }
+}
+
+/// Convert a job into hash fields
+fn job_to_fields(job: &Job) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("id", job.id.clone()),
+        ("repo", job.repo.clone()),
+        ("branch", job.branch.clone()),
+        ("commit", job.commit.clone()),
+        ("status", job.status.as_str().to_lowercase()),
+        ("started_at", job.started_at.to_rfc3339()),
+        ("attempts", job.attempts.to_string()),
+    ];
+
+    if let Some(finished_at) = job.finished_at {
+        fields.push(("finished_at", finished_at.to_rfc3339()));
+    }
+    if let Some(duration) = job.duration {
+        fields.push(("duration", duration.to_string()));
+    }
+    if let Some(ref agent_id) = job.agent_id {
+        fields.push(("agent_id", agent_id.clone()));
+    }
+    if let Some(exit_code) = job.exit_code {
+        fields.push(("exit_code", exit_code.to_string()));
+    }
+    if let Some(ref tag) = job.tag {
+        fields.push(("tag", tag.clone()));
+    }
+    if let Some(ref pr) = job.pull_request {
+        fields.push(("pr_number", pr.number.to_string()));
+        fields.push(("pr_base_branch", pr.base_branch.clone()));
+        fields.push(("pr_head_repo", pr.head_repo.clone()));
+        fields.push(("pr_head_ref", pr.head_ref.clone()));
+    }
+
+    fields
+}
+
+/// Parse a job from hash fields
+fn job_from_fields(data: &HashMap<String, String>) -> Result<Job> {
+    let field = |name: &str| {
+        data.get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Missing {}", name))
+    };
+
+    let status = field("status")?.parse::<JobStatus>()?;
+    let started_at = field("started_at")?
+        .parse::<DateTime<Utc>>()
+        .context("Invalid started_at")?;
+
+    Ok(Job {
+        id: field("id")?,
+        repo: field("repo")?,
+        branch: field("branch")?,
+        commit: field("commit")?,
+        status,
+        started_at,
+        finished_at: data
+            .get("finished_at")
+            .and_then(|s| s.parse::<DateTime<Utc>>().ok()),
+        duration: data.get("duration").and_then(|s| s.parse().ok()),
+        agent_id: data.get("agent_id").cloned(),
+        exit_code: data.get("exit_code").and_then(|s| s.parse().ok()),
+        attempts: data
+            .get("attempts")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default(),
+        pull_request: pull_request_from_fields(data),
+        tag: data.get("tag").cloned(),
+    })
+}
+
+/// Parse the pull request fields of a job, if present
+fn pull_request_from_fields(data: &HashMap<String, String>) -> Option<PullRequest> {
+    Some(PullRequest {
+        number: data.get("pr_number")?.parse().ok()?,
+        base_branch: data.get("pr_base_branch")?.clone(),
+        head_repo: data.get("pr_head_repo")?.clone(),
+        head_ref: data.get("pr_head_ref").cloned().unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_fields_roundtrip() {
+        let mut job = Job {
+            pull_request: Some(PullRequest {
+                number: 42,
+                base_branch: "main".to_string(),
+                head_repo: "fork/raibid-ci".to_string(),
+                head_ref: "refs/pull/42/head".to_string(),
+            }),
+            tag: Some("v1.2.3".to_string()),
+            ..Job::new("job-123", "raibid-ci", "main", "abc123")
+        };
+        job.mark_running("agent-1");
+        job.mark_finished(JobStatus::Success, Some(0));
+
+        let data: HashMap<String, String> = job_to_fields(&job)
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        let parsed = job_from_fields(&data).unwrap();
+
+        assert_eq!(parsed.id, "job-123");
+        assert_eq!(parsed.status, JobStatus::Success);
+        assert_eq!(parsed.agent_id.as_deref(), Some("agent-1"));
+        assert_eq!(parsed.exit_code, Some(0));
+        assert_eq!(parsed.attempts, 1);
+        assert!(parsed.finished_at.is_some());
+        assert_eq!(parsed.pull_request, job.pull_request);
+        assert_eq!(parsed.tag.as_deref(), Some("v1.2.3"));
+    }
+
+    #[test]
+    fn test_job_from_fields() {
+        let mut data = HashMap::new();
+        data.insert("id".to_string(), "job-123".to_string());
+        data.insert("repo".to_string(), "raibid-ci".to_string());
+        data.insert("branch".to_string(), "main".to_string());
+        data.insert("commit".to_string(), "abc123".to_string());
+        data.insert("status".to_string(), "running".to_string());
+        data.insert("started_at".to_string(), "2025-11-01T12:00:00Z".to_string());
+
+        let job = job_from_fields(&data).unwrap();
+        assert_eq!(job.id, "job-123");
+        assert_eq!(job.repo, "raibid-ci");
+        assert_eq!(job.status, JobStatus::Running);
+    }
+
+    #[test]
+    fn test_job_from_fields_missing_field() {
+        let mut data = HashMap::new();
+        data.insert("id".to_string(), "job-123".to_string());
+
+        assert!(job_from_fields(&data).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Webhook delivery log types
+//!
+//! The server records every webhook delivery it receives, whether it queued a job,
+//! was ignored or was rejected, in a capped delivery log. The log is listed by
+//! `GET /webhooks/deliveries` and read back by `raibid webhooks`; deliveries can be
+//! replayed once the cause of a rejection (e.g. a misconfigured secret) is fixed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Default number of deliveries kept in the log
+pub const DEFAULT_DELIVERY_LOG_SIZE: usize = 100;
+
+/// What came of a webhook delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryOutcome {
+    /// A job was queued
+    Queued,
+    /// The delivery was acknowledged without queueing a job
+    Ignored,
+    /// The signature or token was missing or invalid
+    Unauthorized,
+    /// The payload couldn't be parsed
+    Invalid,
+    /// The server failed to handle the delivery
+    Error,
+}
+
+impl DeliveryOutcome {
+    /// Get the outcome's label, as used in metrics and the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryOutcome::Queued => "queued",
+            DeliveryOutcome::Ignored => "ignored",
+            DeliveryOutcome::Unauthorized => "unauthorized",
+            DeliveryOutcome::Invalid => "invalid",
+            DeliveryOutcome::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for DeliveryOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A webhook delivery as received and handled by the server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookDelivery {
+    /// Unique record ID, used to replay the delivery
+    pub id: String,
+
+    /// Provider the delivery was sent to (e.g. "github", "gitea")
+    pub provider: String,
+
+    /// Event from the provider's event header
+    #[serde(default)]
+    pub event: Option<String>,
+
+    /// Provider's delivery ID, shared by redeliveries
+    #[serde(default)]
+    pub delivery_id: Option<String>,
+
+    /// When the server received the delivery
+    pub received_at: DateTime<Utc>,
+
+    /// Request headers, with secrets redacted
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Request body
+    #[serde(default)]
+    pub body: String,
+
+    /// What came of the delivery
+    pub outcome: DeliveryOutcome,
+
+    /// HTTP status returned to the provider
+    pub status: u16,
+
+    /// Response message or error
+    pub message: String,
+
+    /// Job queued by the delivery
+    #[serde(default)]
+    pub job_id: Option<String>,
+
+    /// Record this delivery replays
+    #[serde(default)]
+    pub replay_of: Option<String>,
+}
+
+/// Delivery log listing returned by the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryList {
+    /// Deliveries, newest first
+    pub deliveries: Vec<WebhookDelivery>,
+
+    /// Number of deliveries returned
+    pub total: usize,
+}
+
+impl WebhookDeliveryList {
+    /// Build a listing
+    pub fn new(deliveries: Vec<WebhookDelivery>) -> Self {
+        let total = deliveries.len();
+        Self { deliveries, total }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_serialization() {
+        assert_eq!(
+            serde_json::to_string(&DeliveryOutcome::Unauthorized).unwrap(),
+            r#""unauthorized""#
+        );
+        assert_eq!(DeliveryOutcome::Queued.to_string(), "queued");
+    }
+
+    #[test]
+    fn test_delivery_deserializes_without_optional_fields() {
+        let json = r#"{
+            "id": "d-1",
+            "provider": "github",
+            "received_at": "2024-01-01T00:00:00Z",
+            "outcome": "invalid",
+            "status": 400,
+            "message": "Invalid webhook payload"
+        }"#;
+
+        let delivery: WebhookDelivery = serde_json::from_str(json).unwrap();
+        assert_eq!(delivery.outcome, DeliveryOutcome::Invalid);
+        assert!(delivery.headers.is_empty());
+        assert_eq!(delivery.job_id, None);
+    }
+}
@@ -1,6 +1,22 @@
 //! Server configuration
 
+use raibid_common::agents::DEFAULT_AGENT_OFFLINE_SECS;
+use raibid_common::queue::{
+    DEFAULT_CONSUMER_GROUP, DEFAULT_JOB_STREAM, DEFAULT_JOB_STREAM_MAX_LENGTH,
+};
+use raibid_common::retention::{
+    RetentionPolicy, DEFAULT_KEEP_JOBS_PER_REPO, DEFAULT_MAX_JOB_AGE_SECS,
+};
+use raibid_common::storage::StorageConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::warn;
+
+/// Default time a webhook delivery ID is remembered (24 hours)
+pub const DEFAULT_WEBHOOK_DELIVERY_TTL_SECS: u64 = 86400;
+
+/// Default interval between job pruning runs (1 hour)
+pub const DEFAULT_PRUNE_INTERVAL_SECS: u64 = 3600;
 
 /// Server configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -23,14 +39,71 @@ pub struct ServerConfig {
     /// Redis connection URL
     pub redis_url: String,
 
+    /// Redis stream used as the job queue
+    pub job_stream: String,
+
+    /// Consumer group agents use to read the job queue
+    pub consumer_group: String,
+
+    /// Entries the job queue stream is trimmed to when pruning (0 = unlimited)
+    ///
+    /// Only acknowledged entries are trimmed, so a backlog of undelivered or
+    /// unacknowledged jobs can grow the stream past this.
+    pub job_stream_max_length: usize,
+
+    /// Gitea base URL used to validate manually triggered jobs
+    pub gitea_url: Option<String>,
+
+    /// Gitea API token
+    pub gitea_token: Option<String>,
+
+    /// GitHub API token, used to report commit statuses to mirrored repositories
+    pub github_token: Option<String>,
+
+    /// URL the server is reachable at, used to link commit statuses to jobs
+    pub public_url: Option<String>,
+
     /// Gitea webhook secret
     pub gitea_webhook_secret: Option<String>,
 
     /// GitHub webhook secret
     pub github_webhook_secret: Option<String>,
 
+    /// GitLab webhook secret token
+    pub gitlab_webhook_secret: Option<String>,
+
+    /// Forgejo webhook secret
+    pub forgejo_webhook_secret: Option<String>,
+
+    /// Webhook secrets by repository full name, overriding the provider secrets
+    pub repository_webhook_secrets: BTreeMap<String, String>,
+
     /// Rate limit (requests per minute)
     pub rate_limit_rpm: u64,
+
+    /// Seconds without a heartbeat after which an agent is reported offline
+    pub agent_offline_secs: u64,
+
+    /// Seconds a webhook delivery ID is remembered to drop redeliveries
+    pub webhook_delivery_ttl_secs: u64,
+
+    /// Cancel pending builds of a branch when a newer push to it is queued
+    pub coalesce_pushes: bool,
+
+    /// Serve the webhook delivery log and its replay endpoint
+    ///
+    /// Off by default: recorded deliveries include their payloads, and replaying
+    /// one queues builds without the provider's involvement.
+    pub delivery_api: bool,
+
+    /// Object store archived job logs are read from
+    pub storage: Option<StorageConfig>,
+
+    /// Finished jobs kept in Redis
+    pub retention: RetentionPolicy,
+
+    /// Seconds between pruning runs enforcing the retention policy (0 = disabled)
+    pub prune_interval_secs: u64,
 }
 
 impl Default for ServerConfig {
@@ -42,9 +115,26 @@ impl Default for ServerConfig {
             cors_enabled: true,
             max_body_size: 10 * 1024 * 1024, // 10MB
             redis_url: "redis://127.0.0.1:6379".to_string(),
+            job_stream: DEFAULT_JOB_STREAM.to_string(),
+            consumer_group: DEFAULT_CONSUMER_GROUP.to_string(),
+            job_stream_max_length: DEFAULT_JOB_STREAM_MAX_LENGTH,
+            gitea_url: None,
+            gitea_token: None,
+            github_token: None,
+            public_url: None,
             gitea_webhook_secret: None,
             github_webhook_secret: None,
+            gitlab_webhook_secret: None,
+            forgejo_webhook_secret: None,
+            repository_webhook_secrets: BTreeMap::new(),
             rate_limit_rpm: 100,
+            agent_offline_secs: DEFAULT_AGENT_OFFLINE_SECS,
+            webhook_delivery_ttl_secs: DEFAULT_WEBHOOK_DELIVERY_TTL_SECS,
+            coalesce_pushes: false,
+            delivery_api: false,
+            storage: None,
+            retention: RetentionPolicy::default(),
+            prune_interval_secs: DEFAULT_PRUNE_INTERVAL_SECS,
         }
     }
 }
@@ -60,9 +150,26 @@ impl ServerConfig {
             max_body_size: 10 * 1024 * 1024,
             redis_url: std::env::var("RAIBID_REDIS_URL")
                 .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string()),
+            job_stream: config.redis.job_stream.clone(),
+            consumer_group: config.redis.consumer_group.clone(),
+            job_stream_max_length: job_stream_max_length_from_env(),
+            gitea_url: Some(config.gitea.url.clone()),
+            gitea_token: std::env::var("GITEA_TOKEN").ok(),
+            github_token: std::env::var("GITHUB_TOKEN").ok(),
+            public_url: std::env::var("RAIBID_PUBLIC_URL").ok(),
             gitea_webhook_secret: std::env::var("RAIBID_GITEA_WEBHOOK_SECRET").ok(),
             github_webhook_secret: std::env::var("RAIBID_GITHUB_WEBHOOK_SECRET").ok(),
+            gitlab_webhook_secret: std::env::var("RAIBID_GITLAB_WEBHOOK_SECRET").ok(),
+            forgejo_webhook_secret: std::env::var("RAIBID_FORGEJO_WEBHOOK_SECRET").ok(),
+            repository_webhook_secrets: config.mirroring.webhooks.repository_secrets.clone(),
             rate_limit_rpm: 100,
+            agent_offline_secs: agent_offline_secs_from_env(),
+            webhook_delivery_ttl_secs: webhook_delivery_ttl_secs_from_env(),
+            coalesce_pushes: coalesce_pushes_from_env(),
+            delivery_api: delivery_api_from_env(),
+            storage: storage_from_env(),
+            retention: retention_from_env(),
+            prune_interval_secs: prune_interval_secs_from_env(),
         }
     }
 
@@ -85,16 +192,106 @@ impl ServerConfig {
                 .unwrap_or(10 * 1024 * 1024),
             redis_url: std::env::var("RAIBID_REDIS_URL")
                 .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string()),
+            job_stream: std::env::var("RAIBID_REDIS_JOB_STREAM")
+                .unwrap_or_else(|_| DEFAULT_JOB_STREAM.to_string()),
+            consumer_group: std::env::var("RAIBID_REDIS_CONSUMER_GROUP")
+                .unwrap_or_else(|_| DEFAULT_CONSUMER_GROUP.to_string()),
+            job_stream_max_length: job_stream_max_length_from_env(),
+            gitea_url: std::env::var("RAIBID_GITEA_URL").ok(),
+            gitea_token: std::env::var("GITEA_TOKEN").ok(),
+            github_token: std::env::var("GITHUB_TOKEN").ok(),
+            public_url: std::env::var("RAIBID_PUBLIC_URL").ok(),
             gitea_webhook_secret: std::env::var("RAIBID_GITEA_WEBHOOK_SECRET").ok(),
             github_webhook_secret: std::env::var("RAIBID_GITHUB_WEBHOOK_SECRET").ok(),
+            gitlab_webhook_secret: std::env::var("RAIBID_GITLAB_WEBHOOK_SECRET").ok(),
+            forgejo_webhook_secret: std::env::var("RAIBID_FORGEJO_WEBHOOK_SECRET").ok(),
+            repository_webhook_secrets: BTreeMap::new(),
             rate_limit_rpm: std::env::var("RAIBID_RATE_LIMIT_RPM")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(100),
+            agent_offline_secs: agent_offline_secs_from_env(),
+            webhook_delivery_ttl_secs: webhook_delivery_ttl_secs_from_env(),
+            coalesce_pushes: coalesce_pushes_from_env(),
+            delivery_api: delivery_api_from_env(),
+            storage: storage_from_env(),
+            retention: retention_from_env(),
+            prune_interval_secs: prune_interval_secs_from_env(),
         }
     }
 }
 
+/// Read the agent offline threshold from `RAIBID_AGENT_OFFLINE_SECS`
+fn agent_offline_secs_from_env() -> u64 {
+    std::env::var("RAIBID_AGENT_OFFLINE_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_AGENT_OFFLINE_SECS)
+}
+
+/// Read the webhook delivery TTL from `RAIBID_WEBHOOK_DELIVERY_TTL_SECS`
+fn webhook_delivery_ttl_secs_from_env() -> u64 {
+    std::env::var("RAIBID_WEBHOOK_DELIVERY_TTL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_WEBHOOK_DELIVERY_TTL_SECS)
+}
+
+/// Read the job queue stream cap from `RAIBID_JOB_STREAM_MAX_LENGTH`
+fn job_stream_max_length_from_env() -> usize {
+    std::env::var("RAIBID_JOB_STREAM_MAX_LENGTH")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_JOB_STREAM_MAX_LENGTH)
+}
+
+/// Read the retention policy from `RAIBID_RETENTION_KEEP_PER_REPO` and
+/// `RAIBID_RETENTION_MAX_AGE_SECS`
+fn retention_from_env() -> RetentionPolicy {
+    RetentionPolicy {
+        keep_per_repo: std::env::var("RAIBID_RETENTION_KEEP_PER_REPO")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_KEEP_JOBS_PER_REPO),
+        max_age_secs: std::env::var("RAIBID_RETENTION_MAX_AGE_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MAX_JOB_AGE_SECS),
+    }
+}
+
+/// Read the pruning interval from `RAIBID_PRUNE_INTERVAL_SECS`
+fn prune_interval_secs_from_env() -> u64 {
+    std::env::var("RAIBID_PRUNE_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_PRUNE_INTERVAL_SECS)
+}
+
+/// Read the object store configuration, ignoring it if incomplete
+fn storage_from_env() -> Option<StorageConfig> {
+    StorageConfig::from_env().unwrap_or_else(|e| {
+        warn!("Ignoring object storage configuration: {:#}", e);
+        None
+    })
+}
+
+/// Read the coalesce mode from `RAIBID_COALESCE_PUSHES`
+fn coalesce_pushes_from_env() -> bool {
+    std::env::var("RAIBID_COALESCE_PUSHES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(false)
+}
+
+/// Read whether the delivery log API is served from `RAIBID_WEBHOOK_DELIVERY_API`
+fn delivery_api_from_env() -> bool {
+    std::env::var("RAIBID_WEBHOOK_DELIVERY_API")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,7 +305,17 @@ mod tests {
         assert!(config.cors_enabled);
         assert_eq!(config.max_body_size, 10 * 1024 * 1024);
         assert_eq!(config.redis_url, "redis://127.0.0.1:6379");
+        assert_eq!(config.job_stream, "raibid:jobs");
+        assert_eq!(config.consumer_group, "raibid-workers");
+        assert!(config.gitea_url.is_none());
         assert_eq!(config.rate_limit_rpm, 100);
+        assert_eq!(config.agent_offline_secs, 30);
+        assert_eq!(config.webhook_delivery_ttl_secs, 86400);
+        assert!(!config.coalesce_pushes);
+        assert!(!config.delivery_api);
+        assert_eq!(config.job_stream_max_length, 10000);
+        assert_eq!(config.retention.keep_per_repo, 100);
+        assert_eq!(config.prune_interval_secs, 3600);
     }
 
     #[test]
@@ -120,6 +327,30 @@ mod tests {
         assert!(config.port > 0);
     }
 
+    #[test]
+    fn test_from_common_config_uses_job_stream() {
+        let mut common = raibid_common::Config::default();
+        common.redis.job_stream = "custom:jobs".to_string();
+        common.redis.consumer_group = "custom-workers".to_string();
+        common
+            .mirroring
+            .webhooks
+            .repository_secrets
+            .insert("owner/repo".to_string(), "repo-secret".to_string());
+
+        let config = ServerConfig::from_common_config(&common);
+        assert_eq!(config.job_stream, "custom:jobs");
+        assert_eq!(config.consumer_group, "custom-workers");
+        assert_eq!(config.gitea_url, Some(common.gitea.url.clone()));
+        assert_eq!(
+            config
+                .repository_webhook_secrets
+                .get("owner/repo")
+                .map(String::as_str),
+            Some("repo-secret")
+        );
+    }
+
     #[test]
     fn test_config_clone() {
         let config = ServerConfig::default();
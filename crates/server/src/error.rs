@@ -26,6 +26,10 @@ pub enum ServerError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    /// Conflict with the current resource state
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     /// Configuration error
     #[error("Configuration error: {0}")]
     Config(String),
@@ -38,6 +42,10 @@ pub enum ServerError {
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
+    /// A feature the request needs isn't configured on this server
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
     /// Redis error
     #[error("Redis error: {0}")]
     Redis(#[from] redis::RedisError),
@@ -49,6 +57,10 @@ pub enum ServerError {
     /// JSON parsing error
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
+
+    /// Job store error
+    #[error("Job store error: {0:#}")]
+    Store(#[from] anyhow::Error),
 }
 
 /// Error response for JSON API
@@ -65,27 +77,41 @@ pub struct ErrorResponse {
     pub request_id: Option<String>,
 }
 
+impl ServerError {
+    /// Get the HTTP status code the error is reported with
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ServerError::BadRequest(_) | ServerError::Json(_) => StatusCode::BAD_REQUEST,
+            ServerError::NotFound(_) => StatusCode::NOT_FOUND,
+            ServerError::Conflict(_) => StatusCode::CONFLICT,
+            ServerError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ServerError::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            ServerError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ServerError::Internal(_)
+            | ServerError::Config(_)
+            | ServerError::Redis(_)
+            | ServerError::Io(_)
+            | ServerError::Store(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
 impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            ServerError::Internal(ref msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-            ServerError::BadRequest(ref msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-            ServerError::NotFound(ref msg) => (StatusCode::NOT_FOUND, msg.clone()),
-            ServerError::Config(ref msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-            ServerError::Unauthorized(ref msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
-            ServerError::RateLimitExceeded => (
-                StatusCode::TOO_MANY_REQUESTS,
-                "Rate limit exceeded".to_string(),
-            ),
-            ServerError::Redis(ref err) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Redis error: {}", err),
-            ),
-            ServerError::Io(ref err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
-            ServerError::Json(ref err) => (
-                StatusCode::BAD_REQUEST,
-                format!("JSON parsing error: {}", err),
-            ),
+        let status = self.status_code();
+        let error_message = match self {
+            ServerError::Internal(ref msg)
+            | ServerError::BadRequest(ref msg)
+            | ServerError::NotFound(ref msg)
+            | ServerError::Conflict(ref msg)
+            | ServerError::Config(ref msg)
+            | ServerError::Unauthorized(ref msg)
+            | ServerError::ServiceUnavailable(ref msg) => msg.clone(),
+            ServerError::RateLimitExceeded => "Rate limit exceeded".to_string(),
+            ServerError::Redis(ref err) => format!("Redis error: {}", err),
+            ServerError::Io(ref err) => err.to_string(),
+            ServerError::Json(ref err) => format!("JSON parsing error: {}", err),
+            ServerError::Store(ref err) => format!("Job store error: {:#}", err),
         };
 
         let body = Json(ErrorResponse {
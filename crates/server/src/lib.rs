@@ -13,6 +13,9 @@
 //! - `config`: Configuration management
 //! - `state`: Shared application state
 //! - `routes`: HTTP route handlers
+//! - `queue`: Job enqueueing shared by routes
+//! - `retention`: Pruning of expired jobs
+//! - `metrics`: Prometheus metrics
 //! - `middleware`: Custom middleware (logging, auth, etc.)
 //! - `error`: Error types and handling
 //!
@@ -24,24 +27,32 @@
 //! #[tokio::main]
 //! async fn main() -> anyhow::Result<()> {
 //!     let config = ServerConfig::default();
-//!     let server = Server::new(config);
+//!     let server = Server::new(config)?;
 //!     server.run().await
 //! }
 //! ```
 
 pub mod config;
 pub mod error;
+pub mod metrics;
 pub mod middleware;
+pub mod queue;
+pub mod retention;
 pub mod routes;
 pub mod state;
 
+use raibid_common::commit_status::CommitStatusReporter;
+use raibid_common::config::GiteaConfig;
+use raibid_common::gitea_api::GiteaClient;
+use raibid_common::github::GitHubClient;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::Router;
 use tokio::signal;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{info, warn};
 
 pub use config::ServerConfig;
 pub use error::{ServerError, ServerResult};
@@ -55,9 +66,47 @@ pub struct Server {
 
 impl Server {
     /// Create a new server instance
-    pub fn new(config: ServerConfig) -> Self {
-        let state = Arc::new(AppState::new());
-        Self { config, state }
+    ///
+    /// Fails if the Redis URL is invalid, since the server can't queue jobs
+    /// without Redis.
+    pub fn new(config: ServerConfig) -> ServerResult<Self> {
+        let mut state = AppState::from_server_config(&config).map_err(|e| {
+            ServerError::Config(format!("Invalid Redis URL {}: {}", config.redis_url, e))
+        })?;
+
+        if let Some(ref gitea_url) = config.gitea_url {
+            let gitea_config = GiteaConfig {
+                url: gitea_url.clone(),
+                ..Default::default()
+            };
+            match GiteaClient::new(gitea_config, config.gitea_token.clone()) {
+                Ok(client) => state = state.with_gitea_client(client),
+                Err(e) => warn!("Failed to create Gitea client for {}: {}", gitea_url, e),
+            }
+        }
+
+        // Posting statuses needs write access, so only report with a token
+        if config.gitea_token.is_some() {
+            state = match status_reporter(&config, state.gitea_client().cloned()) {
+                Ok(reporter) => state.with_status_reporter(reporter),
+                Err(e) => {
+                    warn!("Failed to create commit status reporter: {}", e);
+                    state
+                }
+            };
+        }
+
+        if let Some(ref storage) = config.storage {
+            match storage.open() {
+                Ok(store) => state = state.with_object_store(store),
+                Err(e) => warn!("Failed to open object store: {:#}", e),
+            }
+        }
+
+        Ok(Self {
+            config,
+            state: Arc::new(state),
+        })
     }
 
     /// Create a new server instance with custom state
@@ -72,8 +121,13 @@ impl Server {
     fn build_router(&self) -> Router {
         Router::new()
             .merge(routes::health::routes())
-            .merge(routes::jobs::routes())
+            .merge(api_routes())
+            .nest("/api", api_routes())
             .merge(routes::webhooks::routes())
+            .layer(axum::middleware::from_fn_with_state(
+                self.state.clone(),
+                middleware::track_metrics,
+            ))
             .layer(TraceLayer::new_for_http())
             .layer(middleware::request_id::RequestIdLayer)
             .with_state(self.state.clone())
@@ -95,10 +149,22 @@ impl Server {
         let listener = tokio::net::TcpListener::bind(addr).await?;
         info!("Server listening on {}", addr);
 
+        let prune_task = (self.config.prune_interval_secs > 0).then(|| {
+            retention::spawn_prune_task(
+                self.state.clone(),
+                Duration::from_secs(self.config.prune_interval_secs),
+            )
+        });
+
         // Run server with graceful shutdown
-        axum::serve(listener, app)
+        let served = axum::serve(listener, app)
             .with_graceful_shutdown(shutdown_signal())
-            .await?;
+            .await;
+
+        if let Some(task) = prune_task {
+            task.abort();
+        }
+        served?;
 
         info!("Server shutdown complete");
 
@@ -138,6 +204,34 @@ impl Server {
     }
 }
 
+/// Create the commit status reporter, reporting to GitHub if a token is configured
+fn status_reporter(
+    config: &ServerConfig,
+    gitea_client: Option<GiteaClient>,
+) -> anyhow::Result<CommitStatusReporter> {
+    let github_client = match &config.github_token {
+        Some(token) => Some(GitHubClient::from_token(token.clone())?),
+        None => None,
+    };
+
+    Ok(CommitStatusReporter::new(
+        gitea_client,
+        github_client,
+        config.public_url.clone(),
+    ))
+}
+
+/// Routes served both at the root and under `/api`
+fn api_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .merge(routes::jobs::routes())
+        .merge(routes::logs::routes())
+        .merge(routes::artifacts::routes())
+        .merge(routes::agents::routes())
+        .merge(routes::metrics::routes())
+        .merge(routes::webhooks::deliveries::routes())
+}
+
 /// Graceful shutdown signal handler
 async fn shutdown_signal() {
     let ctrl_c = async {
@@ -10,6 +10,6 @@ async fn main() -> anyhow::Result<()> {
     let config = ServerConfig::from_env();
 
     // Create and run the server
-    let server = Server::new(config);
+    let server = Server::new(config)?;
     server.run().await
 }
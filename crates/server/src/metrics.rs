@@ -0,0 +1,227 @@
+//! Prometheus metrics
+//!
+//! HTTP requests and webhook deliveries are recorded as they are handled. Queue
+//! and job metrics are refreshed from Redis and the job store each time
+//! `GET /metrics` is scraped. Finished jobs are exposed as counters from the job
+//! store's outcome counts rather than as record counts, so pruning old records
+//! doesn't make them go backwards.
+
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use raibid_common::jobs::JobStatus;
+use raibid_common::QueueMetrics;
+use std::time::Duration;
+
+use crate::error::ServerError;
+
+/// Content type of the Prometheus text exposition format
+pub const TEXT_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Route label for requests that matched no route
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Server metrics registry
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    http_request_duration: HistogramVec,
+    http_requests: IntCounter,
+    http_requests_in_flight: IntGauge,
+    webhooks: IntCounterVec,
+    queue_length: IntGauge,
+    queue_depth: IntGauge,
+    queue_pending: IntGauge,
+    queue_oldest_pending: IntGauge,
+    jobs: IntGaugeVec,
+    jobs_finished: IntCounterVec,
+}
+
+impl Metrics {
+    /// Create a registry with all server metrics
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "raibid_http_request_duration_seconds",
+                "HTTP request latency by method, route and status",
+            ),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric");
+        let http_requests = IntCounter::new("raibid_http_requests_total", "HTTP requests handled")
+            .expect("valid metric");
+        let http_requests_in_flight = IntGauge::new(
+            "raibid_http_requests_in_flight",
+            "HTTP requests currently being handled",
+        )
+        .expect("valid metric");
+        let webhooks = IntCounterVec::new(
+            Opts::new(
+                "raibid_webhooks_total",
+                "Webhook deliveries by provider and result",
+            ),
+            &["provider", "result"],
+        )
+        .expect("valid metric");
+        let queue_length = IntGauge::new(
+            "raibid_queue_length",
+            "Entries in the job queue stream, including acknowledged ones",
+        )
+        .expect("valid metric");
+        let queue_depth = IntGauge::new(
+            "raibid_queue_depth",
+            "Jobs waiting to be delivered to an agent",
+        )
+        .expect("valid metric");
+        let queue_pending = IntGauge::new(
+            "raibid_queue_pending",
+            "Jobs delivered to an agent but not yet acknowledged",
+        )
+        .expect("valid metric");
+        let queue_oldest_pending = IntGauge::new(
+            "raibid_queue_oldest_pending_seconds",
+            "Age of the oldest unacknowledged job",
+        )
+        .expect("valid metric");
+        let jobs = IntGaugeVec::new(
+            Opts::new("raibid_jobs", "Pending and running jobs by status"),
+            &["status"],
+        )
+        .expect("valid metric");
+        let jobs_finished = IntCounterVec::new(
+            Opts::new("raibid_jobs_finished_total", "Finished jobs by outcome"),
+            &["status"],
+        )
+        .expect("valid metric");
+
+        for collector in [
+            Box::new(http_request_duration.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(http_requests.clone()),
+            Box::new(http_requests_in_flight.clone()),
+            Box::new(webhooks.clone()),
+            Box::new(queue_length.clone()),
+            Box::new(queue_depth.clone()),
+            Box::new(queue_pending.clone()),
+            Box::new(queue_oldest_pending.clone()),
+            Box::new(jobs.clone()),
+            Box::new(jobs_finished.clone()),
+        ] {
+            registry.register(collector).expect("unique metric");
+        }
+
+        Self {
+            registry,
+            http_request_duration,
+            http_requests,
+            http_requests_in_flight,
+            webhooks,
+            queue_length,
+            queue_depth,
+            queue_pending,
+            queue_oldest_pending,
+            jobs,
+            jobs_finished,
+        }
+    }
+
+    /// Record a handled HTTP request
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        self.http_request_duration
+            .with_label_values(&[method, route, &status.to_string()])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Record a webhook delivery
+    pub fn record_webhook(&self, provider: &str, result: &str) {
+        self.webhooks.with_label_values(&[provider, result]).inc();
+    }
+
+    /// Publish the request and in-flight counts tracked by the application state
+    pub fn set_http_totals(&self, requests: u64, in_flight: u64) {
+        let reported = self.http_requests.get();
+        self.http_requests.inc_by(requests.saturating_sub(reported));
+        self.http_requests_in_flight.set(in_flight as i64);
+    }
+
+    /// Publish a queue snapshot
+    pub fn set_queue(&self, queue: &QueueMetrics) {
+        self.queue_length.set(queue.length as i64);
+        self.queue_depth.set(queue.depth() as i64);
+        self.queue_pending.set(queue.pending as i64);
+        self.queue_oldest_pending
+            .set(queue.oldest_pending_age_secs.unwrap_or(0) as i64);
+    }
+
+    /// Publish the number of jobs in a status
+    pub fn set_jobs(&self, status: JobStatus, count: usize) {
+        let label = status.as_str().to_lowercase();
+        self.jobs.with_label_values(&[&label]).set(count as i64);
+    }
+
+    /// Publish the number of jobs that have finished with an outcome so far
+    pub fn set_jobs_finished(&self, status: JobStatus, total: u64) {
+        let label = status.as_str().to_lowercase();
+        let counter = self.jobs_finished.with_label_values(&[&label]);
+        counter.inc_by(total.saturating_sub(counter.get()));
+    }
+
+    /// Render all metrics in the Prometheus text format
+    pub fn render(&self) -> Result<String, ServerError> {
+        TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .map_err(|e| ServerError::Internal(format!("Failed to encode metrics: {}", e)))
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_recorded_metrics() {
+        let metrics = Metrics::new();
+        metrics.observe_request("GET", "/jobs/{id}", 200, Duration::from_millis(12));
+        metrics.record_webhook("github", "queued");
+        metrics.set_http_totals(5, 1);
+        metrics.set_http_totals(7, 0);
+        metrics.set_jobs(JobStatus::Running, 2);
+        metrics.set_jobs_finished(JobStatus::Success, 3);
+        metrics.set_jobs_finished(JobStatus::Success, 1);
+
+        let text = metrics.render().unwrap();
+        assert!(text.contains(
+            r#"raibid_http_request_duration_seconds_count{method="GET",route="/jobs/{id}",status="200"} 1"#
+        ));
+        assert!(text.contains(r#"raibid_webhooks_total{provider="github",result="queued"} 1"#));
+        assert!(text.contains("raibid_http_requests_total 7"));
+        assert!(text.contains("raibid_http_requests_in_flight 0"));
+        assert!(text.contains(r#"raibid_jobs{status="running"} 2"#));
+        assert!(text.contains(r#"raibid_jobs_finished_total{status="success"} 3"#));
+    }
+
+    #[test]
+    fn test_set_queue() {
+        let mut queue = QueueMetrics::empty("raibid:jobs", "raibid-workers", 15);
+        queue.length = 10;
+        queue.lag = Some(4);
+        queue.pending = 2;
+        queue.oldest_pending_age_secs = Some(30);
+
+        let metrics = Metrics::new();
+        metrics.set_queue(&queue);
+
+        let text = metrics.render().unwrap();
+        assert!(text.contains("raibid_queue_depth 4"));
+        assert!(text.contains("raibid_queue_pending 2"));
+        assert!(text.contains("raibid_queue_oldest_pending_seconds 30"));
+    }
+}
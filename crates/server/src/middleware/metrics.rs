@@ -0,0 +1,91 @@
+//! Request metrics middleware
+//!
+//! Counts requests, tracks how many are in flight and records their latency by
+//! matched route, so `/jobs/{id}` is one series rather than one per job.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::metrics::UNMATCHED_ROUTE;
+use crate::state::AppState;
+
+/// Decrements the in-flight count when a request finishes or is dropped
+struct InFlight<'a>(&'a AppState);
+
+impl<'a> InFlight<'a> {
+    fn start(state: &'a AppState) -> Self {
+        state.increment_requests();
+        state.increment_connections();
+        Self(state)
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.decrement_connections();
+    }
+}
+
+/// Record request metrics
+pub async fn track_metrics(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let method = request.method().to_string();
+
+    let _in_flight = InFlight::start(&state);
+    let start = Instant::now();
+    let response = next.run(request).await;
+
+    state
+        .metrics()
+        .observe_request(&method, &route, response.status().as_u16(), start.elapsed());
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_requests_recorded_by_route() {
+        let state = Arc::new(AppState::new());
+        let app = Router::new()
+            .route("/jobs/{id}", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                track_metrics,
+            ))
+            .with_state(state.clone());
+
+        for uri in ["/jobs/a", "/jobs/b", "/missing"] {
+            app.clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(state.request_count(), 3);
+        assert_eq!(state.active_connections(), 0);
+
+        let text = state.metrics().render().unwrap();
+        assert!(text.contains(
+            r#"raibid_http_request_duration_seconds_count{method="GET",route="/jobs/{id}",status="200"} 2"#
+        ));
+        assert!(text.contains(r#"route="unmatched",status="404"} 1"#));
+    }
+}
@@ -1,5 +1,7 @@
 //! Server middleware
 
+pub mod metrics;
 pub mod request_id;
 
+pub use metrics::track_metrics;
 pub use request_id::RequestIdLayer;
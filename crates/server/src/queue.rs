@@ -0,0 +1,358 @@
+//! Job enqueueing and queue inspection
+//!
+//! Shared by the webhook handlers and the jobs API so that every job reaches
+//! agents through the same path.
+
+use raibid_common::metrics::{
+    count_completed, parse_stream_id, ConsumerMetrics, QueueMetrics, Throughput,
+};
+use raibid_common::queue::{cancel_key, JobEnvelope, CANCEL_KEY_TTL_SECS, ENVELOPE_FIELD};
+use raibid_common::store::MAX_LIST_LIMIT;
+use raibid_common::{Job, JobListQuery, JobStatus};
+use redis::streams::{
+    StreamInfoConsumersReply, StreamInfoGroupsReply, StreamPendingCountReply, StreamPendingReply,
+    StreamRangeReply,
+};
+use redis::AsyncCommands;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{error::ServerError, state::AppState};
+
+/// Create a pending job for a repository revision
+pub fn new_job(repo: String, branch: String, commit: String) -> Job {
+    Job::new(Uuid::new_v4().to_string(), repo, branch, commit)
+}
+
+/// Record a job and queue it to Redis Streams
+pub async fn enqueue_job(state: &AppState, envelope: &JobEnvelope) -> Result<String, ServerError> {
+    let mut conn = state.redis_connection().await?;
+
+    // Serialize envelope to JSON
+    let envelope_json = envelope.encode()?;
+
+    // Record the job before queueing so agents always find it in the store
+    state.job_store().save(&envelope.job).await?;
+
+    // Add job to Redis Stream using XADD; pruning trims it (see `trim_job_stream`)
+    let fields = [(ENVELOPE_FIELD, envelope_json.as_str())];
+    let queued: Result<String, redis::RedisError> =
+        conn.xadd(state.job_stream(), "*", &fields).await;
+
+    if let Err(e) = queued {
+        // Don't leave a pending record for a job no agent will ever see
+        let _ = state.job_store().delete(&envelope.job.id).await;
+        return Err(ServerError::Internal(format!("Failed to queue job: {}", e)));
+    }
+
+    state.report_status(&envelope.job);
+    Ok(envelope.job.id.clone())
+}
+
+/// Maximum number of entries trimmed from the job stream at once
+const TRIM_SCAN_LIMIT: usize = 10_000;
+
+/// Trim the job stream to about `job_stream_max_length` entries
+///
+/// Only entries every consumer group has acknowledged are trimmed. Entries not
+/// yet delivered, or delivered but not acknowledged, are kept even past the cap:
+/// their jobs would otherwise stay pending with no agent ever seeing them.
+/// Returns the number of entries removed.
+pub async fn trim_job_stream(state: &AppState) -> Result<usize, ServerError> {
+    let max_length = state.job_stream_max_length();
+    if max_length == 0 {
+        return Ok(0);
+    }
+
+    let mut conn = state.redis_connection().await?;
+    let stream = state.job_stream();
+    let length: usize = conn.xlen(stream).await?;
+    if length <= max_length {
+        return Ok(0);
+    }
+
+    // The oldest entry the cap keeps
+    let excess = (length - max_length).min(TRIM_SCAN_LIMIT);
+    let oldest: StreamRangeReply = conn.xrange_count(stream, "-", "+", excess + 1).await?;
+    let Some(cap_id) = oldest.ids.last().map(|entry| entry.id.clone()) else {
+        return Ok(0);
+    };
+
+    let groups: StreamInfoGroupsReply = conn.xinfo_groups(stream).await?;
+    let mut unacknowledged = Vec::with_capacity(groups.groups.len());
+    for group in groups.groups {
+        let oldest = match group.pending {
+            0 => group.last_delivered_id,
+            _ => match conn.xpending(stream, &group.name).await? {
+                StreamPendingReply::Data(data) => data.start_id,
+                StreamPendingReply::Empty => group.last_delivered_id,
+            },
+        };
+        unacknowledged.push(oldest);
+    }
+
+    let Some(min_id) = trim_min_id(&cap_id, &unacknowledged) else {
+        return Ok(0);
+    };
+    let removed: usize = redis::cmd("XTRIM")
+        .arg(stream)
+        .arg("MINID")
+        .arg("~")
+        .arg(min_id)
+        .query_async(&mut conn)
+        .await?;
+    Ok(removed)
+}
+
+/// Pick the ID the job stream can be trimmed below
+///
+/// `unacknowledged` holds, for each consumer group, its oldest pending entry, or
+/// its last delivered one if none is pending: entries from there on may still
+/// be needed. Without a group nothing has been delivered, so nothing is trimmed.
+fn trim_min_id<'a>(cap_id: &'a str, unacknowledged: &'a [String]) -> Option<&'a str> {
+    if unacknowledged.is_empty() {
+        return None;
+    }
+
+    std::iter::once(cap_id)
+        .chain(unacknowledged.iter().map(String::as_str))
+        .filter_map(|id| Some((parse_stream_id(id)?, id)))
+        .min()
+        .map(|(_, id)| id)
+}
+
+/// Set the cancellation key agents watch for a job
+pub async fn signal_cancel(state: &AppState, job_id: &str) -> Result<(), ServerError> {
+    let mut conn = state.redis_connection().await?;
+    let _: () = conn
+        .set_ex(cancel_key(job_id), "1", CANCEL_KEY_TTL_SECS)
+        .await
+        .map_err(|e| ServerError::Internal(format!("Failed to signal cancellation: {}", e)))?;
+    Ok(())
+}
+
+/// Cancel pending push builds of the same branch queued before a job
+///
+/// Release and pull request builds are never superseded. Returns the IDs of the
+/// cancelled jobs.
+pub async fn supersede_pending(state: &AppState, job: &Job) -> Result<Vec<String>, ServerError> {
+    let pending = state
+        .job_store()
+        .list(&JobListQuery {
+            status: Some(JobStatus::Pending),
+            repo: Some(job.repo.clone()),
+            branch: Some(job.branch.clone()),
+            limit: Some(MAX_LIST_LIMIT),
+            offset: None,
+        })
+        .await?;
+
+    let mut superseded = Vec::new();
+    for older in pending.jobs {
+        if older.id == job.id
+            || older.is_release()
+            || older.pull_request.is_some()
+            || older.started_at > job.started_at
+        {
+            continue;
+        }
+
+        // Agents skip cancelled jobs; the signal covers one being picked up now
+        if let Err(e) = signal_cancel(state, &older.id).await {
+            warn!("Failed to signal cancellation of job {}: {}", older.id, e);
+        }
+        // An agent that started the job first records the cancellation itself
+        let cancelled = state.job_store().cancel_pending(&older.id).await?;
+        if cancelled.status != JobStatus::Cancelled {
+            continue;
+        }
+        state.report_status(&cancelled);
+        superseded.push(older.id);
+    }
+
+    Ok(superseded)
+}
+
+/// Maximum number of stream entries scanned when computing queue metrics
+const METRICS_SCAN_LIMIT: usize = 10_000;
+
+/// Compute queue metrics from the job stream and its consumer group
+///
+/// Throughput counts entries added during the last `window_minutes`; counts are
+/// capped at [`METRICS_SCAN_LIMIT`] entries.
+pub async fn queue_metrics(
+    state: &AppState,
+    window_minutes: u64,
+) -> Result<QueueMetrics, ServerError> {
+    let mut conn = state.redis_connection().await?;
+    let stream = state.job_stream();
+    let group = state.consumer_group();
+    let mut metrics = QueueMetrics::empty(stream, group, window_minutes);
+
+    // Nothing has been queued yet
+    let exists: bool = conn.exists(stream).await?;
+    if !exists {
+        return Ok(metrics);
+    }
+
+    metrics.length = conn.xlen(stream).await?;
+
+    let groups: StreamInfoGroupsReply = conn.xinfo_groups(stream).await?;
+    let Some(info) = groups.groups.into_iter().find(|g| g.name == group) else {
+        // No agent has created the group yet, so nothing has been delivered
+        metrics.lag = None;
+        return Ok(metrics);
+    };
+
+    metrics.pending = info.pending as u64;
+    metrics.lag = match info.lag {
+        Some(lag) => Some(lag as u64),
+        None => {
+            // Older Redis versions don't report lag; count undelivered entries instead
+            let undelivered: StreamRangeReply = conn
+                .xrange_count(
+                    stream,
+                    format!("({}", info.last_delivered_id),
+                    "+",
+                    METRICS_SCAN_LIMIT,
+                )
+                .await?;
+            Some(undelivered.ids.len() as u64)
+        }
+    };
+
+    let consumers: StreamInfoConsumersReply = conn.xinfo_consumers(stream, group).await?;
+    metrics.consumers = consumers
+        .consumers
+        .into_iter()
+        .map(|consumer| ConsumerMetrics {
+            name: consumer.name,
+            pending: consumer.pending as u64,
+            idle_ms: consumer.idle as u64,
+        })
+        .collect();
+
+    let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+
+    let summary: StreamPendingReply = conn.xpending(stream, group).await?;
+    if let StreamPendingReply::Data(data) = summary {
+        metrics.oldest_pending_age_secs =
+            parse_stream_id(&data.start_id).map(|(millis, _)| now_ms.saturating_sub(millis) / 1000);
+    }
+
+    let window_start = format!(
+        "{}-0",
+        now_ms.saturating_sub(window_minutes.saturating_mul(60_000))
+    );
+    let window: StreamRangeReply = conn
+        .xrange_count(stream, &window_start, "+", METRICS_SCAN_LIMIT)
+        .await?;
+    let pending: StreamPendingCountReply = conn
+        .xpending_count(stream, group, &window_start, "+", METRICS_SCAN_LIMIT)
+        .await?;
+
+    let pending_ids: Vec<&str> = pending.ids.iter().map(|p| p.id.as_str()).collect();
+    let completed = count_completed(
+        window.ids.iter().map(|entry| entry.id.as_str()),
+        &info.last_delivered_id,
+        &pending_ids,
+    );
+    metrics.throughput = Throughput::new(window_minutes, window.ids.len() as u64, completed);
+
+    Ok(metrics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_job_is_pending() {
+        let job = new_job(
+            "owner/repo".to_string(),
+            "main".to_string(),
+            "abc123".to_string(),
+        );
+
+        assert!(!job.id.is_empty());
+        assert_eq!(job.repo, "owner/repo");
+        assert_eq!(job.status, JobStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_without_redis_leaves_no_record() {
+        let state = AppState::new();
+        let job = new_job(
+            "owner/repo".to_string(),
+            "main".to_string(),
+            "abc123".to_string(),
+        );
+        let envelope = JobEnvelope::new(job, "manual", None);
+
+        assert!(enqueue_job(&state, &envelope).await.is_err());
+        assert!(state
+            .job_store()
+            .get(&envelope.job.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_trim_min_id_keeps_unacknowledged_entries() {
+        // No consumer group yet: nothing has been delivered
+        assert_eq!(trim_min_id("500-0", &[]), None);
+
+        // A backlog past the cap keeps its undelivered entries
+        let groups = ["200-0".to_string()];
+        assert_eq!(trim_min_id("500-0", &groups), Some("200-0"));
+
+        // Everything before the cap has been acknowledged
+        let groups = ["900-0".to_string(), "700-1".to_string()];
+        assert_eq!(trim_min_id("500-0", &groups), Some("500-0"));
+
+        // The group lagging behind decides
+        let groups = [
+            "900-0".to_string(),
+            "500-0".to_string(),
+            "300-2".to_string(),
+        ];
+        assert_eq!(trim_min_id("500-1", &groups), Some("300-2"));
+    }
+
+    #[tokio::test]
+    async fn test_supersede_pending_push_builds() {
+        let state = AppState::new();
+        let job = |branch: &str| {
+            new_job(
+                "owner/repo".to_string(),
+                branch.to_string(),
+                "abc123".to_string(),
+            )
+        };
+
+        let older = job("main");
+        let other_branch = job("develop");
+        let mut release = job("main");
+        release.tag = Some("main".to_string());
+        for job in [&older, &other_branch, &release] {
+            state.job_store().save(job).await.unwrap();
+        }
+
+        let newer = job("main");
+        state.job_store().save(&newer).await.unwrap();
+
+        let superseded = supersede_pending(&state, &newer).await.unwrap();
+        assert_eq!(superseded, vec![older.id.clone()]);
+
+        for (job, expected) in [
+            (&older, JobStatus::Cancelled),
+            (&other_branch, JobStatus::Pending),
+            (&release, JobStatus::Pending),
+            (&newer, JobStatus::Pending),
+        ] {
+            let stored = state.job_store().get(&job.id).await.unwrap().unwrap();
+            assert_eq!(stored.status, expected, "job on {}", job.branch);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Job pruning
+//!
+//! Enforces the server's [`RetentionPolicy`](raibid_common::RetentionPolicy):
+//! expired jobs are deleted from the job store together with their log streams,
+//! cancellation keys, archived logs and artifacts, and acknowledged entries are
+//! trimmed from the job queue stream. A background task prunes periodically, and
+//! `POST /jobs/prune` prunes on demand.
+
+use chrono::Utc;
+use raibid_common::artifacts::artifact_key;
+use raibid_common::logs::log_archive_key;
+use raibid_common::queue::{cancel_key, log_stream_key};
+use raibid_common::{Job, PruneReport};
+use redis::AsyncCommands;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+
+use crate::{error::ServerError, queue::trim_job_stream, state::AppState};
+
+/// Prune the finished jobs the retention policy no longer keeps
+///
+/// With `dry_run`, only reports the jobs that would be pruned. Otherwise reports
+/// the jobs pruned: a job that can't be fully deleted is kept and left for the
+/// next run to retry.
+pub async fn prune_jobs(state: &AppState, dry_run: bool) -> Result<PruneReport, ServerError> {
+    let jobs = state.job_store().list_all().await?;
+    let mut expired: Vec<Job> = state
+        .retention()
+        .expired(&jobs, Utc::now())
+        .into_iter()
+        .cloned()
+        .collect();
+
+    if !dry_run {
+        let mut pruned = Vec::with_capacity(expired.len());
+        for job in expired {
+            match delete_job(state, &job.id).await {
+                Ok(()) => pruned.push(job),
+                Err(e) => warn!("Failed to prune job {}: {}", job.id, e),
+            }
+        }
+        expired = pruned;
+
+        if state.has_redis() {
+            match trim_job_stream(state).await {
+                Ok(0) => {}
+                Ok(removed) => info!("Trimmed {} entries from the job stream", removed),
+                Err(e) => warn!("Failed to trim the job stream: {}", e),
+            }
+        }
+    }
+
+    Ok(PruneReport {
+        dry_run,
+        jobs: expired,
+    })
+}
+
+/// Delete a job's stored objects and Redis keys, then its record
+///
+/// The record goes last so a failed deletion is retried by the next run.
+async fn delete_job(state: &AppState, job_id: &str) -> Result<(), ServerError> {
+    if let Some(store) = state.object_store() {
+        store.delete(&log_archive_key(job_id)).await?;
+        for artifact in state.job_store().get_artifacts(job_id).await? {
+            store.delete(&artifact_key(job_id, &artifact.name)).await?;
+        }
+    }
+
+    if state.has_redis() {
+        let mut conn = state.redis_connection().await?;
+        let _: usize = conn
+            .del(&[log_stream_key(job_id), cancel_key(job_id)])
+            .await?;
+    }
+
+    state.job_store().delete(job_id).await?;
+    Ok(())
+}
+
+/// Prune jobs every `interval` until the task is aborted
+pub fn spawn_prune_task(state: Arc<AppState>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            match prune_jobs(&state, false).await {
+                Ok(report) if !report.jobs.is_empty() => {
+                    info!("Pruned {} expired jobs", report.jobs.len())
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Failed to prune jobs: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use raibid_common::{JobStatus, RetentionPolicy};
+
+    fn finished_job(id: &str, age_secs: i64) -> Job {
+        let started_at = Utc::now() - chrono::Duration::seconds(age_secs);
+        Job {
+            status: JobStatus::Success,
+            started_at,
+            finished_at: Some(started_at),
+            exit_code: Some(0),
+            attempts: 1,
+            ..Job::new(id, "owner/repo", "main", "abc123")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prune_jobs() {
+        let state = AppState::new().with_retention(RetentionPolicy {
+            keep_per_repo: 1,
+            max_age_secs: 0,
+        });
+        state
+            .job_store()
+            .save(&finished_job("new", 10))
+            .await
+            .unwrap();
+        state
+            .job_store()
+            .save(&finished_job("old", 20))
+            .await
+            .unwrap();
+
+        let report = prune_jobs(&state, true).await.unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.jobs.len(), 1);
+        assert_eq!(report.jobs[0].id, "old");
+        assert!(state.job_store().get("old").await.unwrap().is_some());
+
+        let report = prune_jobs(&state, false).await.unwrap();
+        assert_eq!(report.jobs.len(), 1);
+        assert!(state.job_store().get("old").await.unwrap().is_none());
+        assert!(state.job_store().get("new").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_prune_deletes_log_archive() {
+        use raibid_common::storage::{LocalObjectStore, ObjectStore};
+
+        let storage = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalObjectStore::new(storage.path()));
+        let state = AppState::new()
+            .with_retention(RetentionPolicy {
+                keep_per_repo: 0,
+                max_age_secs: 1,
+            })
+            .with_object_store(store.clone());
+        for id in ["archived", "stuck"] {
+            state.job_store().save(&finished_job(id, 10)).await.unwrap();
+        }
+        store
+            .put(&log_archive_key("archived"), b"log".to_vec())
+            .await
+            .unwrap();
+        // A directory where the archive should be can't be deleted as a file
+        std::fs::create_dir_all(storage.path().join(log_archive_key("stuck")).join("x")).unwrap();
+
+        let report = prune_jobs(&state, false).await.unwrap();
+        let pruned: Vec<&str> = report.jobs.iter().map(|job| job.id.as_str()).collect();
+        assert_eq!(pruned, ["archived"]);
+        assert!(store
+            .get(&log_archive_key("archived"))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(state.job_store().get("archived").await.unwrap().is_none());
+
+        // The job whose archive couldn't be deleted is kept for the next run
+        assert!(state.job_store().get("stuck").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_prune_deletes_artifacts() {
+        use raibid_common::artifacts::Artifact;
+        use raibid_common::storage::{LocalObjectStore, ObjectStore};
+
+        let storage = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalObjectStore::new(storage.path()));
+        let state = AppState::new()
+            .with_retention(RetentionPolicy {
+                keep_per_repo: 0,
+                max_age_secs: 1,
+            })
+            .with_object_store(store.clone());
+        state
+            .job_store()
+            .save(&finished_job("old", 10))
+            .await
+            .unwrap();
+        let artifact = Artifact::new("dist/app.tar.gz", b"archive");
+        state
+            .job_store()
+            .save_artifacts("old", std::slice::from_ref(&artifact))
+            .await
+            .unwrap();
+        store
+            .put(&artifact_key("old", &artifact.name), b"archive".to_vec())
+            .await
+            .unwrap();
+
+        let report = prune_jobs(&state, false).await.unwrap();
+        assert_eq!(report.jobs.len(), 1);
+        assert!(store
+            .get(&artifact_key("old", &artifact.name))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(state.job_store().get("old").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,83 @@
+//! Agent pool routes
+
+use axum::{extract::State, routing::get, Json, Router};
+use std::sync::Arc;
+
+use crate::{error::ServerError, state::AppState};
+use raibid_common::AgentList;
+
+/// Create agent routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/agents", get(list_agents))
+}
+
+/// GET /agents - List registered agents, marking those that missed heartbeats offline
+async fn list_agents(State(state): State<Arc<AppState>>) -> Result<Json<AgentList>, ServerError> {
+    let now = chrono::Utc::now();
+    let offline_after = state.agent_offline_after();
+
+    let agents = state
+        .agent_registry()
+        .list()
+        .await?
+        .into_iter()
+        .map(|agent| agent.with_liveness(now, offline_after))
+        .collect();
+
+    Ok(Json(AgentList::new(agents)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::{body::Body, http::Request};
+    use raibid_common::{AgentInfo, AgentStatus};
+    use tower::ServiceExt;
+
+    fn agent(id: &str, heartbeat_age_secs: i64) -> AgentInfo {
+        let now = chrono::Utc::now();
+        AgentInfo {
+            id: id.to_string(),
+            hostname: "dgx-01".to_string(),
+            version: "0.1.0".to_string(),
+            status: AgentStatus::Busy,
+            current_jobs: vec!["job-1".to_string()],
+            capacity: 2,
+            load: Some(0.5),
+            started_at: now - chrono::Duration::hours(1),
+            last_heartbeat: now - chrono::Duration::seconds(heartbeat_age_secs),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_agents_marks_missed_heartbeats_offline() {
+        let state = AppState::new();
+        let registry = state.agent_registry();
+        registry.heartbeat(&agent("agent-live", 5)).await.unwrap();
+        registry.heartbeat(&agent("agent-gone", 120)).await.unwrap();
+
+        let app = routes().with_state(Arc::new(state));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/agents")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: AgentList = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(list.total, 2);
+        assert_eq!(list.online, 1);
+        assert_eq!(list.agents[0].id, "agent-gone");
+        assert_eq!(list.agents[0].status, AgentStatus::Offline);
+        assert_eq!(list.agents[1].status, AgentStatus::Busy);
+    }
+}
@@ -0,0 +1,204 @@
+//! Job artifacts
+//!
+//! Agents upload the files matching a pipeline's `artifacts` patterns to the
+//! object store once the job succeeds, and record them with the job. Clients
+//! list a job's artifacts, then download them one at a time by name; the
+//! `x-checksum-sha256` header of each download lets them verify its contents.
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::{error::ServerError, state::AppState};
+use raibid_common::artifacts::artifact_key;
+use raibid_common::JobArtifacts;
+
+/// Header carrying the hex-encoded SHA-256 checksum of a download
+pub const CHECKSUM_HEADER: &str = "x-checksum-sha256";
+
+/// Create artifact routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/jobs/{id}/artifacts", get(list_artifacts))
+        .route("/jobs/{id}/artifacts/{*name}", get(download_artifact))
+}
+
+/// GET /jobs/{id}/artifacts - List a job's artifacts
+///
+/// Artifacts are recorded when the job finishes; until then the list is empty.
+async fn list_artifacts(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<JobArtifacts>, ServerError> {
+    if state.job_store().get(&id).await?.is_none() {
+        return Err(ServerError::NotFound(format!("Job not found: {}", id)));
+    }
+
+    let artifacts = state.job_store().get_artifacts(&id).await?;
+
+    Ok(Json(JobArtifacts {
+        job_id: id,
+        artifacts,
+    }))
+}
+
+/// GET /jobs/{id}/artifacts/{name} - Download one of a job's artifacts
+async fn download_artifact(
+    State(state): State<Arc<AppState>>,
+    Path((id, name)): Path<(String, String)>,
+) -> Result<Response, ServerError> {
+    if state.job_store().get(&id).await?.is_none() {
+        return Err(ServerError::NotFound(format!("Job not found: {}", id)));
+    }
+
+    // Only listed artifacts are served, so names can't reach other objects
+    let artifact = state
+        .job_store()
+        .get_artifacts(&id)
+        .await?
+        .into_iter()
+        .find(|artifact| artifact.name == name)
+        .ok_or_else(|| ServerError::NotFound(format!("Artifact not found: {}", name)))?;
+
+    let store = state
+        .object_store()
+        .ok_or_else(|| ServerError::Internal("Object storage is not configured".to_string()))?;
+    let data = store
+        .get(&artifact_key(&id, &artifact.name))
+        .await?
+        .ok_or_else(|| ServerError::NotFound(format!("Artifact not found: {}", name)))?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, "application/octet-stream".to_string()),
+            (CONTENT_DISPOSITION, content_disposition(&artifact.name)),
+        ],
+        [(CHECKSUM_HEADER, artifact.sha256)],
+        Body::from_stream(data),
+    )
+        .into_response())
+}
+
+/// Build the `Content-Disposition` header of a download, named after the file
+///
+/// Characters that can't appear in a quoted header value are replaced.
+fn content_disposition(name: &str) -> String {
+    let file_name: String = name
+        .rsplit('/')
+        .next()
+        .unwrap_or(name)
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' => '_',
+            c if c == ' ' || c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .collect();
+
+    format!("attachment; filename=\"{}\"", file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{Request, StatusCode};
+    use raibid_common::storage::{LocalObjectStore, ObjectStore};
+    use raibid_common::{Artifact, Job, JobStatus};
+    use tower::ServiceExt;
+
+    fn sample_job() -> Job {
+        Job {
+            status: JobStatus::Success,
+            exit_code: Some(0),
+            ..Job::new("job-1", "raibid-ci", "main", "abc123")
+        }
+    }
+
+    async fn get(app: &Router, uri: &str) -> Response {
+        app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_content_disposition() {
+        assert_eq!(
+            content_disposition("target/release/raibid"),
+            "attachment; filename=\"raibid\""
+        );
+        assert_eq!(
+            content_disposition("dist/\"app\"\n.tar.gz"),
+            "attachment; filename=\"_app__.tar.gz\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_artifacts() {
+        let storage = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalObjectStore::new(storage.path()));
+        store
+            .put(
+                &artifact_key("job-1", "dist/app.tar.gz"),
+                b"archive".to_vec(),
+            )
+            .await
+            .unwrap();
+
+        let state = AppState::new().with_object_store(store);
+        state.job_store().save(&sample_job()).await.unwrap();
+        state
+            .job_store()
+            .save_artifacts(
+                "job-1",
+                &[
+                    Artifact::new("dist/app.tar.gz", b"archive"),
+                    Artifact::new("dist/lost.txt", b"lost"),
+                ],
+            )
+            .await
+            .unwrap();
+        let app = routes().with_state(Arc::new(state));
+
+        let response = get(&app, "/jobs/job-1/artifacts").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: JobArtifacts = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list.job_id, "job-1");
+        assert_eq!(list.artifacts.len(), 2);
+
+        let response = get(&app, "/jobs/job-1/artifacts/dist/app.tar.gz").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[CHECKSUM_HEADER],
+            list.artifacts[0].sha256.as_str()
+        );
+        assert_eq!(
+            response.headers()[CONTENT_DISPOSITION],
+            "attachment; filename=\"app.tar.gz\""
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"archive");
+
+        // Listed but missing from the store
+        let response = get(&app, "/jobs/job-1/artifacts/dist/lost.txt").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Not listed
+        let response = get(&app, "/jobs/job-1/artifacts/dist/other.txt").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = get(&app, "/jobs/missing/artifacts").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
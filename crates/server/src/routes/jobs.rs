@@ -2,22 +2,22 @@
 
 use axum::{
     extract::{Path, Query, State},
-    response::{
-        sse::{Event, KeepAlive},
-        Sse,
-    },
-    routing::get,
+    http::StatusCode,
+    routing::{get, post},
     Json, Router,
 };
-use futures::stream::{self, Stream};
-use redis::AsyncCommands;
-use serde::{Deserialize, Serialize};
-use std::convert::Infallible;
+use serde::Deserialize;
 use std::sync::Arc;
-use std::time::Duration;
 
-use crate::{error::ServerError, state::AppState};
-use raibid_common::{Job, JobStatus};
+use crate::{
+    error::ServerError,
+    queue::{enqueue_job, new_job, signal_cancel},
+    retention,
+    state::AppState,
+};
+use raibid_common::queue::JobEnvelope;
+use raibid_common::{Job, JobList, JobListQuery, JobStatus, JobSteps, JobTrigger, PruneReport};
+use tracing::{info, warn};
 
 /// Query parameters for job list endpoint
 #[derive(Debug, Deserialize)]
@@ -32,133 +32,118 @@ pub struct JobsQueryParams {
     pub limit: Option<usize>,
     /// Pagination offset
     pub offset: Option<usize>,
-    /// Cursor for cursor-based pagination
-    pub cursor: Option<String>,
 }
 
-/// Response with pagination cursor
-#[derive(Debug, Serialize)]
-pub struct JobListResponse {
-    /// Jobs list
-    pub jobs: Vec<Job>,
-    /// Total count
-    pub total: usize,
-    /// Current offset
-    pub offset: usize,
-    /// Current limit
-    pub limit: usize,
-    /// Next cursor (for cursor-based pagination)
-    pub next_cursor: Option<String>,
+/// Query parameters for the prune endpoint
+#[derive(Debug, Deserialize)]
+pub struct PruneQueryParams {
+    /// Report the jobs that would be pruned without removing them
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 /// Create job routes
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
-        .route("/jobs", get(list_jobs))
+        .route("/jobs", get(list_jobs).post(trigger_job))
+        .route("/jobs/prune", post(prune_jobs))
         .route("/jobs/{id}", get(get_job))
-        .route("/jobs/{id}/logs", get(get_job_logs))
+        .route("/jobs/{id}/cancel", post(cancel_job))
+        .route("/jobs/{id}/steps", get(get_job_steps))
 }
 
 /// GET /jobs - List all jobs with filtering and pagination
 async fn list_jobs(
     State(state): State<Arc<AppState>>,
     Query(params): Query<JobsQueryParams>,
-) -> Result<Json<JobListResponse>, ServerError> {
+) -> Result<Json<JobList>, ServerError> {
     // Parse status filter if provided
-    let status_filter = if let Some(status_str) = &params.status {
-        Some(
+    let status = params
+        .status
+        .as_deref()
+        .map(|status_str| {
             status_str
                 .parse::<JobStatus>()
-                .map_err(|_| ServerError::BadRequest(format!("Invalid status: {}", status_str)))?,
-        )
-    } else {
-        None
-    };
+                .map_err(|_| ServerError::BadRequest(format!("Invalid status: {}", status_str)))
+        })
+        .transpose()?;
 
-    // Get Redis connection
-    let mut conn = state.redis_connection().await?;
-
-    // For MVP, we'll store jobs as Redis hashes with keys like "job:{id}"
-    // In production, you'd want a more sophisticated data structure
-
-    // Get all job IDs using SCAN for cursor-based pagination
-    let pattern = "job:*";
-    let cursor = params.cursor.clone().unwrap_or_else(|| "0".to_string());
-    let scan_limit = params.limit.unwrap_or(20).min(100); // Max 100 per request
-
-    // SCAN returns (next_cursor, keys)
-    let (next_cursor, job_keys): (String, Vec<String>) = redis::cmd("SCAN")
-        .arg(&cursor)
-        .arg("MATCH")
-        .arg(pattern)
-        .arg("COUNT")
-        .arg(scan_limit)
-        .query_async(&mut conn)
-        .await
-        .map_err(|e| ServerError::Internal(format!("Failed to scan jobs: {}", e)))?;
-
-    // Fetch job data for each key
-    let mut jobs = Vec::new();
-    for key in &job_keys {
-        // Get job hash data
-        let job_data: std::collections::HashMap<String, String> = conn
-            .hgetall(key)
-            .await
-            .map_err(|e| ServerError::Internal(format!("Failed to get job: {}", e)))?;
+    let query = JobListQuery {
+        status,
+        repo: params.repo,
+        branch: params.branch,
+        limit: params.limit,
+        offset: params.offset,
+    };
 
-        if job_data.is_empty() {
-            continue;
-        }
+    let jobs = state.job_store().list(&query).await?;
+    Ok(Json(jobs))
+}
 
-        // Parse job from hash
-        if let Ok(job) = parse_job_from_hash(&job_data) {
-            // Apply filters
-            if let Some(status) = status_filter {
-                if job.status != status {
-                    continue;
-                }
-            }
-            if let Some(ref repo) = params.repo {
-                if &job.repo != repo {
-                    continue;
-                }
-            }
-            if let Some(ref branch) = params.branch {
-                if &job.branch != branch {
-                    continue;
-                }
-            }
-            jobs.push(job);
-        }
+/// POST /jobs - Manually trigger a job
+async fn trigger_job(
+    State(state): State<Arc<AppState>>,
+    Json(trigger): Json<JobTrigger>,
+) -> Result<(StatusCode, Json<Job>), ServerError> {
+    let (owner, name) = trigger
+        .repo
+        .split_once('/')
+        .filter(|(owner, name)| !owner.is_empty() && !name.is_empty())
+        .ok_or_else(|| {
+            ServerError::BadRequest(format!(
+                "Repository must be in owner/name format: {}",
+                trigger.repo
+            ))
+        })?;
+
+    if trigger.branch.is_empty() {
+        return Err(ServerError::BadRequest("Branch is required".to_string()));
     }
 
-    // Sort by started_at descending (newest first)
-    jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    let gitea = state.gitea_client().ok_or_else(|| {
+        ServerError::ServiceUnavailable(
+            "Manual job triggers require Gitea; set RAIBID_GITEA_URL on the server".to_string(),
+        )
+    })?;
 
-    // Apply offset-based pagination if cursor not used
-    let offset = params.offset.unwrap_or(0);
-    let limit = params.limit.unwrap_or(20);
-    let total = jobs.len();
+    // Validate the repository against Gitea
+    let exists = gitea
+        .repository_exists(owner, name)
+        .await
+        .map_err(|e| ServerError::Internal(format!("Failed to query Gitea: {}", e)))?;
+    if !exists {
+        return Err(ServerError::NotFound(format!(
+            "Repository not found: {}",
+            trigger.repo
+        )));
+    }
 
-    let paginated_jobs = if params.cursor.is_none() {
-        jobs.into_iter().skip(offset).take(limit).collect()
-    } else {
-        jobs.into_iter().take(limit).collect()
+    // Resolve a missing commit to the branch head
+    let commit = match trigger.commit.filter(|c| !c.is_empty()) {
+        Some(commit) => commit,
+        None => {
+            gitea
+                .get_branch(owner, name, &trigger.branch)
+                .await
+                .map_err(|e| ServerError::Internal(format!("Failed to query Gitea: {}", e)))?
+                .ok_or_else(|| {
+                    ServerError::NotFound(format!("Branch not found: {}", trigger.branch))
+                })?
+                .commit
+                .id
+        }
     };
 
-    let response = JobListResponse {
-        jobs: paginated_jobs,
-        total,
-        offset,
-        limit,
-        next_cursor: if next_cursor == "0" {
-            None
-        } else {
-            Some(next_cursor)
-        },
-    };
+    let job = new_job(trigger.repo, trigger.branch, commit);
+    let envelope = JobEnvelope::new(job, "manual", None);
+    let job_id = enqueue_job(&state, &envelope).await?;
 
-    Ok(Json(response))
+    info!(
+        "Queued manual job {} for repository {}",
+        job_id, envelope.job.repo
+    );
+
+    Ok((StatusCode::CREATED, Json(envelope.job)))
 }
 
 /// GET /jobs/{id} - Get a specific job by ID
@@ -166,201 +151,99 @@ async fn get_job(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<Json<Job>, ServerError> {
-    let mut conn = state.redis_connection().await?;
+    let job = state
+        .job_store()
+        .get(&id)
+        .await?
+        .ok_or_else(|| ServerError::NotFound(format!("Job not found: {}", id)))?;
 
-    // Get job hash
-    let key = format!("job:{}", id);
-    let job_data: std::collections::HashMap<String, String> = conn
-        .hgetall(&key)
-        .await
-        .map_err(|e| ServerError::Internal(format!("Failed to get job: {}", e)))?;
-
-    if job_data.is_empty() {
-        return Err(ServerError::NotFound(format!("Job not found: {}", id)));
-    }
-
-    let job = parse_job_from_hash(&job_data)?;
     Ok(Json(job))
 }
 
-/// GET /jobs/{id}/logs - Stream job logs via Server-Sent Events
-async fn get_job_logs(
+/// GET /jobs/{id}/steps - Get a job's step results
+///
+/// Steps are recorded when the job finishes; until then the list is empty.
+async fn get_job_steps(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ServerError> {
-    // First verify the job exists
-    let mut conn = state.redis_connection().await?;
-    let key = format!("job:{}", id);
-    let exists: bool = conn
-        .exists(&key)
-        .await
-        .map_err(|e| ServerError::Internal(format!("Failed to check job: {}", e)))?;
-
-    if !exists {
+) -> Result<Json<JobSteps>, ServerError> {
+    if state.job_store().get(&id).await?.is_none() {
         return Err(ServerError::NotFound(format!("Job not found: {}", id)));
     }
 
-    // Create stream for SSE
-    // In production, this would use Redis Streams (XREAD) to stream logs in real-time
-    // For MVP, we'll demonstrate the SSE pattern with periodic polling
-    let stream = stream::unfold(
-        (state.clone(), id.clone(), 0u64),
-        |(state, job_id, last_seq)| async move {
-            // Get logs from Redis stream
-            let log_stream = format!("job:{}:logs", job_id);
-
-            match state.redis_connection().await {
-                Ok(mut conn) => {
-                    // Use XREAD to get new log entries
-                    // Format: XREAD COUNT count STREAMS stream_key last_id
-                    let start_id = if last_seq == 0 {
-                        "0".to_string()
-                    } else {
-                        last_seq.to_string()
-                    };
-
-                    // Try to read log entries
-                    match read_log_entries(&mut conn, &log_stream, &start_id).await {
-                        Ok((entries, new_seq)) => {
-                            if !entries.is_empty() {
-                                // Create SSE event with log data
-                                let data = serde_json::to_string(&entries).unwrap_or_default();
-                                let event = Event::default().data(data);
-                                tokio::time::sleep(Duration::from_millis(100)).await;
-                                Some((Ok(event), (state, job_id, new_seq)))
-                            } else {
-                                // No new entries, wait and retry
-                                tokio::time::sleep(Duration::from_millis(500)).await;
-                                Some((
-                                    Ok(Event::default().comment("keepalive")),
-                                    (state, job_id, last_seq),
-                                ))
-                            }
-                        }
-                        Err(_) => {
-                            // Error reading, send keepalive
-                            tokio::time::sleep(Duration::from_millis(500)).await;
-                            Some((
-                                Ok(Event::default().comment("keepalive")),
-                                (state, job_id, last_seq),
-                            ))
-                        }
-                    }
-                }
-                Err(_) => None, // Connection error, end stream
-            }
-        },
-    );
+    let steps = state.job_store().get_steps(&id).await?;
 
-    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+    Ok(Json(JobSteps { job_id: id, steps }))
 }
 
-/// Parse job from Redis hash data
-fn parse_job_from_hash(
-    data: &std::collections::HashMap<String, String>,
-) -> Result<Job, ServerError> {
-    let id = data
-        .get("id")
-        .ok_or_else(|| ServerError::Internal("Missing job id".to_string()))?
-        .clone();
-    let repo = data
-        .get("repo")
-        .ok_or_else(|| ServerError::Internal("Missing repo".to_string()))?
-        .clone();
-    let branch = data
-        .get("branch")
-        .ok_or_else(|| ServerError::Internal("Missing branch".to_string()))?
-        .clone();
-    let commit = data
-        .get("commit")
-        .ok_or_else(|| ServerError::Internal("Missing commit".to_string()))?
-        .clone();
-
-    let status = data
-        .get("status")
-        .ok_or_else(|| ServerError::Internal("Missing status".to_string()))?
-        .parse::<JobStatus>()
-        .map_err(|e| ServerError::Internal(format!("Invalid status: {}", e)))?;
-
-    let started_at = data
-        .get("started_at")
-        .ok_or_else(|| ServerError::Internal("Missing started_at".to_string()))?
-        .parse::<chrono::DateTime<chrono::Utc>>()
-        .map_err(|e| ServerError::Internal(format!("Invalid started_at: {}", e)))?;
-
-    let finished_at = data
-        .get("finished_at")
-        .and_then(|s| s.parse::<chrono::DateTime<chrono::Utc>>().ok());
-
-    let duration = data.get("duration").and_then(|s| s.parse::<u64>().ok());
-    let agent_id = data.get("agent_id").cloned();
-    let exit_code = data.get("exit_code").and_then(|s| s.parse::<i32>().ok());
-
-    Ok(Job {
-        id,
-        repo,
-        branch,
-        commit,
-        status,
-        started_at,
-        finished_at,
-        duration,
-        agent_id,
-        exit_code,
-    })
-}
+/// POST /jobs/{id}/cancel - Cancel a pending or running job
+///
+/// Pending jobs are marked cancelled immediately and skipped when an agent reads
+/// them from the queue. Running jobs are signalled and the agent records the
+/// cancellation once it has stopped the build, so the response still shows the
+/// job as running.
+async fn cancel_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<Job>), ServerError> {
+    let job = state
+        .job_store()
+        .get(&id)
+        .await?
+        .ok_or_else(|| ServerError::NotFound(format!("Job not found: {}", id)))?;
+
+    if job.status.is_terminal() {
+        return Err(ServerError::Conflict(format!(
+            "Job {} already finished with status {}",
+            id, job.status
+        )));
+    }
+
+    // Signal agents; this also covers a pending job being picked up concurrently
+    let signalled = signal_cancel(&state, &id).await;
 
-/// Read log entries from Redis stream
-async fn read_log_entries(
-    conn: &mut redis::aio::MultiplexedConnection,
-    stream_key: &str,
-    start_id: &str,
-) -> Result<(Vec<serde_json::Value>, u64), redis::RedisError> {
-    // XREAD COUNT 100 STREAMS stream_key start_id
-    // Type alias for complex Redis stream response
-    type StreamEntry = (String, Vec<(String, String)>);
-    type StreamMessages = Vec<(String, Vec<StreamEntry>)>;
-    type StreamResponse = Vec<Vec<StreamMessages>>;
-
-    let result: StreamResponse = redis::cmd("XREAD")
-        .arg("COUNT")
-        .arg(100)
-        .arg("STREAMS")
-        .arg(stream_key)
-        .arg(start_id)
-        .query_async(conn)
-        .await?;
-
-    let mut entries = Vec::new();
-    let mut last_seq = start_id.parse::<u64>().unwrap_or(0);
-
-    // Parse XREAD response
-    for stream_data in result {
-        for stream_info in stream_data {
-            for (_, messages) in stream_info {
-                for (msg_id, fields) in messages {
-                    // Parse message ID to get sequence
-                    if let Some((seq_str, _)) = msg_id.split_once('-') {
-                        if let Ok(seq) = seq_str.parse::<u64>() {
-                            last_seq = last_seq.max(seq);
-                        }
-                    }
-
-                    // Convert fields to JSON
-                    let mut log_entry = serde_json::Map::new();
-                    log_entry.insert("id".to_string(), serde_json::Value::String(msg_id.clone()));
-
-                    for (key, value) in fields {
-                        log_entry.insert(key.clone(), serde_json::Value::String(value.clone()));
-                    }
-
-                    entries.push(serde_json::Value::Object(log_entry));
-                }
+    // An agent may have started the job since; it then stops on the signal
+    let job = match job.status {
+        JobStatus::Pending => state.job_store().cancel_pending(&id).await?,
+        _ => job,
+    };
+
+    match job.status {
+        JobStatus::Cancelled => {
+            if let Err(e) = signalled {
+                warn!(
+                    "Failed to signal cancellation for pending job {}: {}",
+                    id, e
+                );
             }
+
+            state.report_status(&job);
+            info!("Cancelled pending job {}", id);
+            Ok((StatusCode::OK, Json(job)))
         }
+        status if status.is_terminal() => Err(ServerError::Conflict(format!(
+            "Job {} already finished with status {}",
+            id, status
+        ))),
+        _ => {
+            signalled?;
+            info!("Requested cancellation of running job {}", id);
+            Ok((StatusCode::ACCEPTED, Json(job)))
+        }
+    }
+}
+
+/// POST /jobs/prune - Remove finished jobs the retention policy no longer keeps
+async fn prune_jobs(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<PruneQueryParams>,
+) -> Result<Json<PruneReport>, ServerError> {
+    let report = retention::prune_jobs(&state, params.dry_run).await?;
+    if !report.dry_run {
+        info!("Pruned {} expired jobs", report.jobs.len());
     }
 
-    Ok((entries, last_seq))
+    Ok(Json(report))
 }
 
 #[cfg(test)]
@@ -370,62 +253,319 @@ mod tests {
     use axum::{body::Body, http::Request};
     use tower::ServiceExt;
 
+    fn sample_job(id: &str) -> Job {
+        Job::new(id, "raibid-ci", "main", "abc123")
+    }
+
+    /// Start a minimal Gitea API stand-in that knows `owner/repo` with branch `main`
+    async fn spawn_mock_gitea() -> String {
+        let app = Router::new()
+            .route("/api/v1/repos/owner/repo", get(|| async { "{}" }))
+            .route(
+                "/api/v1/repos/owner/repo/branches/main",
+                get(|| async {
+                    Json(serde_json::json!({ "name": "main", "commit": { "id": "deadbeef" } }))
+                }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{}", addr)
+    }
+
+    fn state_with_gitea(url: String) -> Arc<AppState> {
+        let config = raibid_common::config::GiteaConfig {
+            url,
+            ..Default::default()
+        };
+        let client = raibid_common::gitea_api::GiteaClient::new(config, None).unwrap();
+        Arc::new(AppState::new().with_gitea_client(client))
+    }
+
+    async fn post_trigger(state: Arc<AppState>, body: serde_json::Value) -> StatusCode {
+        routes()
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/jobs")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
     #[tokio::test]
-    async fn test_list_jobs_without_redis() {
+    async fn test_trigger_job_invalid_repo() {
         let state = Arc::new(AppState::new());
+        let body = serde_json::json!({ "repo": "no-owner", "branch": "main" });
+
+        assert_eq!(post_trigger(state, body).await, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_job_without_gitea() {
+        let body = serde_json::json!({ "repo": "owner/repo", "branch": "main" });
+        let response = routes()
+            .with_state(Arc::new(AppState::new()))
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/jobs")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(error["error"]
+            .as_str()
+            .unwrap()
+            .contains("RAIBID_GITEA_URL"));
+    }
+
+    #[tokio::test]
+    async fn test_trigger_job_unknown_repo() {
+        let state = state_with_gitea(spawn_mock_gitea().await);
+        let body = serde_json::json!({ "repo": "owner/missing", "branch": "main" });
+
+        assert_eq!(post_trigger(state, body).await, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_job_unknown_branch() {
+        let state = state_with_gitea(spawn_mock_gitea().await);
+        let body = serde_json::json!({ "repo": "owner/repo", "branch": "missing" });
+
+        assert_eq!(post_trigger(state, body).await, StatusCode::NOT_FOUND);
+    }
+
+    async fn post_cancel(state: Arc<AppState>, id: &str) -> StatusCode {
+        routes()
+            .with_state(state)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/jobs/{}/cancel", id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_cancel_pending_job() {
+        let state = Arc::new(AppState::new());
+        state.job_store().save(&sample_job("job-1")).await.unwrap();
+
+        assert_eq!(post_cancel(state.clone(), "job-1").await, StatusCode::OK);
+
+        let job = state.job_store().get("job-1").await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert!(job.finished_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_finished_job_conflicts() {
+        let state = Arc::new(AppState::new());
+        let mut job = sample_job("job-1");
+        job.mark_finished(JobStatus::Success, Some(0));
+        state.job_store().save(&job).await.unwrap();
+
+        assert_eq!(post_cancel(state, "job-1").await, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_unknown_job() {
+        let state = Arc::new(AppState::new());
+
+        assert_eq!(post_cancel(state, "missing").await, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_job_requires_signal() {
+        // Without Redis the agent can't be told to stop, so the request fails
+        let state = Arc::new(AppState::new());
+        let mut job = sample_job("job-1");
+        job.mark_running("agent-1");
+        state.job_store().save(&job).await.unwrap();
+
+        assert_eq!(
+            post_cancel(state.clone(), "job-1").await,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        let job = state.job_store().get("job-1").await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn test_list_jobs_from_store() {
+        let state = Arc::new(AppState::new());
+        state.job_store().save(&sample_job("job-1")).await.unwrap();
         let app = routes().with_state(state);
 
         let response = app
             .oneshot(Request::builder().uri("/jobs").body(Body::empty()).unwrap())
             .await
             .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
 
-        // Should return error since Redis is not configured
-        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: JobList = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list.total, 1);
+        assert_eq!(list.jobs[0].id, "job-1");
     }
 
     #[tokio::test]
-    async fn test_get_job_without_redis() {
+    async fn test_get_job_steps() {
         let state = Arc::new(AppState::new());
+        state.job_store().save(&sample_job("job-1")).await.unwrap();
+        state
+            .job_store()
+            .save_steps(
+                "job-1",
+                &[raibid_common::StepResult {
+                    step: "test".to_string(),
+                    success: false,
+                    exit_code: Some(101),
+                    duration_secs: 42,
+                    output: "test failed".to_string(),
+                }],
+            )
+            .await
+            .unwrap();
         let app = routes().with_state(state);
 
         let response = app
+            .clone()
             .oneshot(
                 Request::builder()
-                    .uri("/jobs/test-job-123")
+                    .uri("/jobs/job-1/steps")
                     .body(Body::empty())
                     .unwrap(),
             )
             .await
             .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let steps: JobSteps = serde_json::from_slice(&body).unwrap();
+        assert_eq!(steps.job_id, "job-1");
+        assert_eq!(steps.steps[0].step, "test");
+        assert_eq!(steps.steps[0].duration_secs, 42);
 
-        // Should return error since Redis is not configured
-        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/jobs/missing/steps")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
-    #[test]
-    fn test_parse_job_from_hash() {
-        let mut data = std::collections::HashMap::new();
-        data.insert("id".to_string(), "job-123".to_string());
-        data.insert("repo".to_string(), "raibid-ci".to_string());
-        data.insert("branch".to_string(), "main".to_string());
-        data.insert("commit".to_string(), "abc123".to_string());
-        data.insert("status".to_string(), "running".to_string());
-        data.insert("started_at".to_string(), "2025-11-01T12:00:00Z".to_string());
-
-        let job = parse_job_from_hash(&data).unwrap();
-        assert_eq!(job.id, "job-123");
-        assert_eq!(job.repo, "raibid-ci");
-        assert_eq!(job.status, JobStatus::Running);
+    #[tokio::test]
+    async fn test_list_jobs_invalid_status() {
+        let state = Arc::new(AppState::new());
+        let app = routes().with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/jobs?status=bogus")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_get_job_from_store() {
+        let state = Arc::new(AppState::new());
+        state.job_store().save(&sample_job("job-1")).await.unwrap();
+        let app = routes().with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/jobs/job-1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_get_job_not_found() {
+        let state = Arc::new(AppState::new());
+        let app = routes().with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/jobs/test-job-123")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
-    #[test]
-    fn test_parse_job_from_hash_missing_field() {
-        let mut data = std::collections::HashMap::new();
-        data.insert("id".to_string(), "job-123".to_string());
-        // Missing required fields
+    #[tokio::test]
+    async fn test_prune_dry_run() {
+        let state = Arc::new(AppState::new());
+        let mut job = sample_job("job-1");
+        job.mark_finished(JobStatus::Success, Some(0));
+        job.started_at = chrono::Utc::now() - chrono::Duration::days(365);
+        job.finished_at = Some(job.started_at);
+        state.job_store().save(&job).await.unwrap();
+
+        let response = routes()
+            .with_state(state.clone())
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/jobs/prune?dry_run=true")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
 
-        let result = parse_job_from_hash(&data);
-        assert!(result.is_err());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: PruneReport = serde_json::from_slice(&body).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.jobs.len(), 1);
+        assert!(state.job_store().get("job-1").await.unwrap().is_some());
     }
 }
@@ -0,0 +1,504 @@
+//! Job log streaming
+//!
+//! Agents append build output to a Redis stream per job (see
+//! [`log_stream_key`]). Subscribers follow it over Server-Sent Events: each
+//! event carries a batch of entries and the stream ID of the last one, so a
+//! client that reconnects with `Last-Event-ID` resumes exactly where it left
+//! off. New subscribers first receive the last lines of the log. Once the job
+//! has finished and its log is drained, a final `end` event carries the job's
+//! status and the stream is closed.
+//!
+//! Clients asking for `application/json` get the last lines as [`JobLogs`]
+//! instead, without following the log. Either way `step` limits the lines to
+//! those of one pipeline step.
+//!
+//! Agents archive the logs of finished jobs to the object store. Once a job's
+//! stream has been trimmed, its log is served from the archive instead.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::ACCEPT, HeaderMap},
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Response, Sse,
+    },
+    routing::get,
+    Json, Router,
+};
+use futures::stream::{self, Stream};
+use redis::aio::MultiplexedConnection;
+use redis::streams::{StreamId, StreamRangeReply, StreamReadOptions, StreamReadReply};
+use redis::AsyncCommands;
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::{error::ServerError, state::AppState};
+use raibid_common::logs::{parse_log_entry, read_archived_log};
+use raibid_common::queue::log_stream_key;
+use raibid_common::{Job, JobLogEntry, JobLogs};
+
+/// Lines of past output sent to new subscribers by default
+const DEFAULT_LOG_BACKFILL: usize = 1000;
+
+/// Maximum number of log entries sent in one event
+const LOG_BATCH_SIZE: usize = 100;
+
+/// How long a read waits for new lines before the job's status is checked again
+const LOG_BLOCK_MS: usize = 2000;
+
+/// Maximum number of entries read at once while collecting past lines
+const LOG_PAGE_SIZE: usize = 1000;
+
+/// Query parameters for the job logs endpoint
+#[derive(Debug, Deserialize)]
+pub struct LogsQueryParams {
+    /// Number of past lines to send before following the log
+    pub tail: Option<usize>,
+    /// Only send lines of this pipeline step
+    pub step: Option<String>,
+}
+
+/// Create log routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/jobs/{id}/logs", get(get_job_logs))
+}
+
+/// Where a subscriber is in a job's log
+struct LogFollower {
+    state: Arc<AppState>,
+    conn: MultiplexedConnection,
+    job_id: String,
+    stream_key: String,
+    step: Option<String>,
+    /// ID of the last entry read, or "0" before the first one
+    cursor: String,
+    /// Lines still to backfill, until the backfill has been sent
+    backfill: Option<usize>,
+    done: bool,
+}
+
+/// GET /jobs/{id}/logs - Stream job logs via Server-Sent Events
+async fn get_job_logs(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<LogsQueryParams>,
+    headers: HeaderMap,
+) -> Result<Response, ServerError> {
+    // First verify the job exists
+    let Some(job) = state.job_store().get(&id).await? else {
+        return Err(ServerError::NotFound(format!("Job not found: {}", id)));
+    };
+
+    let last_event_id = match headers.get("Last-Event-ID") {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .filter(|id| stream_id(id).is_some())
+                .ok_or_else(|| ServerError::BadRequest("Invalid Last-Event-ID".to_string()))?
+                .to_string(),
+        ),
+        None => None,
+    };
+
+    let snapshot = headers
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"));
+    let tail = params.tail.unwrap_or(DEFAULT_LOG_BACKFILL);
+
+    // Each subscriber gets its own connection, since reads block
+    let mut conn = state.redis_connection().await?;
+
+    if job.status.is_terminal() {
+        if let Some(entries) = archived_log(&state, &mut conn, &id).await? {
+            let entries = select_lines(
+                entries,
+                params.step.as_deref(),
+                last_event_id.as_deref(),
+                tail,
+            );
+            if snapshot {
+                return Ok(Json(JobLogs {
+                    job_id: id,
+                    entries,
+                })
+                .into_response());
+            }
+            return Ok(archive_stream(entries, &job).into_response());
+        }
+    }
+
+    // Resuming subscribers have already seen the backfill
+    let mut follower = LogFollower {
+        stream_key: log_stream_key(&id),
+        step: params.step,
+        backfill: match last_event_id {
+            Some(_) => None,
+            None => Some(tail),
+        },
+        cursor: last_event_id.unwrap_or_else(|| "0".to_string()),
+        state,
+        conn,
+        job_id: id,
+        done: false,
+    };
+
+    if snapshot {
+        let entries = follower.read_backfill(tail).await?;
+        return Ok(Json(JobLogs {
+            job_id: follower.job_id,
+            entries,
+        })
+        .into_response());
+    }
+
+    Ok(sse_stream(follower).into_response())
+}
+
+/// Follow a job's log as a stream of events
+fn sse_stream(follower: LogFollower) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = stream::unfold(follower, |mut follower| async move {
+        if follower.done {
+            return None;
+        }
+
+        match follower.next_event().await {
+            Ok(event) => Some((Ok(event), follower)),
+            Err(e) => {
+                // Clients reconnect with Last-Event-ID and resume
+                warn!("Failed to read logs of job {}: {}", follower.job_id, e);
+                None
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Get a finished job's archived log, if its stream no longer holds all of it
+async fn archived_log(
+    state: &AppState,
+    conn: &mut MultiplexedConnection,
+    job_id: &str,
+) -> Result<Option<Vec<JobLogEntry>>, ServerError> {
+    let Some(store) = state.object_store() else {
+        return Ok(None);
+    };
+
+    // Lines are numbered from 1, so a stream starting later has been trimmed
+    let reply: StreamRangeReply = conn
+        .xrange_count(log_stream_key(job_id), "-", "+", 1)
+        .await?;
+    let trimmed = reply
+        .ids
+        .first()
+        .and_then(parse_log_entry)
+        .is_none_or(|entry| entry.line > 1);
+    if !trimmed {
+        return Ok(None);
+    }
+
+    Ok(read_archived_log(store.as_ref(), job_id).await?)
+}
+
+/// Pick the archived lines a request asks for
+///
+/// Resuming clients get the lines after the last one they saw, others the last
+/// `tail` lines.
+fn select_lines(
+    entries: Vec<JobLogEntry>,
+    step: Option<&str>,
+    last_event_id: Option<&str>,
+    tail: usize,
+) -> Vec<JobLogEntry> {
+    let mut entries: Vec<JobLogEntry> = entries
+        .into_iter()
+        .filter(|entry| is_step(entry, step))
+        .collect();
+
+    match last_event_id.and_then(stream_id) {
+        Some(last) => entries.retain(|entry| {
+            entry
+                .id
+                .as_deref()
+                .and_then(stream_id)
+                .is_some_and(|id| id > last)
+        }),
+        None => {
+            let skip = entries.len().saturating_sub(tail);
+            entries.drain(..skip);
+        }
+    }
+
+    entries
+}
+
+/// Send archived lines in batches, then end the stream
+fn archive_stream(
+    entries: Vec<JobLogEntry>,
+    job: &Job,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let mut events: Vec<Event> = entries
+        .chunks(LOG_BATCH_SIZE)
+        .map(|batch| {
+            let id = batch.last().and_then(|entry| entry.id.as_deref());
+            lines_event(batch, id.unwrap_or_default())
+        })
+        .collect();
+    events.push(end_event(Some(job)));
+
+    Sse::new(stream::iter(events.into_iter().map(Ok)))
+}
+
+impl LogFollower {
+    /// Wait for the next event to send
+    async fn next_event(&mut self) -> Result<Event, ServerError> {
+        if let Some(count) = self.backfill.take() {
+            let entries = self.read_backfill(count).await?;
+            if !entries.is_empty() {
+                return Ok(self.entries_event(&entries));
+            }
+        }
+
+        loop {
+            // Check the status first: once the job has finished no more lines are
+            // written, so an empty read means the log is complete
+            let job = self.state.job_store().get(&self.job_id).await?;
+            let finished = job.as_ref().is_none_or(|job| job.status.is_terminal());
+
+            let block = (!finished).then_some(LOG_BLOCK_MS);
+            let (read, entries) = self.read_entries(block).await?;
+            if !entries.is_empty() {
+                return Ok(self.entries_event(&entries));
+            }
+            if read > 0 {
+                // Only lines of other steps, there may be more
+                continue;
+            }
+
+            if finished {
+                self.done = true;
+                return Ok(end_event(job.as_ref()));
+            }
+        }
+    }
+
+    /// Read the last `count` lines, moving the cursor past the newest entry
+    ///
+    /// The newest entry is looked up even when no lines are wanted, so that only
+    /// lines written after subscribing are followed.
+    async fn read_backfill(&mut self, count: usize) -> Result<Vec<JobLogEntry>, ServerError> {
+        // Lines of other steps are skipped, so keep reading back until enough match
+        let page_size = match self.step {
+            Some(_) => LOG_PAGE_SIZE,
+            None => count.clamp(1, LOG_PAGE_SIZE),
+        };
+
+        let mut entries = Vec::new();
+        let mut end = "+".to_string();
+        loop {
+            let reply: StreamRangeReply = self
+                .conn
+                .xrevrange_count(&self.stream_key, &end, "-", page_size)
+                .await?;
+
+            if end == "+" {
+                if let Some(newest) = reply.ids.first() {
+                    self.cursor = newest.id.clone();
+                }
+            }
+            let Some(oldest) = reply.ids.last() else {
+                break;
+            };
+            end = format!("({}", oldest.id);
+
+            let exhausted = reply.ids.len() < page_size;
+            entries.extend(self.matching_entries(&reply.ids));
+            if entries.len() >= count || exhausted {
+                break;
+            }
+        }
+
+        entries.truncate(count);
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// Read entries after the cursor, waiting up to `block` ms for some to arrive
+    ///
+    /// Returns the number of entries read along with the lines that match.
+    async fn read_entries(
+        &mut self,
+        block: Option<usize>,
+    ) -> Result<(usize, Vec<JobLogEntry>), ServerError> {
+        let mut opts = StreamReadOptions::default().count(LOG_BATCH_SIZE);
+        if let Some(ms) = block {
+            opts = opts.block(ms);
+        }
+
+        let reply: StreamReadReply = self
+            .conn
+            .xread_options(&[&self.stream_key], &[&self.cursor], &opts)
+            .await?;
+
+        let ids: Vec<StreamId> = reply.keys.into_iter().flat_map(|key| key.ids).collect();
+        if let Some(last) = ids.last() {
+            self.cursor = last.id.clone();
+        }
+        Ok((ids.len(), self.matching_entries(&ids)))
+    }
+
+    /// Parse stream entries, keeping the lines of the requested step
+    fn matching_entries(&self, ids: &[StreamId]) -> Vec<JobLogEntry> {
+        ids.iter()
+            .filter_map(parse_log_entry)
+            .filter(|entry| is_step(entry, self.step.as_deref()))
+            .collect()
+    }
+
+    /// Build the event for a batch of lines, identified by the cursor after it
+    fn entries_event(&self, entries: &[JobLogEntry]) -> Event {
+        lines_event(entries, &self.cursor)
+    }
+}
+
+/// Check whether a line belongs to the requested step, if any
+fn is_step(entry: &JobLogEntry, step: Option<&str>) -> bool {
+    step.is_none_or(|step| entry.step.as_deref() == Some(step))
+}
+
+/// Build the event for a batch of lines, identified by the stream ID to resume after
+fn lines_event(entries: &[JobLogEntry], id: &str) -> Event {
+    Event::default()
+        .id(id)
+        .data(serde_json::to_string(entries).unwrap_or_default())
+}
+
+/// Build the event sent after the last line of a finished job's log
+fn end_event(job: Option<&Job>) -> Event {
+    let data = serde_json::json!({
+        "status": job.map(|job| job.status),
+        "exit_code": job.and_then(|job| job.exit_code),
+    });
+    Event::default().event("end").data(data.to_string())
+}
+
+/// Parse a complete stream ID (`<ms>-<seq>`) into comparable parts
+fn stream_id(id: &str) -> Option<(u64, u64)> {
+    let (ms, seq) = id.split_once('-')?;
+    Some((ms.parse().ok()?, seq.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use raibid_common::{JobStatus, LogLevel, LogStream};
+    use tower::ServiceExt;
+
+    fn sample_job(status: JobStatus) -> Job {
+        Job {
+            status,
+            exit_code: Some(0),
+            ..Job::new("job-1", "raibid-ci", "main", "abc123")
+        }
+    }
+
+    #[test]
+    fn test_stream_id() {
+        assert_eq!(stream_id("1700000000000-0"), Some((1700000000000, 0)));
+        assert_eq!(stream_id("1700000000000-12"), Some((1700000000000, 12)));
+        assert!(stream_id("1700000000000-12") > stream_id("1700000000000-9"));
+        assert_eq!(stream_id("1700000000000"), None);
+        assert_eq!(stream_id("1700000000000-"), None);
+        assert_eq!(stream_id("abc-0"), None);
+        assert_eq!(stream_id(""), None);
+    }
+
+    #[test]
+    fn test_select_lines() {
+        let entries: Vec<JobLogEntry> = (1..=6)
+            .map(|line| {
+                let step = if line <= 3 { "check" } else { "test" };
+                let mut entry = JobLogEntry::new(
+                    line,
+                    Some(step),
+                    LogStream::Stdout,
+                    LogLevel::Info,
+                    format!("line {}", line),
+                );
+                entry.id = Some(format!("1700000000000-{}", line));
+                entry
+            })
+            .collect();
+        let lines = |selected: Vec<JobLogEntry>| -> Vec<u64> {
+            selected.iter().map(|entry| entry.line).collect()
+        };
+
+        assert_eq!(lines(select_lines(entries.clone(), None, None, 2)), [5, 6]);
+        assert_eq!(
+            lines(select_lines(entries.clone(), Some("check"), None, 1000)),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            lines(select_lines(
+                entries.clone(),
+                None,
+                Some("1700000000000-4"),
+                1
+            )),
+            [5, 6]
+        );
+        assert_eq!(
+            lines(select_lines(
+                entries,
+                Some("check"),
+                Some("1700000000000-2"),
+                0
+            )),
+            [3]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_logs_of_unknown_job() {
+        let response = routes()
+            .with_state(Arc::new(AppState::new()))
+            .oneshot(
+                Request::builder()
+                    .uri("/jobs/missing/logs")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_logs_invalid_last_event_id() {
+        let state = AppState::new();
+        state
+            .job_store()
+            .save(&sample_job(JobStatus::Running))
+            .await
+            .unwrap();
+
+        let response = routes()
+            .with_state(Arc::new(state))
+            .oneshot(
+                Request::builder()
+                    .uri("/jobs/job-1/logs")
+                    .header("Last-Event-ID", "1700000000000")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,172 @@
+//! Metrics routes
+
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::{
+    error::ServerError, metrics::TEXT_CONTENT_TYPE, queue::queue_metrics, state::AppState,
+};
+use raibid_common::jobs::JobStatus;
+use raibid_common::metrics::{
+    QueueMetrics, DEFAULT_THROUGHPUT_WINDOW_MINUTES, MAX_THROUGHPUT_WINDOW_MINUTES,
+};
+
+/// Query parameters for the queue metrics endpoint
+#[derive(Debug, Deserialize)]
+pub struct QueueMetricsParams {
+    /// Throughput window in minutes
+    pub window: Option<u64>,
+}
+
+/// Create metrics routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/metrics", get(prometheus_metrics))
+        .route("/metrics/queue", get(get_queue_metrics))
+}
+
+/// GET /metrics - Prometheus text exposition
+///
+/// Queue and job metrics are refreshed on each scrape. A source being unavailable
+/// leaves its metrics at their last value rather than failing the scrape.
+async fn prometheus_metrics(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ServerError> {
+    let metrics = state.metrics();
+    metrics.set_http_totals(state.request_count(), state.active_connections());
+
+    match queue_metrics(&state, DEFAULT_THROUGHPUT_WINDOW_MINUTES).await {
+        Ok(queue) => metrics.set_queue(&queue),
+        Err(e) => debug!("Skipping queue metrics: {}", e),
+    }
+
+    let store = state.job_store();
+    match store.count_by_status().await {
+        Ok(counts) => {
+            for status in [JobStatus::Pending, JobStatus::Running] {
+                metrics.set_jobs(status, counts.get(&status).copied().unwrap_or(0));
+            }
+        }
+        Err(e) => debug!("Skipping job metrics: {:#}", e),
+    }
+    match store.outcome_counts().await {
+        Ok(outcomes) => {
+            for status in [JobStatus::Success, JobStatus::Failed, JobStatus::Cancelled] {
+                metrics.set_jobs_finished(status, outcomes.get(&status).copied().unwrap_or(0));
+            }
+        }
+        Err(e) => debug!("Skipping job outcome metrics: {:#}", e),
+    }
+
+    Ok((
+        [(header::CONTENT_TYPE, TEXT_CONTENT_TYPE)],
+        metrics.render()?,
+    ))
+}
+
+/// GET /metrics/queue - Queue length, lag, pending entries and throughput
+async fn get_queue_metrics(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<QueueMetricsParams>,
+) -> Result<Json<QueueMetrics>, ServerError> {
+    let window = params.window.unwrap_or(DEFAULT_THROUGHPUT_WINDOW_MINUTES);
+    if window == 0 || window > MAX_THROUGHPUT_WINDOW_MINUTES {
+        return Err(ServerError::BadRequest(format!(
+            "window must be between 1 and {} minutes",
+            MAX_THROUGHPUT_WINDOW_MINUTES
+        )));
+    }
+
+    let metrics = queue_metrics(&state, window).await?;
+    Ok(Json(metrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    async fn status_for(uri: &str) -> StatusCode {
+        let app = routes().with_state(Arc::new(AppState::new()));
+        app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_invalid_window_rejected() {
+        assert_eq!(
+            status_for("/metrics/queue?window=0").await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status_for("/metrics/queue?window=100000").await,
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_metrics() {
+        let state = AppState::new();
+        for _ in 0..2 {
+            state
+                .job_store()
+                .save(&crate::queue::new_job(
+                    "org/repo".to_string(),
+                    "main".to_string(),
+                    "abc123".to_string(),
+                ))
+                .await
+                .unwrap();
+        }
+        let jobs = state.job_store().list_all().await.unwrap();
+        state
+            .job_store()
+            .mark_finished(&jobs[0].id, JobStatus::Success, Some(0))
+            .await
+            .unwrap();
+        state.metrics().record_webhook("gitea", "queued");
+
+        let app = routes().with_state(Arc::new(state));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], TEXT_CONTENT_TYPE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains(r#"raibid_jobs{status="pending"} 1"#));
+        assert!(text.contains(r#"raibid_jobs{status="running"} 0"#));
+        assert!(text.contains(r#"raibid_jobs_finished_total{status="success"} 1"#));
+        assert!(text.contains(r#"raibid_jobs_finished_total{status="failed"} 0"#));
+        assert!(text.contains(r#"raibid_webhooks_total{provider="gitea",result="queued"} 1"#));
+    }
+
+    #[tokio::test]
+    async fn test_queue_metrics_without_redis() {
+        assert_eq!(
+            status_for("/metrics/queue").await,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
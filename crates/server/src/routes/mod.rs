@@ -1,5 +1,9 @@
 //! HTTP route handlers
 
+pub mod agents;
+pub mod artifacts;
 pub mod health;
 pub mod jobs;
+pub mod logs;
+pub mod metrics;
 pub mod webhooks;
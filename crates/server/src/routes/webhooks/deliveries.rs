@@ -0,0 +1,271 @@
+//! Webhook delivery log routes
+//!
+//! Every delivery is recorded with its headers, body and outcome, so a rejected
+//! delivery can be inspected with `GET /webhooks/deliveries` instead of digging
+//! through server logs, and replayed once the cause (e.g. a mismatched secret) is
+//! fixed.
+//!
+//! The routes are only served with `RAIBID_WEBHOOK_DELIVERY_API=true`: deliveries
+//! are recorded with their payloads, and a replay queues builds the way a
+//! verified delivery would, on behalf of anyone who can reach the server.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderName, HeaderValue},
+    routing::{get, post},
+    Json, Router,
+};
+use raibid_common::store::{DEFAULT_LIST_LIMIT, MAX_LIST_LIMIT};
+use raibid_common::webhooks::{WebhookDelivery, WebhookDeliveryList};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::{delivery_outcome, find_provider, receive, WebhookProvider, WebhookResult};
+use crate::{error::ServerError, state::AppState};
+
+/// Headers never written to the delivery log
+///
+/// GitLab's token is the webhook secret itself, so replaying a GitLab delivery
+/// only passes verification when no GitLab secret is configured.
+const REDACTED_HEADERS: &[&str] = &["authorization", "cookie", "x-gitlab-token"];
+
+/// Value recorded in place of a redacted header
+const REDACTED: &str = "[redacted]";
+
+/// Query parameters for the delivery list endpoint
+#[derive(Debug, Deserialize)]
+pub struct DeliveriesQueryParams {
+    /// Maximum number of deliveries to return
+    pub limit: Option<usize>,
+}
+
+/// Create delivery log routes
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/webhooks/deliveries", get(list_deliveries))
+        .route("/webhooks/deliveries/{id}", get(get_delivery))
+        .route("/webhooks/deliveries/{id}/replay", post(replay_delivery))
+}
+
+/// GET /webhooks/deliveries - List recent deliveries, newest first
+async fn list_deliveries(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<DeliveriesQueryParams>,
+) -> Result<Json<WebhookDeliveryList>, ServerError> {
+    ensure_enabled(&state)?;
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_LIST_LIMIT)
+        .min(MAX_LIST_LIMIT);
+    let deliveries = state.delivery_log().list(limit).await?;
+
+    Ok(Json(WebhookDeliveryList::new(deliveries)))
+}
+
+/// GET /webhooks/deliveries/{id} - Get a delivery
+async fn get_delivery(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<WebhookDelivery>, ServerError> {
+    ensure_enabled(&state)?;
+    let delivery = find_delivery(&state, &id).await?;
+    Ok(Json(delivery))
+}
+
+/// POST /webhooks/deliveries/{id}/replay - Handle a recorded delivery again
+///
+/// The replay is verified and handled like a new delivery, except that it is never
+/// treated as a redelivery. The response is the replay's own log record.
+async fn replay_delivery(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<WebhookDelivery>, ServerError> {
+    ensure_enabled(&state)?;
+    let original = find_delivery(&state, &id).await?;
+    let provider = find_provider(&original.provider).ok_or_else(|| {
+        ServerError::BadRequest(format!("Unknown webhook provider: {}", original.provider))
+    })?;
+    let headers = header_map(&original.headers);
+
+    let (_, replay) = receive(
+        &state,
+        provider,
+        &headers,
+        &original.body,
+        Some(original.id.clone()),
+    )
+    .await;
+    info!(
+        "Replayed webhook delivery {} as {}: {}",
+        original.id, replay.id, replay.outcome
+    );
+
+    Ok(Json(replay))
+}
+
+/// Refuse delivery log requests unless the delivery API is enabled
+fn ensure_enabled(state: &AppState) -> Result<(), ServerError> {
+    if state.delivery_api() {
+        Ok(())
+    } else {
+        Err(ServerError::NotFound(
+            "Webhook delivery API is disabled (set RAIBID_WEBHOOK_DELIVERY_API=true)".to_string(),
+        ))
+    }
+}
+
+/// Look up a delivery by record ID
+async fn find_delivery(state: &AppState, id: &str) -> Result<WebhookDelivery, ServerError> {
+    state
+        .delivery_log()
+        .get(id)
+        .await?
+        .ok_or_else(|| ServerError::NotFound(format!("Delivery not found: {}", id)))
+}
+
+/// Record a handled delivery in the delivery log
+///
+/// Failing to record a delivery doesn't fail it; the record is returned either way.
+pub(super) async fn record(
+    state: &AppState,
+    provider: &dyn WebhookProvider,
+    headers: &HeaderMap,
+    body: &str,
+    result: &WebhookResult,
+    replay_of: Option<String>,
+) -> WebhookDelivery {
+    let delivery = new_delivery(provider, headers, body, result, replay_of);
+
+    if let Err(e) = state.delivery_log().record(&delivery).await {
+        warn!("Failed to record webhook delivery {}: {:#}", delivery.id, e);
+    }
+
+    delivery
+}
+
+/// Build the log record for a handled delivery
+fn new_delivery(
+    provider: &dyn WebhookProvider,
+    headers: &HeaderMap,
+    body: &str,
+    result: &WebhookResult,
+    replay_of: Option<String>,
+) -> WebhookDelivery {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+
+    let (status, message, job_id) = match result {
+        Ok((status, Json(response))) => (
+            status.as_u16(),
+            response.message.clone(),
+            response.job_id.clone(),
+        ),
+        Err(e) => (e.status_code().as_u16(), e.to_string(), None),
+    };
+
+    WebhookDelivery {
+        id: Uuid::new_v4().to_string(),
+        provider: provider.name().to_string(),
+        event: header(provider.event_header()),
+        delivery_id: header(provider.delivery_header()),
+        received_at: chrono::Utc::now(),
+        headers: header_record(headers),
+        body: body.to_string(),
+        outcome: delivery_outcome(result),
+        status,
+        message,
+        job_id,
+        replay_of,
+    }
+}
+
+/// Convert request headers into a log record, redacting credentials
+fn header_record(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let mut record: BTreeMap<String, String> = BTreeMap::new();
+
+    for (name, value) in headers {
+        let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+            REDACTED
+        } else {
+            match value.to_str() {
+                Ok(value) => value,
+                Err(_) => continue,
+            }
+        };
+
+        record
+            .entry(name.as_str().to_string())
+            .and_modify(|existing| {
+                existing.push_str(", ");
+                existing.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+
+    record
+}
+
+/// Rebuild request headers from a log record, skipping redacted ones
+fn header_map(record: &BTreeMap<String, String>) -> HeaderMap {
+    record
+        .iter()
+        .filter(|(_, value)| value.as_str() != REDACTED)
+        .filter_map(|(name, value)| {
+            Some((
+                HeaderName::from_bytes(name.as_bytes()).ok()?,
+                HeaderValue::from_str(value).ok()?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::webhooks::{acknowledged, providers::GitHub};
+    use axum::http::StatusCode;
+    use raibid_common::webhooks::DeliveryOutcome;
+
+    #[test]
+    fn test_new_delivery_records_outcome() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-github-event", "push".parse().unwrap());
+        headers.insert("x-github-delivery", "72d3162e".parse().unwrap());
+        headers.insert("authorization", "Bearer secret".parse().unwrap());
+
+        let rejected: WebhookResult = Err(ServerError::Unauthorized("Invalid signature".into()));
+        let delivery = new_delivery(&GitHub, &headers, "{}", &rejected, None);
+        assert_eq!(delivery.event.as_deref(), Some("push"));
+        assert_eq!(delivery.delivery_id.as_deref(), Some("72d3162e"));
+        assert_eq!(delivery.outcome, DeliveryOutcome::Unauthorized);
+        assert_eq!(delivery.status, 401);
+        assert_eq!(delivery.message, "Unauthorized: Invalid signature");
+        assert_eq!(delivery.headers["authorization"], REDACTED);
+
+        let ignored: WebhookResult = Ok(acknowledged("pong"));
+        let delivery = new_delivery(&GitHub, &headers, "{}", &ignored, Some("d-1".into()));
+        assert_eq!(delivery.outcome, DeliveryOutcome::Ignored);
+        assert_eq!(delivery.status, StatusCode::OK.as_u16());
+        assert_eq!(delivery.replay_of.as_deref(), Some("d-1"));
+    }
+
+    #[test]
+    fn test_header_map_skips_redacted() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-gitea-event", "push".parse().unwrap());
+        headers.insert("cookie", "session=1".parse().unwrap());
+        headers.insert("x-gitlab-token", "secret".parse().unwrap());
+
+        let rebuilt = header_map(&header_record(&headers));
+        assert_eq!(rebuilt.get("x-gitea-event").unwrap(), "push");
+        assert!(rebuilt.get("cookie").is_none());
+        assert!(rebuilt.get("x-gitlab-token").is_none());
+    }
+}
@@ -0,0 +1,225 @@
+//! Provider-independent webhook events
+//!
+//! Each provider parses its payloads into these types, so skipping, job creation
+//! and queueing are written once for every provider.
+
+use super::payloads::{
+    GitHubWebhookPayload, GitLabMergeRequestPayload, GitLabPushPayload, GiteaWebhookPayload,
+    PullRequestWebhookPayload,
+};
+
+/// Pull request actions that queue a build of the head ref
+///
+/// GitHub reports new commits as `synchronize`, Gitea and Forgejo as `synchronized`.
+const PULL_REQUEST_BUILD_ACTIONS: &[&str] = &["opened", "reopened", "synchronize", "synchronized"];
+
+/// Merge request actions that queue a build of the source branch
+///
+/// `update` only counts when it adds commits (the payload then carries `oldrev`).
+const MERGE_REQUEST_BUILD_ACTIONS: &[&str] = &["open", "reopen"];
+
+/// Webhook event, normalised across providers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebhookEvent {
+    Push(PushEvent),
+    PullRequest(PullRequestEvent),
+    Ping,
+    Other(String),
+}
+
+/// Push of a branch or tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushEvent {
+    /// Repository full name (e.g. "owner/repo")
+    pub repo: String,
+    /// Pushed ref (e.g. "refs/heads/main")
+    pub ref_name: Option<String>,
+    /// Commit the ref points to after the push (all zeros when it was deleted)
+    pub after: Option<String>,
+    /// User that pushed
+    pub pusher: Option<String>,
+    /// Commits listed in the payload
+    pub commits: Vec<PushCommit>,
+}
+
+/// Commit listed in a push
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushCommit {
+    pub id: String,
+    pub message: String,
+}
+
+/// Pull request (merge request on GitLab) update
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestEvent {
+    /// Provider's action name (e.g. "opened", "update")
+    pub action: String,
+    /// Whether the action brings commits to build
+    pub build: bool,
+    /// Repository the pull request targets
+    pub repo: String,
+    /// Pull request number
+    pub number: u64,
+    /// Head branch
+    pub head_ref: String,
+    /// Head commit
+    pub head_sha: String,
+    /// Repository the head branch lives in (differs from `repo` for forks)
+    pub head_repo: String,
+    /// Ref `repo` publishes the head commit under
+    pub pull_ref: String,
+    /// Branch the pull request merges into
+    pub base_branch: String,
+    /// User that triggered the event
+    pub author: Option<String>,
+}
+
+impl From<GiteaWebhookPayload> for PushEvent {
+    fn from(payload: GiteaWebhookPayload) -> Self {
+        Self {
+            repo: payload.repository.full_name,
+            ref_name: payload.ref_name,
+            after: payload.after,
+            pusher: Some(payload.pusher.username),
+            commits: payload
+                .commits
+                .unwrap_or_default()
+                .into_iter()
+                .map(|commit| PushCommit {
+                    id: commit.id,
+                    message: commit.message,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<GitHubWebhookPayload> for PushEvent {
+    fn from(payload: GitHubWebhookPayload) -> Self {
+        Self {
+            repo: payload.repository.full_name,
+            ref_name: payload.ref_name,
+            after: payload.after,
+            pusher: Some(payload.pusher.name),
+            commits: payload
+                .commits
+                .unwrap_or_default()
+                .into_iter()
+                .map(|commit| PushCommit {
+                    id: commit.id,
+                    message: commit.message,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<GitLabPushPayload> for PushEvent {
+    fn from(payload: GitLabPushPayload) -> Self {
+        Self {
+            repo: payload.project.path_with_namespace,
+            ref_name: payload.ref_name,
+            // `after` is the tag object for annotated tags; `checkout_sha` is the
+            // commit, and is null when the ref was deleted
+            after: payload.checkout_sha.or(payload.after),
+            pusher: payload.user_username,
+            commits: payload
+                .commits
+                .into_iter()
+                .map(|commit| PushCommit {
+                    id: commit.id,
+                    message: commit.message,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<PullRequestWebhookPayload> for PullRequestEvent {
+    fn from(payload: PullRequestWebhookPayload) -> Self {
+        let pr = payload.pull_request;
+        let head_repo = pr
+            .head
+            .repo
+            .map(|repo| repo.full_name)
+            .unwrap_or_else(|| payload.repository.full_name.clone());
+
+        Self {
+            build: PULL_REQUEST_BUILD_ACTIONS.contains(&payload.action.as_str()),
+            action: payload.action,
+            repo: payload.repository.full_name,
+            number: pr.number,
+            head_ref: pr.head.ref_name,
+            head_sha: pr.head.sha,
+            head_repo,
+            pull_ref: format!("refs/pull/{}/head", pr.number),
+            base_branch: pr.base.ref_name,
+            author: payload.sender.and_then(|sender| sender.name()),
+        }
+    }
+}
+
+impl From<GitLabMergeRequestPayload> for PullRequestEvent {
+    fn from(payload: GitLabMergeRequestPayload) -> Self {
+        let mr = payload.object_attributes;
+        let action = mr.action.unwrap_or_default();
+        let build = MERGE_REQUEST_BUILD_ACTIONS.contains(&action.as_str())
+            || (action == "update" && mr.oldrev.is_some());
+        let head_repo = mr
+            .source
+            .map(|project| project.path_with_namespace)
+            .unwrap_or_else(|| payload.project.path_with_namespace.clone());
+
+        Self {
+            action,
+            build,
+            repo: payload.project.path_with_namespace,
+            number: mr.iid,
+            head_ref: mr.source_branch,
+            head_sha: mr.last_commit.map(|commit| commit.id).unwrap_or_default(),
+            head_repo,
+            pull_ref: format!("refs/merge-requests/{}/head", mr.iid),
+            base_branch: mr.target_branch,
+            author: payload.user.map(|user| user.username),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_request_build_actions() {
+        let payload = |action: &str, oldrev: Option<&str>| -> GitLabMergeRequestPayload {
+            serde_json::from_value(serde_json::json!({
+                "object_kind": "merge_request",
+                "user": {"username": "alice"},
+                "project": {"path_with_namespace": "group/app"},
+                "object_attributes": {
+                    "iid": 7,
+                    "action": action,
+                    "oldrev": oldrev,
+                    "source_branch": "feature",
+                    "target_branch": "main",
+                    "last_commit": {"id": "def456", "message": "Add feature"},
+                    "source": {"path_with_namespace": "alice/app"}
+                }
+            }))
+            .unwrap()
+        };
+
+        let event = PullRequestEvent::from(payload("open", None));
+        assert!(event.build);
+        assert_eq!(event.repo, "group/app");
+        assert_eq!(event.head_repo, "alice/app");
+        assert_eq!(event.head_sha, "def456");
+        assert_eq!(event.pull_ref, "refs/merge-requests/7/head");
+        assert_eq!(event.author.as_deref(), Some("alice"));
+
+        assert!(PullRequestEvent::from(payload("update", Some("abc123"))).build);
+        // Title or label edits don't add commits
+        assert!(!PullRequestEvent::from(payload("update", None)).build);
+        assert!(!PullRequestEvent::from(payload("merge", None)).build);
+    }
+}
@@ -1,207 +1,769 @@
-//! Webhook route handlers for GitHub and Gitea
+//! Webhook route handlers for GitHub, Gitea, Forgejo and GitLab
+//!
+//! Each provider (see [`providers`]) authenticates deliveries and parses them into
+//! a provider-independent event; acting on the event is shared. Deliveries without
+//! an event header are treated as pushes. Pushes and pull (merge) request updates
+//! queue a job; pings and other events are acknowledged with `200 OK` without
+//! queueing anything. Tag pushes queue release builds. Ref deletions and pushes
+//! whose head commit asks to skip CI (`[skip ci]` / `[ci skip]`) are acknowledged
+//! without queueing a job.
+//!
+//! Deliveries are verified with the secret of the repository they name, if it has
+//! one (configured, or generated when its webhook was registered), and otherwise
+//! with the provider's secret.
+//!
+//! Redeliveries (same provider delivery ID, e.g. `X-GitHub-Delivery`) return the job
+//! the first delivery queued. In coalesce mode a push cancels still-pending builds
+//! of the same branch.
+//!
+//! Every delivery, including rejected ones, is recorded in the delivery log served
+//! by [`deliveries::routes`].
 
+pub mod deliveries;
+mod event;
 mod payloads;
+mod providers;
+mod redelivery;
 mod signature;
 
 use axum::{
     extract::State,
     http::{HeaderMap, StatusCode},
-    response::IntoResponse,
     routing::post,
     Json, Router,
 };
-use redis::AsyncCommands;
+use raibid_common::jobs::{Job, PullRequest};
+use raibid_common::queue::JobEnvelope;
+use raibid_common::webhooks::{DeliveryOutcome, WebhookDelivery};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, warn};
-use uuid::Uuid;
 
-use crate::{error::ServerError, state::AppState};
-pub use payloads::{GitHubWebhookPayload, GiteaWebhookPayload};
-use signature::{verify_gitea_signature, verify_github_signature};
+use crate::{
+    error::ServerError,
+    queue::{enqueue_job, new_job, supersede_pending},
+    state::AppState,
+};
+use event::{PullRequestEvent, PushEvent, WebhookEvent};
+pub use payloads::{
+    Commit, GitHubWebhookPayload, GitLabMergeRequestPayload, GitLabPushPayload,
+    GiteaWebhookPayload, PullRequestWebhookPayload,
+};
+use providers::{find_provider, WebhookProvider, PROVIDERS};
+use redelivery::{deduplicate, delivery_id};
 
-/// Job metadata for Redis Stream
-#[derive(Debug, Serialize, Deserialize)]
-pub struct JobMetadata {
-    pub job_id: String,
-    pub repository: String,
-    pub branch: String,
-    pub commit: String,
-    pub author: String,
-    pub event_type: String,
-    pub created_at: String,
-}
+/// Commit message markers that skip the build of a push
+const SKIP_CI_MARKERS: &[&str] = &["[skip ci]", "[ci skip]"];
 
 /// Webhook response
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WebhookResponse {
-    pub job_id: String,
+    /// Queued job, if the event triggered one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    /// Pending jobs cancelled in favour of the queued one (coalesce mode)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub superseded: Vec<String>,
     pub message: String,
 }
 
-/// Create webhook routes
+/// Create webhook routes, one per provider
 pub fn routes() -> Router<Arc<AppState>> {
-    Router::new()
-        .route("/webhooks/gitea", post(gitea_webhook_handler))
-        .route("/webhooks/github", post(github_webhook_handler))
+    PROVIDERS.iter().fold(Router::new(), |router, &provider| {
+        router.route(
+            &format!("/webhooks/{}", provider.name()),
+            post(
+                move |State(state): State<Arc<AppState>>, headers: HeaderMap, body: String| async move {
+                    receive(&state, provider, &headers, &body, None).await.0
+                },
+            ),
+        )
+    })
+}
+
+/// Webhook handler result
+type WebhookResult = Result<(StatusCode, Json<WebhookResponse>), ServerError>;
+
+/// Handle a delivery and record it in the metrics and the delivery log
+///
+/// Replays (`replay_of` set) are never treated as redeliveries.
+async fn receive(
+    state: &AppState,
+    provider: &dyn WebhookProvider,
+    headers: &HeaderMap,
+    body: &str,
+    replay_of: Option<String>,
+) -> (WebhookResult, WebhookDelivery) {
+    let result = handle_webhook(state, provider, headers, body, replay_of.is_none()).await;
+
+    state
+        .metrics()
+        .record_webhook(provider.name(), delivery_outcome(&result).as_str());
+    let delivery = deliveries::record(state, provider, headers, body, &result, replay_of).await;
+
+    (result, delivery)
 }
 
-/// Gitea webhook handler
-async fn gitea_webhook_handler(
-    State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
-    body: String,
-) -> Result<impl IntoResponse, ServerError> {
-    info!("Received Gitea webhook request");
+/// Verify a delivery and act on its event, unless it is a redelivery
+async fn handle_webhook(
+    state: &AppState,
+    provider: &dyn WebhookProvider,
+    headers: &HeaderMap,
+    body: &str,
+    deduplicate_deliveries: bool,
+) -> WebhookResult {
+    info!("Received {} webhook request", provider.name());
 
     // Verify signature if secret is configured
-    if let Some(secret) = state.gitea_webhook_secret() {
-        let signature = headers
-            .get("X-Gitea-Signature")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| {
-                ServerError::Unauthorized("Missing X-Gitea-Signature header".to_string())
-            })?;
-
-        if !verify_gitea_signature(&body, signature, secret) {
-            warn!("Invalid Gitea webhook signature");
-            return Err(ServerError::Unauthorized("Invalid signature".to_string()));
+    if let Some(secret) = webhook_secret(state, provider, body).await? {
+        provider.verify(headers, body, &secret)?;
+    }
+
+    let delivery =
+        delivery_id(headers, provider.delivery_header()).filter(|_| deduplicate_deliveries);
+    deduplicate(
+        state,
+        provider.name(),
+        delivery,
+        handle_event(state, provider, headers, body),
+    )
+    .await
+}
+
+/// Get the secret a delivery must be verified with, if any
+///
+/// A repository with its own secret is verified with it only, so a leaked
+/// provider secret can't be used to trigger its builds.
+async fn webhook_secret(
+    state: &AppState,
+    provider: &dyn WebhookProvider,
+    body: &str,
+) -> Result<Option<String>, ServerError> {
+    if let Some(repo) = provider.repository(body) {
+        if let Some(secret) = state
+            .repository_webhook_secret(provider.name(), &repo)
+            .await?
+        {
+            return Ok(Some(secret));
         }
     }
 
-    // Parse webhook payload
-    let payload: GiteaWebhookPayload = serde_json::from_str(&body)
-        .map_err(|e| ServerError::BadRequest(format!("Invalid webhook payload: {}", e)))?;
+    Ok(provider.secret(state).map(str::to_string))
+}
+
+/// Act on a verified delivery
+async fn handle_event(
+    state: &AppState,
+    provider: &dyn WebhookProvider,
+    headers: &HeaderMap,
+    body: &str,
+) -> WebhookResult {
+    let event = headers
+        .get(provider.event_header())
+        .and_then(|v| v.to_str().ok());
 
-    // Extract metadata
-    let metadata = JobMetadata {
-        job_id: Uuid::new_v4().to_string(),
-        repository: payload.repository.full_name,
-        branch: payload.ref_name.unwrap_or_else(|| "main".to_string()),
-        commit: payload.after.unwrap_or_default(),
-        author: payload.pusher.username,
-        event_type: "push".to_string(),
-        created_at: chrono::Utc::now().to_rfc3339(),
-    };
+    match provider.parse(event, body)? {
+        WebhookEvent::Push(push) => handle_push(state, push).await,
+        WebhookEvent::PullRequest(pull_request) => handle_pull_request(state, pull_request).await,
+        WebhookEvent::Ping => Ok(acknowledged("pong")),
+        WebhookEvent::Other(event) => Ok(unsupported(&event)),
+    }
+}
 
-    // Queue job to Redis Streams
-    let job_id = queue_job(&state, &metadata).await?;
+/// Queue a build of a push, unless it shouldn't be built
+async fn handle_push(state: &AppState, push: PushEvent) -> WebhookResult {
+    if let Some(reason) = push_skip_reason(&push) {
+        return Ok(acknowledged(reason));
+    }
 
-    info!(
-        "Queued job {} for repository {}",
-        job_id, metadata.repository
+    let job = push_job(
+        push.repo,
+        push.ref_name.as_deref(),
+        push.after.unwrap_or_default(),
     );
+    queue_push(state, push_envelope(job, push.pusher)).await
+}
 
-    Ok((
-        StatusCode::ACCEPTED,
-        Json(WebhookResponse {
-            job_id: job_id.clone(),
-            message: format!("Job {} queued successfully", job_id),
-        }),
-    ))
+/// Get why a push shouldn't be built, if it shouldn't
+///
+/// Deleting a ref sends a push whose `after` is the all-zero SHA, which can't be
+/// checked out. The head commit is the one matching `after`, falling back to the
+/// last commit listed.
+fn push_skip_reason(push: &PushEvent) -> Option<String> {
+    let after = push.after.as_deref();
+    if after.is_some_and(|sha| !sha.is_empty() && sha.chars().all(|c| c == '0')) {
+        return Some(format!(
+            "Ignoring deletion of {}",
+            push.ref_name.as_deref().unwrap_or("unknown ref")
+        ));
+    }
+
+    let head = push
+        .commits
+        .iter()
+        .find(|commit| Some(commit.id.as_str()) == after)
+        .or_else(|| push.commits.last())?;
+
+    SKIP_CI_MARKERS
+        .iter()
+        .find(|marker| head.message.contains(*marker))
+        .map(|marker| format!("Skipping commit {}: message contains {}", head.id, marker))
 }
 
-/// GitHub webhook handler
-async fn github_webhook_handler(
-    State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
-    body: String,
-) -> Result<impl IntoResponse, ServerError> {
-    info!("Received GitHub webhook request");
+/// Build the job for a push, marking tag pushes as release builds
+fn push_job(repo: String, ref_name: Option<&str>, commit: String) -> Job {
+    match ref_name.and_then(|r| r.strip_prefix("refs/tags/")) {
+        Some(tag) => {
+            let mut job = new_job(repo, tag.to_string(), commit);
+            job.tag = Some(tag.to_string());
+            job
+        }
+        None => new_job(repo, branch_from_ref(ref_name), commit),
+    }
+}
 
-    // Verify signature if secret is configured
-    if let Some(secret) = state.github_webhook_secret() {
-        let signature = headers
-            .get("X-Hub-Signature-256")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| {
-                ServerError::Unauthorized("Missing X-Hub-Signature-256 header".to_string())
-            })?;
-
-        if !verify_github_signature(&body, signature, secret) {
-            warn!("Invalid GitHub webhook signature");
-            return Err(ServerError::Unauthorized("Invalid signature".to_string()));
+/// Wrap a push job in an envelope, with event type `tag` for release builds
+fn push_envelope(job: Job, pusher: Option<String>) -> JobEnvelope {
+    let event_type = if job.is_release() { "tag" } else { "push" };
+    JobEnvelope::new(job, event_type, pusher)
+}
+
+/// Queue a push build, superseding pending builds of its branch in coalesce mode
+async fn queue_push(state: &AppState, envelope: JobEnvelope) -> WebhookResult {
+    let coalesce = state.coalesce_pushes() && !envelope.job.is_release();
+    let job = envelope.job.clone();
+    let (status, Json(mut response)) = queue(state, envelope).await?;
+
+    if coalesce {
+        // The new job is queued either way; a failure leaves older builds running
+        match supersede_pending(state, &job).await {
+            Ok(superseded) => {
+                if !superseded.is_empty() {
+                    info!(
+                        "Job {} superseded pending jobs: {}",
+                        job.id,
+                        superseded.join(", ")
+                    );
+                }
+                response.superseded = superseded;
+            }
+            Err(e) => warn!("Failed to supersede pending jobs for {}: {}", job.id, e),
         }
     }
 
-    // Parse webhook payload
-    let payload: GitHubWebhookPayload = serde_json::from_str(&body)
-        .map_err(|e| ServerError::BadRequest(format!("Invalid webhook payload: {}", e)))?;
+    Ok((status, Json(response)))
+}
+
+/// Queue a build of a pull request's head ref
+async fn handle_pull_request(state: &AppState, pull_request: PullRequestEvent) -> WebhookResult {
+    if !pull_request.build {
+        return Ok(acknowledged(format!(
+            "Ignoring pull request action: {}",
+            pull_request.action
+        )));
+    }
+
+    let mut job = new_job(
+        pull_request.repo,
+        pull_request.head_ref,
+        pull_request.head_sha,
+    );
+    job.pull_request = Some(PullRequest {
+        number: pull_request.number,
+        base_branch: pull_request.base_branch,
+        head_repo: pull_request.head_repo,
+        head_ref: pull_request.pull_ref,
+    });
+
+    queue(
+        state,
+        JobEnvelope::new(job, "pull_request", pull_request.author),
+    )
+    .await
+}
 
-    // Extract metadata
-    let metadata = JobMetadata {
-        job_id: Uuid::new_v4().to_string(),
-        repository: payload.repository.full_name,
-        branch: payload.ref_name.unwrap_or_else(|| "main".to_string()),
-        commit: payload.after.unwrap_or_default(),
-        author: payload.pusher.name,
-        event_type: "push".to_string(),
-        created_at: chrono::Utc::now().to_rfc3339(),
-    };
+/// Parse a webhook payload
+fn parse_payload<T: serde::de::DeserializeOwned>(body: &str) -> Result<T, ServerError> {
+    serde_json::from_str(body)
+        .map_err(|e| ServerError::BadRequest(format!("Invalid webhook payload: {}", e)))
+}
 
-    // Queue job to Redis Streams
-    let job_id = queue_job(&state, &metadata).await?;
+/// Queue a job to Redis Streams
+async fn queue(state: &AppState, envelope: JobEnvelope) -> WebhookResult {
+    let job_id = enqueue_job(state, &envelope).await?;
 
     info!(
-        "Queued job {} for repository {}",
-        job_id, metadata.repository
+        "Queued {} job {} for repository {}",
+        envelope.event_type, job_id, envelope.job.repo
     );
 
     Ok((
         StatusCode::ACCEPTED,
         Json(WebhookResponse {
-            job_id: job_id.clone(),
+            job_id: Some(job_id.clone()),
+            superseded: Vec::new(),
             message: format!("Job {} queued successfully", job_id),
         }),
     ))
 }
 
-/// Queue a job to Redis Streams
-async fn queue_job(state: &AppState, metadata: &JobMetadata) -> Result<String, ServerError> {
-    let mut conn = state.redis_connection().await?;
+/// Acknowledge a delivery without queueing a job
+fn acknowledged(message: impl Into<String>) -> (StatusCode, Json<WebhookResponse>) {
+    let message = message.into();
+    info!("Webhook acknowledged: {}", message);
+
+    (
+        StatusCode::OK,
+        Json(WebhookResponse {
+            job_id: None,
+            superseded: Vec::new(),
+            message,
+        }),
+    )
+}
 
-    // Serialize metadata to JSON
-    let metadata_json = serde_json::to_string(metadata)?;
+/// Acknowledge an event the server doesn't build
+fn unsupported(event: &str) -> (StatusCode, Json<WebhookResponse>) {
+    acknowledged(format!("Ignoring unsupported event: {}", event))
+}
 
-    // Add job to Redis Stream using XADD
-    let _stream_id: String = conn
-        .xadd("ci:jobs", "*", &[("data", metadata_json.as_str())])
-        .await
-        .map_err(|e| ServerError::Internal(format!("Failed to queue job: {}", e)))?;
+/// Classify a webhook handler result
+fn delivery_outcome(result: &WebhookResult) -> DeliveryOutcome {
+    match result {
+        Ok((StatusCode::ACCEPTED, _)) => DeliveryOutcome::Queued,
+        Ok(_) => DeliveryOutcome::Ignored,
+        Err(ServerError::Unauthorized(_)) => DeliveryOutcome::Unauthorized,
+        Err(ServerError::BadRequest(_)) => DeliveryOutcome::Invalid,
+        Err(_) => DeliveryOutcome::Error,
+    }
+}
 
-    Ok(metadata.job_id.clone())
+/// Extract the branch name from a git ref (e.g. "refs/heads/main" -> "main")
+fn branch_from_ref(ref_name: Option<&str>) -> String {
+    match ref_name {
+        Some(r) => r.strip_prefix("refs/heads/").unwrap_or(r).to_string(),
+        None => "main".to_string(),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ServerConfig;
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
 
     #[test]
-    fn test_job_metadata_serialization() {
-        let metadata = JobMetadata {
-            job_id: "test-123".to_string(),
-            repository: "owner/repo".to_string(),
-            branch: "main".to_string(),
-            commit: "abc123".to_string(),
-            author: "testuser".to_string(),
-            event_type: "push".to_string(),
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-        };
+    fn test_branch_from_ref() {
+        assert_eq!(branch_from_ref(Some("refs/heads/main")), "main");
+        assert_eq!(branch_from_ref(Some("refs/heads/feature/x")), "feature/x");
+        assert_eq!(branch_from_ref(Some("develop")), "develop");
+        assert_eq!(branch_from_ref(None), "main");
+    }
+
+    #[test]
+    fn test_push_job_detects_tags() {
+        let job = push_job(
+            "owner/repo".to_string(),
+            Some("refs/tags/v1.2.3"),
+            "abc123".to_string(),
+        );
+        assert!(job.is_release());
+        assert_eq!(job.tag.as_deref(), Some("v1.2.3"));
+        assert_eq!(job.branch, "v1.2.3");
+        assert_eq!(
+            push_envelope(job, Some("alice".to_string())).event_type,
+            "tag"
+        );
+
+        let job = push_job(
+            "owner/repo".to_string(),
+            Some("refs/heads/main"),
+            "abc123".to_string(),
+        );
+        assert!(!job.is_release());
+        assert_eq!(job.branch, "main");
+        assert_eq!(
+            push_envelope(job, Some("alice".to_string())).event_type,
+            "push"
+        );
+    }
+
+    fn push(ref_name: &str, after: &str, commits: &[(&str, &str)]) -> PushEvent {
+        PushEvent {
+            repo: "owner/repo".to_string(),
+            ref_name: Some(ref_name.to_string()),
+            after: Some(after.to_string()),
+            pusher: None,
+            commits: commits
+                .iter()
+                .map(|(id, message)| event::PushCommit {
+                    id: id.to_string(),
+                    message: message.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_push_skip_reason() {
+        let zero = "0".repeat(40);
+        assert_eq!(
+            push_skip_reason(&push("refs/heads/old", &zero, &[])).as_deref(),
+            Some("Ignoring deletion of refs/heads/old")
+        );
+
+        let commits = [("aaa", "WIP [skip ci]"), ("bbb", "Fix build\n\n[ci skip]")];
+        assert_eq!(
+            push_skip_reason(&push("refs/heads/main", "bbb", &commits)).as_deref(),
+            Some("Skipping commit bbb: message contains [ci skip]")
+        );
 
-        let json = serde_json::to_string(&metadata).unwrap();
-        assert!(json.contains("test-123"));
-        assert!(json.contains("owner/repo"));
+        // Only the head commit's message counts
+        let commits = [("aaa", "WIP [skip ci]"), ("bbb", "Finish")];
+        assert_eq!(
+            push_skip_reason(&push("refs/heads/main", "bbb", &commits)),
+            None
+        );
+        assert_eq!(
+            push_skip_reason(&push("refs/heads/main", "abc123", &[])),
+            None
+        );
     }
 
     #[test]
     fn test_webhook_response_serialization() {
         let response = WebhookResponse {
-            job_id: "job-123".to_string(),
+            job_id: Some("job-123".to_string()),
+            superseded: Vec::new(),
             message: "Job queued".to_string(),
         };
 
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("job-123"));
         assert!(json.contains("Job queued"));
+
+        let ignored = WebhookResponse {
+            job_id: None,
+            superseded: Vec::new(),
+            message: "pong".to_string(),
+        };
+        let json = serde_json::to_string(&ignored).unwrap();
+        assert!(!json.contains("job_id"));
+        assert!(!json.contains("superseded"));
+    }
+
+    async fn deliver(uri: &str, event_header: &str, event: &str, body: &str) -> StatusCode {
+        let app = routes().with_state(Arc::new(AppState::new()));
+        app.oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(event_header, event)
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+    }
+
+    #[tokio::test]
+    async fn test_ping_and_unsupported_events_acknowledged() {
+        // Neither body is a push payload; they must not be parsed as one
+        assert_eq!(
+            deliver(
+                "/webhooks/github",
+                "X-GitHub-Event",
+                "ping",
+                r#"{"zen":"hi"}"#
+            )
+            .await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            deliver("/webhooks/gitea", "X-Gitea-Event", "issues", "{}").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            deliver("/webhooks/forgejo", "X-Forgejo-Event", "ping", "{}").await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_token_checked() {
+        let config = ServerConfig {
+            gitlab_webhook_secret: Some("gitlab-token".to_string()),
+            ..ServerConfig::default()
+        };
+        let state = Arc::new(AppState::from_server_config(&config).unwrap());
+        let app = routes().with_state(state);
+
+        let request = |token: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("/webhooks/gitlab")
+                .header("X-Gitlab-Event", "Note Hook")
+                .header("X-Gitlab-Token", token)
+                .body(Body::from("{}"))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request("wrong")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.oneshot(request("gitlab-token")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_repository_secret_overrides_provider_secret() {
+        use hmac::{Hmac, Mac};
+        use raibid_common::store::{
+            InMemoryDeliveryLog, InMemoryWebhookSecretStore, WebhookSecretStore,
+        };
+
+        let config = ServerConfig {
+            github_webhook_secret: Some("global".to_string()),
+            repository_webhook_secrets: [("owner/pinned".to_string(), "pinned".to_string())].into(),
+            ..ServerConfig::default()
+        };
+        let store = Arc::new(InMemoryWebhookSecretStore::new());
+        store
+            .set("github", "owner/generated", "generated")
+            .await
+            .unwrap();
+        // A secret for another provider's repository of the same name
+        store.set("gitea", "owner/other", "gitea").await.unwrap();
+        let state = AppState::from_server_config(&config)
+            .unwrap()
+            .with_webhook_secret_store(store)
+            .with_delivery_log(Arc::new(InMemoryDeliveryLog::default()));
+        let app = routes().with_state(Arc::new(state));
+
+        let ping = |repo: &str, secret: &str| {
+            let body = format!(r#"{{"zen":"hi","repository":{{"full_name":"{}"}}}}"#, repo);
+            let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+            mac.update(body.as_bytes());
+            Request::builder()
+                .method("POST")
+                .uri("/webhooks/github")
+                .header("X-GitHub-Event", "ping")
+                .header(
+                    "X-Hub-Signature-256",
+                    format!("sha256={}", hex::encode(mac.finalize().into_bytes())),
+                )
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        for (repo, secret, expected) in [
+            ("owner/pinned", "pinned", StatusCode::OK),
+            ("owner/pinned", "global", StatusCode::UNAUTHORIZED),
+            ("owner/generated", "generated", StatusCode::OK),
+            ("owner/generated", "global", StatusCode::UNAUTHORIZED),
+            ("owner/other", "global", StatusCode::OK),
+            ("owner/other", "pinned", StatusCode::UNAUTHORIZED),
+            ("owner/other", "gitea", StatusCode::UNAUTHORIZED),
+        ] {
+            let response = app.clone().oneshot(ping(repo, secret)).await.unwrap();
+            assert_eq!(
+                response.status(),
+                expected,
+                "{} signed with {}",
+                repo,
+                secret
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_closed_merge_request_not_queued() {
+        let body = r#"{
+            "object_kind": "merge_request",
+            "user": {"username": "alice"},
+            "project": {"path_with_namespace": "group/app"},
+            "object_attributes": {
+                "iid": 4,
+                "action": "close",
+                "source_branch": "feature",
+                "target_branch": "main",
+                "last_commit": {"id": "def456"}
+            }
+        }"#;
+
+        // No Redis is configured, so queueing would fail with a 500
+        assert_eq!(
+            deliver(
+                "/webhooks/gitlab",
+                "X-Gitlab-Event",
+                "Merge Request Hook",
+                body
+            )
+            .await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_branch_deletion_not_queued() {
+        let body = r#"{
+            "ref": "refs/heads/feature",
+            "before": "def456",
+            "after": "0000000000000000000000000000000000000000",
+            "repository": {
+                "id": 1,
+                "name": "test-repo",
+                "full_name": "owner/test-repo",
+                "owner": {"id": 1, "login": "owner"},
+                "html_url": "https://git.example.com/owner/test-repo",
+                "clone_url": "https://git.example.com/owner/test-repo.git",
+                "ssh_url": "git@git.example.com:owner/test-repo.git",
+                "default_branch": "main"
+            },
+            "pusher": {"id": 1, "username": "owner"},
+            "commits": []
+        }"#;
+
+        // No Redis is configured, so queueing would fail with a 500
+        assert_eq!(
+            deliver("/webhooks/gitea", "X-Gitea-Event", "push", body).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_closed_pull_request_not_queued() {
+        let body = r#"{
+            "action": "closed",
+            "number": 3,
+            "pull_request": {
+                "number": 3,
+                "head": {"ref": "feature", "sha": "def456"},
+                "base": {"ref": "main", "sha": "abc123"}
+            },
+            "repository": {
+                "id": 1,
+                "name": "test-repo",
+                "full_name": "owner/test-repo",
+                "owner": {"id": 1, "login": "owner"},
+                "html_url": "https://github.com/owner/test-repo",
+                "clone_url": "https://github.com/owner/test-repo.git",
+                "ssh_url": "git@github.com:owner/test-repo.git",
+                "default_branch": "main"
+            }
+        }"#;
+
+        // No Redis is configured, so queueing would fail with a 500
+        assert_eq!(
+            deliver("/webhooks/github", "X-GitHub-Event", "pull_request", body).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deliveries_recorded_and_replayed() {
+        let state = Arc::new(AppState::new().with_delivery_api(true));
+        let app = routes()
+            .merge(deliveries::routes())
+            .with_state(state.clone());
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/webhooks/gitea")
+                    .header("X-Gitea-Event", "issues")
+                    .header("X-Gitea-Delivery", "delivery-1")
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let recorded = state.delivery_log().list(10).await.unwrap();
+        assert_eq!(recorded.len(), 1);
+        let original = &recorded[0];
+        assert_eq!(original.provider, "gitea");
+        assert_eq!(original.event.as_deref(), Some("issues"));
+        assert_eq!(original.delivery_id.as_deref(), Some("delivery-1"));
+        assert_eq!(original.outcome, DeliveryOutcome::Ignored);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/webhooks/deliveries/{}/replay", original.id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let replay: WebhookDelivery = serde_json::from_slice(&body).unwrap();
+        assert_eq!(replay.replay_of.as_deref(), Some(original.id.as_str()));
+        assert_eq!(replay.event.as_deref(), Some("issues"));
+        assert_eq!(state.delivery_log().list(10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_api_disabled_by_default() {
+        let state = Arc::new(AppState::new());
+        let app = routes()
+            .merge(deliveries::routes())
+            .with_state(state.clone());
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/webhooks/gitea")
+                    .header("X-Gitea-Event", "issues")
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let original = state.delivery_log().list(10).await.unwrap().remove(0);
+
+        for (method, uri) in [
+            ("GET", "/webhooks/deliveries".to_string()),
+            ("GET", format!("/webhooks/deliveries/{}", original.id)),
+            (
+                "POST",
+                format!("/webhooks/deliveries/{}/replay", original.id),
+            ),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri(&uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::NOT_FOUND,
+                "{} {}",
+                method,
+                uri
+            );
+        }
+
+        // Nothing was replayed
+        assert_eq!(state.delivery_log().list(10).await.unwrap().len(), 1);
     }
 }
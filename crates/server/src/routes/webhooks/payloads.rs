@@ -1,4 +1,8 @@
-//! Webhook payload structures for GitHub and Gitea
+//! Webhook payload structures for GitHub, Gitea, Forgejo and GitLab
+//!
+//! Push payloads differ slightly between GitHub and Gitea (which Forgejo shares);
+//! their pull request payloads share the fields the server reads. GitLab has its own
+//! push and merge request payloads.
 
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +30,56 @@ pub struct GitHubWebhookPayload {
     pub commits: Option<Vec<Commit>>,
 }
 
+/// Pull request webhook payload (GitHub and Gitea)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestWebhookPayload {
+    pub action: String,
+    pub number: u64,
+    pub pull_request: PullRequestData,
+    pub repository: Repository,
+    pub sender: Option<Sender>,
+}
+
+/// Pull request details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestData {
+    pub number: u64,
+    pub title: Option<String>,
+    pub html_url: Option<String>,
+    pub head: PullRequestBranch,
+    pub base: PullRequestBranch,
+}
+
+/// Head or base of a pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestBranch {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub sha: String,
+    pub repo: Option<PullRequestRepository>,
+}
+
+/// Repository a pull request branch lives in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestRepository {
+    pub full_name: String,
+    pub clone_url: Option<String>,
+}
+
+/// User that triggered an event (GitHub sets `login`, Gitea sets both)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sender {
+    pub login: Option<String>,
+    pub username: Option<String>,
+}
+
+impl Sender {
+    /// Get the sender's user name
+    pub fn name(&self) -> Option<String> {
+        self.login.clone().or_else(|| self.username.clone())
+    }
+}
+
 /// Repository information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Repository {
@@ -82,6 +136,73 @@ pub struct CommitAuthor {
     pub username: Option<String>,
 }
 
+/// GitLab push and tag push payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabPushPayload {
+    pub object_kind: String,
+    #[serde(rename = "ref")]
+    pub ref_name: Option<String>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub checkout_sha: Option<String>,
+    pub user_username: Option<String>,
+    pub project: GitLabProject,
+    #[serde(default)]
+    pub commits: Vec<GitLabCommit>,
+}
+
+/// GitLab merge request payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabMergeRequestPayload {
+    pub object_kind: String,
+    pub user: Option<GitLabUser>,
+    pub project: GitLabProject,
+    pub object_attributes: GitLabMergeRequest,
+}
+
+/// GitLab merge request details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabMergeRequest {
+    pub iid: u64,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub action: Option<String>,
+    /// Previous head commit, only set when an update pushed new commits
+    pub oldrev: Option<String>,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub last_commit: Option<GitLabCommit>,
+    pub source: Option<GitLabProject>,
+}
+
+/// GitLab project information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabProject {
+    pub id: Option<u64>,
+    pub name: Option<String>,
+    pub path_with_namespace: String,
+    pub web_url: Option<String>,
+    pub git_http_url: Option<String>,
+    pub git_ssh_url: Option<String>,
+    pub default_branch: Option<String>,
+}
+
+/// GitLab user information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabUser {
+    pub username: String,
+    pub name: Option<String>,
+}
+
+/// GitLab commit information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabCommit {
+    pub id: String,
+    #[serde(default)]
+    pub message: String,
+    pub url: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +273,52 @@ mod tests {
         assert_eq!(payload.pusher.name, "testuser");
     }
 
+    #[test]
+    fn test_pull_request_payload_deserialization() {
+        let json = r#"{
+            "action": "synchronize",
+            "number": 7,
+            "pull_request": {
+                "number": 7,
+                "title": "Add feature",
+                "head": {
+                    "ref": "feature",
+                    "sha": "def456",
+                    "repo": {"full_name": "contributor/test-repo"}
+                },
+                "base": {
+                    "ref": "main",
+                    "sha": "abc123",
+                    "repo": {"full_name": "owner/test-repo"}
+                }
+            },
+            "repository": {
+                "id": 1,
+                "name": "test-repo",
+                "full_name": "owner/test-repo",
+                "owner": {"id": 1, "login": "owner"},
+                "html_url": "https://github.com/owner/test-repo",
+                "clone_url": "https://github.com/owner/test-repo.git",
+                "ssh_url": "git@github.com:owner/test-repo.git",
+                "default_branch": "main"
+            },
+            "sender": {"login": "contributor"}
+        }"#;
+
+        let payload: PullRequestWebhookPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.action, "synchronize");
+        assert_eq!(payload.pull_request.head.ref_name, "feature");
+        assert_eq!(payload.pull_request.base.ref_name, "main");
+        assert_eq!(
+            payload.pull_request.head.repo.unwrap().full_name,
+            "contributor/test-repo"
+        );
+        assert_eq!(
+            payload.sender.and_then(|s| s.name()),
+            Some("contributor".to_string())
+        );
+    }
+
     #[test]
     fn test_repository_serialization() {
         let repo = Repository {
@@ -173,4 +340,33 @@ mod tests {
         assert!(json.contains("test-repo"));
         assert!(json.contains("owner/test-repo"));
     }
+
+    #[test]
+    fn test_gitlab_push_payload_deserialization() {
+        let json = r#"{
+            "object_kind": "tag_push",
+            "ref": "refs/tags/v1.0.0",
+            "before": "0000000000000000000000000000000000000000",
+            "after": "82b3d5ae55f7080f1e6022629cdb57bfae7cccc7",
+            "checkout_sha": "82b3d5ae55f7080f1e6022629cdb57bfae7cccc7",
+            "user_username": "jsmith",
+            "project": {
+                "id": 1,
+                "name": "Example",
+                "path_with_namespace": "jsmith/example",
+                "web_url": "https://gitlab.example.com/jsmith/example",
+                "git_http_url": "https://gitlab.example.com/jsmith/example.git",
+                "default_branch": "main"
+            },
+            "commits": [],
+            "total_commits_count": 0
+        }"#;
+
+        let payload: GitLabPushPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.object_kind, "tag_push");
+        assert_eq!(payload.ref_name.as_deref(), Some("refs/tags/v1.0.0"));
+        assert_eq!(payload.project.path_with_namespace, "jsmith/example");
+        assert_eq!(payload.user_username.as_deref(), Some("jsmith"));
+        assert!(payload.commits.is_empty());
+    }
 }
@@ -0,0 +1,325 @@
+//! Webhook providers
+//!
+//! A [`WebhookProvider`] knows how a forge authenticates its deliveries, which
+//! headers carry the event and delivery ID, and how to read its payloads into a
+//! [`WebhookEvent`]. Everything after that is shared by all providers.
+//!
+//! The repository a delivery is about is read before the delivery is verified, so
+//! that repositories with their own webhook secret are verified with it.
+//!
+//! Forgejo sends Gitea's payloads (and also Gitea's headers); it gets its own route
+//! and secret so both forges can be used side by side.
+
+use axum::http::HeaderMap;
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+use super::event::{PullRequestEvent, PushEvent, WebhookEvent};
+use super::parse_payload;
+use super::payloads::{
+    GitHubWebhookPayload, GitLabMergeRequestPayload, GitLabPushPayload, GiteaWebhookPayload,
+    PullRequestWebhookPayload,
+};
+use super::signature::{verify_gitea_signature, verify_github_signature, verify_gitlab_token};
+use crate::{error::ServerError, state::AppState};
+
+/// Providers the server accepts deliveries from, each at `/webhooks/{name}`
+pub(super) static PROVIDERS: &[&dyn WebhookProvider] = &[&GitHub, &Gitea, &Forgejo, &GitLab];
+
+/// A forge that sends webhooks
+pub(super) trait WebhookProvider: Send + Sync {
+    /// Name used in the route, metrics and the delivery log
+    fn name(&self) -> &'static str;
+
+    /// Header carrying the event type
+    fn event_header(&self) -> &'static str;
+
+    /// Header carrying the delivery ID, shared by redeliveries
+    fn delivery_header(&self) -> &'static str;
+
+    /// Get the configured secret, if deliveries must be authenticated
+    fn secret<'a>(&self, state: &'a AppState) -> Option<&'a str>;
+
+    /// Get the full name of the repository a delivery is about, if it names one
+    fn repository(&self, body: &str) -> Option<String> {
+        json_string(body, "/repository/full_name")
+    }
+
+    /// Check a delivery's signature or token against the secret
+    fn verify(&self, headers: &HeaderMap, body: &str, secret: &str) -> Result<(), ServerError>;
+
+    /// Parse a delivery into an event, given the event header's value
+    fn parse(&self, event: Option<&str>, body: &str) -> Result<WebhookEvent, ServerError>;
+}
+
+/// Look up a provider by name
+pub(super) fn find_provider(name: &str) -> Option<&'static dyn WebhookProvider> {
+    PROVIDERS
+        .iter()
+        .copied()
+        .find(|provider| provider.name() == name)
+}
+
+/// GitHub
+pub(super) struct GitHub;
+
+impl WebhookProvider for GitHub {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn event_header(&self) -> &'static str {
+        "X-GitHub-Event"
+    }
+
+    fn delivery_header(&self) -> &'static str {
+        "X-GitHub-Delivery"
+    }
+
+    fn secret<'a>(&self, state: &'a AppState) -> Option<&'a str> {
+        state.github_webhook_secret()
+    }
+
+    fn verify(&self, headers: &HeaderMap, body: &str, secret: &str) -> Result<(), ServerError> {
+        let signature = required_header(headers, "X-Hub-Signature-256")?;
+        check_signature(self, verify_github_signature(body, signature, secret))
+    }
+
+    fn parse(&self, event: Option<&str>, body: &str) -> Result<WebhookEvent, ServerError> {
+        parse_event::<GitHubWebhookPayload>(event, body)
+    }
+}
+
+/// Gitea
+pub(super) struct Gitea;
+
+impl WebhookProvider for Gitea {
+    fn name(&self) -> &'static str {
+        "gitea"
+    }
+
+    fn event_header(&self) -> &'static str {
+        "X-Gitea-Event"
+    }
+
+    fn delivery_header(&self) -> &'static str {
+        "X-Gitea-Delivery"
+    }
+
+    fn secret<'a>(&self, state: &'a AppState) -> Option<&'a str> {
+        state.gitea_webhook_secret()
+    }
+
+    fn verify(&self, headers: &HeaderMap, body: &str, secret: &str) -> Result<(), ServerError> {
+        let signature = required_header(headers, "X-Gitea-Signature")?;
+        check_signature(self, verify_gitea_signature(body, signature, secret))
+    }
+
+    fn parse(&self, event: Option<&str>, body: &str) -> Result<WebhookEvent, ServerError> {
+        parse_event::<GiteaWebhookPayload>(event, body)
+    }
+}
+
+/// Forgejo, which signs deliveries and shapes payloads the way Gitea does
+pub(super) struct Forgejo;
+
+impl WebhookProvider for Forgejo {
+    fn name(&self) -> &'static str {
+        "forgejo"
+    }
+
+    fn event_header(&self) -> &'static str {
+        "X-Forgejo-Event"
+    }
+
+    fn delivery_header(&self) -> &'static str {
+        "X-Forgejo-Delivery"
+    }
+
+    fn secret<'a>(&self, state: &'a AppState) -> Option<&'a str> {
+        state.forgejo_webhook_secret()
+    }
+
+    fn verify(&self, headers: &HeaderMap, body: &str, secret: &str) -> Result<(), ServerError> {
+        let signature = required_header(headers, "X-Forgejo-Signature")?;
+        check_signature(self, verify_gitea_signature(body, signature, secret))
+    }
+
+    fn parse(&self, event: Option<&str>, body: &str) -> Result<WebhookEvent, ServerError> {
+        parse_event::<GiteaWebhookPayload>(event, body)
+    }
+}
+
+/// GitLab
+///
+/// Deliveries carry the secret token itself rather than a signature, and retries
+/// share an `Idempotency-Key`. GitLab has no ping event; "Test" deliveries from
+/// the webhook settings send a push.
+pub(super) struct GitLab;
+
+impl WebhookProvider for GitLab {
+    fn name(&self) -> &'static str {
+        "gitlab"
+    }
+
+    fn event_header(&self) -> &'static str {
+        "X-Gitlab-Event"
+    }
+
+    fn delivery_header(&self) -> &'static str {
+        "Idempotency-Key"
+    }
+
+    fn secret<'a>(&self, state: &'a AppState) -> Option<&'a str> {
+        state.gitlab_webhook_secret()
+    }
+
+    fn repository(&self, body: &str) -> Option<String> {
+        json_string(body, "/project/path_with_namespace")
+    }
+
+    fn verify(&self, headers: &HeaderMap, _body: &str, secret: &str) -> Result<(), ServerError> {
+        let token = required_header(headers, "X-Gitlab-Token")?;
+        if verify_gitlab_token(token, secret) {
+            Ok(())
+        } else {
+            warn!("Invalid GitLab webhook token");
+            Err(ServerError::Unauthorized("Invalid token".to_string()))
+        }
+    }
+
+    fn parse(&self, event: Option<&str>, body: &str) -> Result<WebhookEvent, ServerError> {
+        match event {
+            None | Some("Push Hook") | Some("Tag Push Hook") => Ok(WebhookEvent::Push(
+                parse_payload::<GitLabPushPayload>(body)?.into(),
+            )),
+            Some("Merge Request Hook") => Ok(WebhookEvent::PullRequest(
+                parse_payload::<GitLabMergeRequestPayload>(body)?.into(),
+            )),
+            Some(other) => Ok(WebhookEvent::Other(other.to_string())),
+        }
+    }
+}
+
+/// Parse a GitHub-style delivery (GitHub, Gitea, Forgejo) with push payload `P`
+///
+/// A missing event header is treated as a push.
+fn parse_event<P>(event: Option<&str>, body: &str) -> Result<WebhookEvent, ServerError>
+where
+    P: DeserializeOwned + Into<PushEvent>,
+{
+    match event {
+        None | Some("push") => Ok(WebhookEvent::Push(parse_payload::<P>(body)?.into())),
+        Some("pull_request") => Ok(WebhookEvent::PullRequest(PullRequestEvent::from(
+            parse_payload::<PullRequestWebhookPayload>(body)?,
+        ))),
+        Some("ping") => Ok(WebhookEvent::Ping),
+        Some(other) => Ok(WebhookEvent::Other(other.to_string())),
+    }
+}
+
+/// Read a string out of a JSON body by pointer, without validating the payload
+fn json_string(body: &str, pointer: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    value.pointer(pointer)?.as_str().map(str::to_string)
+}
+
+/// Get a header the provider must send when a secret is configured
+fn required_header<'a>(headers: &'a HeaderMap, name: &str) -> Result<&'a str, ServerError> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| ServerError::Unauthorized(format!("Missing {} header", name)))
+}
+
+/// Turn a signature check into a result
+fn check_signature(provider: &dyn WebhookProvider, valid: bool) -> Result<(), ServerError> {
+    if valid {
+        Ok(())
+    } else {
+        warn!("Invalid {} webhook signature", provider.name());
+        Err(ServerError::Unauthorized("Invalid signature".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_provider() {
+        for name in ["github", "gitea", "forgejo", "gitlab"] {
+            assert_eq!(find_provider(name).unwrap().name(), name);
+        }
+        assert!(find_provider("bitbucket").is_none());
+    }
+
+    #[test]
+    fn test_parse_event_kinds() {
+        assert_eq!(
+            GitHub.parse(Some("ping"), "{}").unwrap(),
+            WebhookEvent::Ping
+        );
+        assert_eq!(
+            Forgejo.parse(Some("issues"), "{}").unwrap(),
+            WebhookEvent::Other("issues".to_string())
+        );
+        assert_eq!(
+            GitLab.parse(Some("Note Hook"), "{}").unwrap(),
+            WebhookEvent::Other("Note Hook".to_string())
+        );
+
+        // A missing header means a push, so the body must be a push payload
+        assert!(matches!(
+            Gitea.parse(None, "{}"),
+            Err(ServerError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_gitlab_tag_push_uses_checkout_sha() {
+        let body = r#"{
+            "object_kind": "tag_push",
+            "ref": "refs/tags/v1.0.0",
+            "before": "0000000000000000000000000000000000000000",
+            "after": "a1b2c3d4e5",
+            "checkout_sha": "82b3d5ae55",
+            "user_username": "jsmith",
+            "project": {"path_with_namespace": "jsmith/example"}
+        }"#;
+
+        let WebhookEvent::Push(push) = GitLab.parse(Some("Tag Push Hook"), body).unwrap() else {
+            panic!("expected a push");
+        };
+        assert_eq!(push.repo, "jsmith/example");
+        assert_eq!(push.after.as_deref(), Some("82b3d5ae55"));
+        assert_eq!(push.pusher.as_deref(), Some("jsmith"));
+    }
+
+    #[test]
+    fn test_repository() {
+        let body = r#"{"zen": "hi", "repository": {"full_name": "owner/repo"}}"#;
+        assert_eq!(GitHub.repository(body).as_deref(), Some("owner/repo"));
+        assert_eq!(Forgejo.repository(body).as_deref(), Some("owner/repo"));
+        assert!(GitLab.repository(body).is_none());
+
+        let body = r#"{"project": {"path_with_namespace": "group/app"}}"#;
+        assert_eq!(GitLab.repository(body).as_deref(), Some("group/app"));
+        assert!(Gitea.repository("not json").is_none());
+    }
+
+    #[test]
+    fn test_gitlab_token_required() {
+        let mut headers = HeaderMap::new();
+        assert!(matches!(
+            GitLab.verify(&headers, "{}", "secret"),
+            Err(ServerError::Unauthorized(message)) if message == "Missing X-Gitlab-Token header"
+        ));
+
+        headers.insert("X-Gitlab-Token", "wrong".parse().unwrap());
+        assert!(GitLab.verify(&headers, "{}", "secret").is_err());
+
+        headers.insert("X-Gitlab-Token", "secret".parse().unwrap());
+        assert!(GitLab.verify(&headers, "{}", "secret").is_ok());
+    }
+}
@@ -0,0 +1,184 @@
+//! Webhook redelivery detection
+//!
+//! GitHub and Gitea retry deliveries they consider failed, reusing the delivery ID
+//! from `X-GitHub-Delivery` / `X-Gitea-Delivery`. The first delivery claims its ID
+//! in Redis; redeliveries within the TTL get the original job back instead of
+//! queueing another one. Deduplication is best effort: if Redis can't be reached
+//! the delivery is handled as usual.
+
+use axum::{
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use redis::AsyncCommands;
+use std::future::Future;
+use tracing::{info, warn};
+
+use super::{WebhookResponse, WebhookResult};
+use crate::{error::ServerError, state::AppState};
+
+/// Value held by a delivery key while it is handled, or when it queued no job
+const NO_JOB: &str = "";
+
+/// Key recording a delivery and the job it queued
+fn delivery_key(provider: &str, delivery_id: &str) -> String {
+    format!("raibid:webhooks:delivery:{}:{}", provider, delivery_id)
+}
+
+/// Read a delivery ID header
+pub(super) fn delivery_id<'a>(headers: &'a HeaderMap, header: &str) -> Option<&'a str> {
+    headers
+        .get(header)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| !id.is_empty())
+}
+
+/// Handle a delivery once per delivery ID
+///
+/// `handle` only runs for deliveries seen for the first time. Failed deliveries
+/// release their ID so the provider's retry is handled again.
+pub(super) async fn deduplicate(
+    state: &AppState,
+    provider: &str,
+    delivery_id: Option<&str>,
+    handle: impl Future<Output = WebhookResult>,
+) -> WebhookResult {
+    let Some(delivery_id) = delivery_id else {
+        return handle.await;
+    };
+    let key = delivery_key(provider, delivery_id);
+
+    match claim(state, &key).await {
+        Ok(None) => {}
+        Ok(Some(job_id)) => return Ok(duplicate(delivery_id, job_id)),
+        Err(e) => {
+            warn!(
+                "Failed to record webhook delivery {}, handling it without deduplication: {}",
+                delivery_id, e
+            );
+            return handle.await;
+        }
+    }
+
+    let result = handle.await;
+    let recorded = match &result {
+        Ok((
+            _,
+            Json(WebhookResponse {
+                job_id: Some(job_id),
+                ..
+            }),
+        )) => record(state, &key, job_id).await,
+        Ok(_) => Ok(()),
+        Err(_) => release(state, &key).await,
+    };
+    if let Err(e) = recorded {
+        warn!("Failed to record webhook delivery {}: {}", delivery_id, e);
+    }
+
+    result
+}
+
+/// Claim a delivery key, returning the job of an earlier delivery if already claimed
+///
+/// The returned job ID is empty if the earlier delivery queued no job or is still
+/// being handled.
+async fn claim(state: &AppState, key: &str) -> Result<Option<String>, ServerError> {
+    let mut conn = state.redis_connection().await?;
+    let claimed: Option<String> = redis::cmd("SET")
+        .arg(key)
+        .arg(NO_JOB)
+        .arg("NX")
+        .arg("EX")
+        .arg(state.webhook_delivery_ttl().as_secs())
+        .query_async(&mut conn)
+        .await?;
+
+    if claimed.is_some() {
+        return Ok(None);
+    }
+
+    let job_id: Option<String> = conn.get(key).await?;
+    Ok(Some(job_id.unwrap_or_default()))
+}
+
+/// Record the job a delivery queued
+async fn record(state: &AppState, key: &str, job_id: &str) -> Result<(), ServerError> {
+    let mut conn = state.redis_connection().await?;
+    let _: () = conn
+        .set_ex(key, job_id, state.webhook_delivery_ttl().as_secs())
+        .await?;
+    Ok(())
+}
+
+/// Forget a delivery so a retry is handled again
+async fn release(state: &AppState, key: &str) -> Result<(), ServerError> {
+    let mut conn = state.redis_connection().await?;
+    let _: () = conn.del(key).await?;
+    Ok(())
+}
+
+/// Respond to a redelivery with the job the original delivery queued
+fn duplicate(delivery_id: &str, job_id: String) -> (StatusCode, Json<WebhookResponse>) {
+    let (job_id, message) = if job_id == NO_JOB {
+        (
+            None,
+            format!("Duplicate delivery {}: already handled", delivery_id),
+        )
+    } else {
+        let message = format!(
+            "Duplicate delivery {}: job {} already queued",
+            delivery_id, job_id
+        );
+        (Some(job_id), message)
+    };
+    info!("Webhook acknowledged: {}", message);
+
+    (
+        StatusCode::OK,
+        Json(WebhookResponse {
+            job_id,
+            superseded: Vec::new(),
+            message,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::webhooks::acknowledged;
+
+    #[test]
+    fn test_delivery_id() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(delivery_id(&headers, "X-GitHub-Delivery"), None);
+
+        headers.insert("X-GitHub-Delivery", "".parse().unwrap());
+        assert_eq!(delivery_id(&headers, "X-GitHub-Delivery"), None);
+
+        headers.insert("X-GitHub-Delivery", "72d3162e".parse().unwrap());
+        assert_eq!(delivery_id(&headers, "X-GitHub-Delivery"), Some("72d3162e"));
+    }
+
+    #[test]
+    fn test_duplicate_returns_original_job() {
+        let (status, Json(response)) = duplicate("72d3162e", "job-1".to_string());
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response.job_id.as_deref(), Some("job-1"));
+
+        let (_, Json(response)) = duplicate("72d3162e", NO_JOB.to_string());
+        assert_eq!(response.job_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_deduplicate_without_redis_handles_delivery() {
+        let state = AppState::new();
+        let result = deduplicate(&state, "github", Some("72d3162e"), async {
+            Ok(acknowledged("pong"))
+        })
+        .await;
+
+        assert!(matches!(result, Ok((StatusCode::OK, _))));
+    }
+}
@@ -1,4 +1,4 @@
-//! Webhook signature verification for GitHub and Gitea
+//! Webhook signature verification for GitHub, Gitea (and Forgejo) and GitLab
 
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
@@ -53,6 +53,14 @@ pub fn verify_github_signature(payload: &str, signature: &str, secret: &str) ->
     constant_time_compare(&expected, signature)
 }
 
+/// Verify GitLab webhook token
+///
+/// GitLab doesn't sign payloads; it sends the configured secret token as is
+/// in the X-Gitlab-Token header.
+pub fn verify_gitlab_token(token: &str, secret: &str) -> bool {
+    constant_time_compare(secret, token)
+}
+
 /// Constant-time string comparison to prevent timing attacks
 fn constant_time_compare(a: &str, b: &str) -> bool {
     if a.len() != b.len() {
@@ -119,6 +127,13 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_gitlab_token_verification() {
+        assert!(verify_gitlab_token("my-secret-key", "my-secret-key"));
+        assert!(!verify_gitlab_token("wrong-secret", "my-secret-key"));
+        assert!(!verify_gitlab_token("", "my-secret-key"));
+    }
+
     #[test]
     fn test_constant_time_compare() {
         assert!(constant_time_compare("abc123", "abc123"));
@@ -1,8 +1,28 @@
 //! Shared application state
 
+use raibid_common::agents::DEFAULT_AGENT_OFFLINE_SECS;
+use raibid_common::commit_status::CommitStatusReporter;
+use raibid_common::gitea_api::GiteaClient;
+use raibid_common::queue::{
+    DEFAULT_CONSUMER_GROUP, DEFAULT_JOB_STREAM, DEFAULT_JOB_STREAM_MAX_LENGTH,
+};
+use raibid_common::retention::RetentionPolicy;
+use raibid_common::storage::ObjectStore;
+use raibid_common::store::{
+    AgentRegistry, DeliveryLog, InMemoryAgentRegistry, InMemoryDeliveryLog, InMemoryJobStore,
+    InMemoryWebhookSecretStore, JobStore, RedisAgentRegistry, RedisDeliveryLog, RedisJobStore,
+    RedisWebhookSecretStore, WebhookSecretStore,
+};
+use raibid_common::webhooks::DEFAULT_DELIVERY_LOG_SIZE;
+use raibid_common::Job;
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+
+use crate::config::{ServerConfig, DEFAULT_WEBHOOK_DELIVERY_TTL_SECS};
+use crate::metrics::Metrics;
 
 /// Application state shared across all handlers
 #[derive(Clone)]
@@ -19,14 +39,74 @@ pub struct AppState {
     /// Health check status
     health_status: Arc<RwLock<HealthStatus>>,
 
+    /// Prometheus metrics
+    metrics: Metrics,
+
     /// Redis client (optional for testing)
     redis_client: Option<redis::Client>,
 
+    /// Job record store
+    job_store: Arc<dyn JobStore>,
+
+    /// Registry agents heartbeat into
+    agent_registry: Arc<dyn AgentRegistry>,
+
+    /// Heartbeat age after which an agent is reported offline
+    agent_offline_after: Duration,
+
+    /// Log of recent webhook deliveries
+    delivery_log: Arc<dyn DeliveryLog>,
+
+    /// Redis stream used as the job queue
+    job_stream: String,
+
+    /// Consumer group agents use to read the job queue
+    consumer_group: String,
+
+    /// Entries the job queue stream is trimmed to when pruning (0 = unlimited)
+    job_stream_max_length: usize,
+
+    /// Finished jobs kept in Redis
+    retention: RetentionPolicy,
+
+    /// How long webhook delivery IDs are remembered
+    webhook_delivery_ttl: Duration,
+
+    /// Whether a push supersedes pending builds of the same branch
+    coalesce_pushes: bool,
+
+    /// Whether the webhook delivery log and replay endpoints are served
+    delivery_api: bool,
+
+    /// Gitea API client (optional, required for manual job triggers)
+    gitea_client: Option<GiteaClient>,
+
+    /// Reports job commit statuses to Gitea and GitHub
+    status_reporter: CommitStatusReporter,
+
+    /// Queue of jobs whose statuses are waiting to be reported, started on first use
+    status_queue: Arc<OnceLock<mpsc::UnboundedSender<Job>>>,
+
+    /// Object store holding archived job logs and artifacts (optional)
+    object_store: Option<Arc<dyn ObjectStore>>,
+
     /// Gitea webhook secret
     gitea_webhook_secret: Option<String>,
 
     /// GitHub webhook secret
     github_webhook_secret: Option<String>,
+
+    /// GitLab webhook secret token
+    gitlab_webhook_secret: Option<String>,
+
+    /// Forgejo webhook secret
+    forgejo_webhook_secret: Option<String>,
+
+    /// Configured webhook secrets by repository full name
+    repository_webhook_secrets: Arc<BTreeMap<String, String>>,
+
+    /// Webhook secrets generated per repository when webhooks were registered
+    webhook_secret_store: Arc<dyn WebhookSecretStore>,
 }
 
 impl std::fmt::Debug for AppState {
@@ -37,11 +117,31 @@ impl std::fmt::Debug for AppState {
             .field("active_connections", &self.active_connections)
             .field("health_status", &self.health_status)
             .field("redis_client", &self.redis_client.is_some())
+            .field("job_stream", &self.job_stream)
+            .field("consumer_group", &self.consumer_group)
+            .field("job_stream_max_length", &self.job_stream_max_length)
+            .field("retention", &self.retention)
+            .field("agent_offline_after", &self.agent_offline_after)
+            .field("gitea_client", &self.gitea_client.is_some())
+            .field("status_reporter", &self.status_reporter.is_enabled())
+            .field("object_store", &self.object_store.is_some())
             .field("gitea_webhook_secret", &self.gitea_webhook_secret.is_some())
             .field(
                 "github_webhook_secret",
                 &self.github_webhook_secret.is_some(),
             )
+            .field(
+                "gitlab_webhook_secret",
+                &self.gitlab_webhook_secret.is_some(),
+            )
+            .field(
+                "forgejo_webhook_secret",
+                &self.forgejo_webhook_secret.is_some(),
+            )
+            .field(
+                "repository_webhook_secrets",
+                &self.repository_webhook_secrets.len(),
+            )
             .finish()
     }
 }
@@ -77,9 +177,29 @@ impl AppState {
             request_count: Arc::new(AtomicU64::new(0)),
             active_connections: Arc::new(AtomicU64::new(0)),
             health_status: Arc::new(RwLock::new(HealthStatus::default())),
+            metrics: Metrics::new(),
             redis_client: None,
+            job_store: Arc::new(InMemoryJobStore::new()),
+            agent_registry: Arc::new(InMemoryAgentRegistry::new()),
+            delivery_log: Arc::new(InMemoryDeliveryLog::default()),
+            agent_offline_after: Duration::from_secs(DEFAULT_AGENT_OFFLINE_SECS),
+            job_stream: DEFAULT_JOB_STREAM.to_string(),
+            consumer_group: DEFAULT_CONSUMER_GROUP.to_string(),
+            job_stream_max_length: DEFAULT_JOB_STREAM_MAX_LENGTH,
+            retention: RetentionPolicy::default(),
+            webhook_delivery_ttl: Duration::from_secs(DEFAULT_WEBHOOK_DELIVERY_TTL_SECS),
+            coalesce_pushes: false,
+            delivery_api: false,
+            gitea_client: None,
+            status_reporter: CommitStatusReporter::default(),
+            status_queue: Arc::default(),
+            object_store: None,
             gitea_webhook_secret: None,
             github_webhook_secret: None,
+            gitlab_webhook_secret: None,
+            forgejo_webhook_secret: None,
+            repository_webhook_secrets: Arc::default(),
+            webhook_secret_store: Arc::new(InMemoryWebhookSecretStore::new()),
         }
     }
 
@@ -87,13 +207,15 @@ impl AppState {
     pub fn with_redis(redis_url: &str) -> Result<Self, redis::RedisError> {
         let client = redis::Client::open(redis_url)?;
         Ok(Self {
-            start_time: chrono::Utc::now(),
-            request_count: Arc::new(AtomicU64::new(0)),
-            active_connections: Arc::new(AtomicU64::new(0)),
-            health_status: Arc::new(RwLock::new(HealthStatus::default())),
+            job_store: Arc::new(RedisJobStore::new(client.clone())),
+            agent_registry: Arc::new(RedisAgentRegistry::new(client.clone())),
+            delivery_log: Arc::new(RedisDeliveryLog::new(
+                client.clone(),
+                DEFAULT_DELIVERY_LOG_SIZE,
+            )),
+            webhook_secret_store: Arc::new(RedisWebhookSecretStore::new(client.clone())),
             redis_client: Some(client),
-            gitea_webhook_secret: None,
-            github_webhook_secret: None,
+            ..Self::new()
         })
     }
 
@@ -105,13 +227,46 @@ impl AppState {
     ) -> Result<Self, redis::RedisError> {
         let client = redis::Client::open(redis_url)?;
         Ok(Self {
-            start_time: chrono::Utc::now(),
-            request_count: Arc::new(AtomicU64::new(0)),
-            active_connections: Arc::new(AtomicU64::new(0)),
-            health_status: Arc::new(RwLock::new(HealthStatus::default())),
+            job_store: Arc::new(RedisJobStore::new(client.clone())),
+            agent_registry: Arc::new(RedisAgentRegistry::new(client.clone())),
+            delivery_log: Arc::new(RedisDeliveryLog::new(
+                client.clone(),
+                DEFAULT_DELIVERY_LOG_SIZE,
+            )),
+            webhook_secret_store: Arc::new(RedisWebhookSecretStore::new(client.clone())),
             redis_client: Some(client),
             gitea_webhook_secret,
             github_webhook_secret,
+            ..Self::new()
+        })
+    }
+
+    /// Create new application state from the server configuration
+    pub fn from_server_config(config: &ServerConfig) -> Result<Self, redis::RedisError> {
+        let client = redis::Client::open(config.redis_url.as_str())?;
+        Ok(Self {
+            job_store: Arc::new(RedisJobStore::new(client.clone())),
+            agent_registry: Arc::new(RedisAgentRegistry::new(client.clone())),
+            delivery_log: Arc::new(RedisDeliveryLog::new(
+                client.clone(),
+                DEFAULT_DELIVERY_LOG_SIZE,
+            )),
+            webhook_secret_store: Arc::new(RedisWebhookSecretStore::new(client.clone())),
+            agent_offline_after: Duration::from_secs(config.agent_offline_secs),
+            redis_client: Some(client),
+            job_stream: config.job_stream.clone(),
+            consumer_group: config.consumer_group.clone(),
+            job_stream_max_length: config.job_stream_max_length,
+            retention: config.retention.clone(),
+            webhook_delivery_ttl: Duration::from_secs(config.webhook_delivery_ttl_secs),
+            coalesce_pushes: config.coalesce_pushes,
+            delivery_api: config.delivery_api,
+            gitea_webhook_secret: config.gitea_webhook_secret.clone(),
+            github_webhook_secret: config.github_webhook_secret.clone(),
+            gitlab_webhook_secret: config.gitlab_webhook_secret.clone(),
+            forgejo_webhook_secret: config.forgejo_webhook_secret.clone(),
+            repository_webhook_secrets: Arc::new(config.repository_webhook_secrets.clone()),
+            ..Self::new()
         })
     }
 
@@ -132,6 +287,174 @@ impl AppState {
         }
     }
 
+    /// Replace the job store
+    pub fn with_job_store(mut self, job_store: Arc<dyn JobStore>) -> Self {
+        self.job_store = job_store;
+        self
+    }
+
+    /// Replace the agent registry
+    pub fn with_agent_registry(mut self, agent_registry: Arc<dyn AgentRegistry>) -> Self {
+        self.agent_registry = agent_registry;
+        self
+    }
+
+    /// Replace the webhook delivery log
+    pub fn with_delivery_log(mut self, delivery_log: Arc<dyn DeliveryLog>) -> Self {
+        self.delivery_log = delivery_log;
+        self
+    }
+
+    /// Replace the per-repository webhook secret store
+    pub fn with_webhook_secret_store(
+        mut self,
+        webhook_secret_store: Arc<dyn WebhookSecretStore>,
+    ) -> Self {
+        self.webhook_secret_store = webhook_secret_store;
+        self
+    }
+
+    /// Set the heartbeat age after which an agent is reported offline
+    pub fn with_agent_offline_after(mut self, offline_after: Duration) -> Self {
+        self.agent_offline_after = offline_after;
+        self
+    }
+
+    /// Set how long webhook delivery IDs are remembered
+    pub fn with_webhook_delivery_ttl(mut self, ttl: Duration) -> Self {
+        self.webhook_delivery_ttl = ttl;
+        self
+    }
+
+    /// Set which finished jobs are kept
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Set whether a push supersedes pending builds of the same branch
+    pub fn with_coalesce_pushes(mut self, coalesce_pushes: bool) -> Self {
+        self.coalesce_pushes = coalesce_pushes;
+        self
+    }
+
+    /// Set whether the webhook delivery log and replay endpoints are served
+    pub fn with_delivery_api(mut self, delivery_api: bool) -> Self {
+        self.delivery_api = delivery_api;
+        self
+    }
+
+    /// Set the Gitea API client
+    pub fn with_gitea_client(mut self, gitea_client: GiteaClient) -> Self {
+        self.gitea_client = Some(gitea_client);
+        self
+    }
+
+    /// Set the reporter posting job commit statuses
+    pub fn with_status_reporter(mut self, status_reporter: CommitStatusReporter) -> Self {
+        self.status_reporter = status_reporter;
+        self.status_queue = Arc::default();
+        self
+    }
+
+    /// Set the object store archived job logs and artifacts are read from
+    pub fn with_object_store(mut self, object_store: Arc<dyn ObjectStore>) -> Self {
+        self.object_store = Some(object_store);
+        self
+    }
+
+    /// Get the object store archived job logs and artifacts are read from
+    pub fn object_store(&self) -> Option<&Arc<dyn ObjectStore>> {
+        self.object_store.as_ref()
+    }
+
+    /// Report a job's status to its commit in the background
+    ///
+    /// Statuses are posted one at a time in the order they're reported, so a job's
+    /// earlier status can't land after a later one and overwrite it.
+    pub fn report_status(&self, job: &Job) {
+        if !self.status_reporter.is_enabled() {
+            return;
+        }
+
+        let queue = self.status_queue.get_or_init(|| {
+            let (sender, mut receiver) = mpsc::unbounded_channel::<Job>();
+            let reporter = self.status_reporter.clone();
+            tokio::spawn(async move {
+                while let Some(job) = receiver.recv().await {
+                    reporter.report(&job).await;
+                }
+            });
+            sender
+        });
+        let _ = queue.send(job.clone());
+    }
+
+    /// Get the Gitea API client, if configured
+    pub fn gitea_client(&self) -> Option<&GiteaClient> {
+        self.gitea_client.as_ref()
+    }
+
+    /// Get the job record store
+    pub fn job_store(&self) -> &dyn JobStore {
+        self.job_store.as_ref()
+    }
+
+    /// Get the agent registry
+    pub fn agent_registry(&self) -> &dyn AgentRegistry {
+        self.agent_registry.as_ref()
+    }
+
+    /// Get the webhook delivery log
+    pub fn delivery_log(&self) -> &dyn DeliveryLog {
+        self.delivery_log.as_ref()
+    }
+
+    /// Get the heartbeat age after which an agent is reported offline
+    pub fn agent_offline_after(&self) -> Duration {
+        self.agent_offline_after
+    }
+
+    /// Get how long webhook delivery IDs are remembered
+    pub fn webhook_delivery_ttl(&self) -> Duration {
+        self.webhook_delivery_ttl
+    }
+
+    /// Check whether a push supersedes pending builds of the same branch
+    pub fn coalesce_pushes(&self) -> bool {
+        self.coalesce_pushes
+    }
+
+    /// Check whether the webhook delivery log and replay endpoints are served
+    pub fn delivery_api(&self) -> bool {
+        self.delivery_api
+    }
+
+    /// Get the job queue stream name
+    pub fn job_stream(&self) -> &str {
+        &self.job_stream
+    }
+
+    /// Get the job queue consumer group name
+    pub fn consumer_group(&self) -> &str {
+        &self.consumer_group
+    }
+
+    /// Get the number of entries the job queue stream is trimmed to (0 = unlimited)
+    pub fn job_stream_max_length(&self) -> usize {
+        self.job_stream_max_length
+    }
+
+    /// Get the policy deciding which finished jobs are kept
+    pub fn retention(&self) -> &RetentionPolicy {
+        &self.retention
+    }
+
+    /// Check whether a Redis client is configured
+    pub fn has_redis(&self) -> bool {
+        self.redis_client.is_some()
+    }
+
     /// Get Gitea webhook secret
     pub fn gitea_webhook_secret(&self) -> Option<&str> {
         self.gitea_webhook_secret.as_deref()
@@ -142,6 +465,32 @@ impl AppState {
         self.github_webhook_secret.as_deref()
     }
 
+    /// Get GitLab webhook secret token
+    pub fn gitlab_webhook_secret(&self) -> Option<&str> {
+        self.gitlab_webhook_secret.as_deref()
+    }
+
+    /// Get Forgejo webhook secret
+    pub fn forgejo_webhook_secret(&self) -> Option<&str> {
+        self.forgejo_webhook_secret.as_deref()
+    }
+
+    /// Get the webhook secret for a repository, if it has its own
+    ///
+    /// Secrets configured for the repository take precedence over those generated
+    /// when its webhook was registered.
+    pub async fn repository_webhook_secret(
+        &self,
+        provider: &str,
+        repo: &str,
+    ) -> Result<Option<String>, crate::error::ServerError> {
+        if let Some(secret) = self.repository_webhook_secrets.get(repo) {
+            return Ok(Some(secret.clone()));
+        }
+
+        Ok(self.webhook_secret_store.get(provider, repo).await?)
+    }
+
     /// Get server start time
     pub fn start_time(&self) -> chrono::DateTime<chrono::Utc> {
         self.start_time
@@ -172,6 +521,11 @@ impl AppState {
         self.active_connections.load(Ordering::Relaxed)
     }
 
+    /// Get the Prometheus metrics
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
     /// Get health status
     pub async fn health_status(&self) -> HealthStatus {
         self.health_status.read().await.clone()
@@ -203,6 +557,19 @@ mod tests {
         assert_eq!(state.active_connections(), 0);
     }
 
+    #[test]
+    fn test_state_from_server_config() {
+        let config = ServerConfig {
+            job_stream: "custom:jobs".to_string(),
+            consumer_group: "custom-workers".to_string(),
+            ..Default::default()
+        };
+
+        let state = AppState::from_server_config(&config).unwrap();
+        assert_eq!(state.job_stream(), "custom:jobs");
+        assert_eq!(state.consumer_group(), "custom-workers");
+    }
+
     #[test]
     fn test_request_counter() {
         let state = AppState::new();
@@ -244,4 +611,106 @@ mod tests {
         assert!(!status.healthy);
         assert_eq!(status.message, "Service degraded");
     }
+
+    /// Commit status states posted to the mock forge, and its repository lookups
+    type ForgeLog = (Arc<std::sync::Mutex<Vec<String>>>, Arc<AtomicU64>);
+
+    /// Start a Gitea and GitHub API stand-in recording the commit statuses posted
+    /// to it, and counting repository lookups
+    async fn spawn_mock_forge() -> (String, ForgeLog) {
+        use axum::{extract::State, routing::get, routing::post, Json, Router};
+
+        let log: ForgeLog = Default::default();
+
+        // Queued statuses are posted slowly, so they'd land last if reports raced
+        async fn record(
+            State((posted, _)): State<ForgeLog>,
+            Json(status): Json<serde_json::Value>,
+        ) -> &'static str {
+            let state = status["state"].as_str().unwrap_or_default().to_string();
+            if state == "pending" {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+            posted.lock().unwrap().push(state);
+            "{}"
+        }
+
+        let app = Router::new()
+            .route(
+                "/api/v1/repos/owner/repo",
+                get(|State((_, lookups)): State<ForgeLog>| async move {
+                    lookups.fetch_add(1, Ordering::SeqCst);
+                    Json(serde_json::json!({
+                        "id": 1,
+                        "name": "repo",
+                        "full_name": "owner/repo",
+                        "private": false,
+                        "mirror": true,
+                        "clone_url": "http://gitea/owner/repo.git",
+                        "html_url": "http://gitea/owner/repo",
+                        "original_url": "https://github.com/owner/repo.git"
+                    }))
+                }),
+            )
+            .route("/api/v1/repos/owner/repo/statuses/{sha}", post(record))
+            .route("/repos/owner/repo/statuses/{sha}", post(record))
+            .with_state(log.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{}", addr), log)
+    }
+
+    #[tokio::test]
+    async fn test_report_status_in_order() {
+        let (url, (posted, lookups)) = spawn_mock_forge().await;
+        let gitea = GiteaClient::new(
+            raibid_common::config::GiteaConfig {
+                url: url.clone(),
+                ..Default::default()
+            },
+            None,
+        )
+        .unwrap();
+        let github =
+            raibid_common::github::GitHubClient::new(raibid_common::config::GitHubConfig {
+                api_url: url,
+                token: None,
+                rate_limit_threshold: 100,
+            })
+            .unwrap();
+        let state = AppState::new().with_status_reporter(CommitStatusReporter::new(
+            Some(gitea),
+            Some(github),
+            None,
+        ));
+
+        let mut job = crate::queue::new_job(
+            "owner/repo".to_string(),
+            "main".to_string(),
+            "abc123".to_string(),
+        );
+        state.report_status(&job);
+        job.mark_finished(raibid_common::jobs::JobStatus::Cancelled, None);
+        state.report_status(&job);
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while posted.lock().unwrap().len() < 4 {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "statuses not posted"
+            );
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        // Each status reaches Gitea then GitHub before the next is posted
+        assert_eq!(
+            *posted.lock().unwrap(),
+            vec!["pending", "pending", "error", "error"]
+        );
+        // The mirror's GitHub source is looked up once
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+    }
 }
@@ -2,6 +2,7 @@
 
 mod common;
 
+use raibid_common::RetentionPolicy;
 use raibid_server::{Server, ServerConfig};
 use std::time::Duration;
 use tokio::time::sleep;
@@ -16,12 +17,29 @@ async fn test_server_starts_and_responds() {
         cors_enabled: false,
         max_body_size: 1024 * 1024,
         redis_url: "redis://127.0.0.1:6379".to_string(),
+        job_stream: "raibid:jobs".to_string(),
+        consumer_group: "raibid-workers".to_string(),
+        job_stream_max_length: 10000,
+        gitea_url: None,
+        gitea_token: None,
+        github_token: None,
+        public_url: None,
         gitea_webhook_secret: None,
         github_webhook_secret: None,
+        gitlab_webhook_secret: None,
+        forgejo_webhook_secret: None,
+        repository_webhook_secrets: Default::default(),
         rate_limit_rpm: 100,
+        agent_offline_secs: 30,
+        webhook_delivery_ttl_secs: 86400,
+        coalesce_pushes: false,
+        delivery_api: false,
+        storage: None,
+        retention: RetentionPolicy::default(),
+        prune_interval_secs: 0,
     };
 
-    let server = Server::new(config.clone());
+    let server = Server::new(config.clone()).expect("Failed to create server");
 
     let server_handle = tokio::spawn(async move { server.run().await });
 
@@ -39,6 +57,16 @@ async fn test_server_starts_and_responds() {
     server_handle.abort();
 }
 
+#[test]
+fn test_server_rejects_invalid_redis_url() {
+    let config = ServerConfig {
+        redis_url: "not-a-redis-url".to_string(),
+        ..Default::default()
+    };
+
+    assert!(Server::new(config).is_err());
+}
+
 #[tokio::test]
 async fn test_health_endpoints_return_json() {
     common::init_test_tracing();
@@ -49,12 +77,29 @@ async fn test_health_endpoints_return_json() {
         cors_enabled: false,
         max_body_size: 1024 * 1024,
         redis_url: "redis://127.0.0.1:6379".to_string(),
+        job_stream: "raibid:jobs".to_string(),
+        consumer_group: "raibid-workers".to_string(),
+        job_stream_max_length: 10000,
+        gitea_url: None,
+        gitea_token: None,
+        github_token: None,
+        public_url: None,
         gitea_webhook_secret: None,
         github_webhook_secret: None,
+        gitlab_webhook_secret: None,
+        forgejo_webhook_secret: None,
+        repository_webhook_secrets: Default::default(),
         rate_limit_rpm: 100,
+        agent_offline_secs: 30,
+        webhook_delivery_ttl_secs: 86400,
+        coalesce_pushes: false,
+        delivery_api: false,
+        storage: None,
+        retention: RetentionPolicy::default(),
+        prune_interval_secs: 0,
     };
 
-    let server = Server::new(config.clone());
+    let server = Server::new(config.clone()).expect("Failed to create server");
 
     let server_handle = tokio::spawn(async move { server.run().await });
 
@@ -105,12 +150,29 @@ async fn test_request_id_header() {
         cors_enabled: false,
         max_body_size: 1024 * 1024,
         redis_url: "redis://127.0.0.1:6379".to_string(),
+        job_stream: "raibid:jobs".to_string(),
+        consumer_group: "raibid-workers".to_string(),
+        job_stream_max_length: 10000,
+        gitea_url: None,
+        gitea_token: None,
+        github_token: None,
+        public_url: None,
         gitea_webhook_secret: None,
         github_webhook_secret: None,
+        gitlab_webhook_secret: None,
+        forgejo_webhook_secret: None,
+        repository_webhook_secrets: Default::default(),
         rate_limit_rpm: 100,
+        agent_offline_secs: 30,
+        webhook_delivery_ttl_secs: 86400,
+        coalesce_pushes: false,
+        delivery_api: false,
+        storage: None,
+        retention: RetentionPolicy::default(),
+        prune_interval_secs: 0,
     };
 
-    let server = Server::new(config.clone());
+    let server = Server::new(config.clone()).expect("Failed to create server");
 
     let server_handle = tokio::spawn(async move { server.run().await });
 
@@ -223,3 +223,101 @@ async fn test_concurrent_job_requests() {
 
     handle.abort();
 }
+
+#[tokio::test]
+async fn test_jobs_routes_mounted_under_api_prefix() {
+    common::init_test_tracing();
+    let (handle, config) = start_test_server(18098, "redis://127.0.0.1:6379").await;
+
+    // The CLI addresses the jobs API under /api
+    let response = reqwest::get(format!("http://{}:{}/api/jobs", config.host, config.port))
+        .await
+        .expect("Failed to make request");
+
+    assert_ne!(response.status(), 404);
+
+    handle.abort();
+}
+
+/// Check if Redis is available
+async fn is_redis_available(redis_url: &str) -> bool {
+    match redis::Client::open(redis_url) {
+        Ok(client) => (client.get_multiplexed_async_connection().await).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Start a minimal Gitea API stand-in that knows `owner/repo` with branch `main`
+async fn spawn_mock_gitea() -> String {
+    use axum::{routing::get, Json, Router};
+
+    let app = Router::new()
+        .route("/api/v1/repos/owner/repo", get(|| async { "{}" }))
+        .route(
+            "/api/v1/repos/owner/repo/branches/main",
+            get(|| async {
+                Json(serde_json::json!({ "name": "main", "commit": { "id": "deadbeef" } }))
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn test_trigger_job_resolves_branch_head() {
+    common::init_test_tracing();
+    let redis_url =
+        std::env::var("RAIBID_REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    if !is_redis_available(&redis_url).await {
+        eprintln!("Skipping test: Redis not available");
+        return;
+    }
+
+    let gitea_config = raibid_common::config::GiteaConfig {
+        url: spawn_mock_gitea().await,
+        ..Default::default()
+    };
+    let gitea = raibid_common::gitea_api::GiteaClient::new(gitea_config, None).unwrap();
+    let state = AppState::with_redis(&redis_url)
+        .unwrap()
+        .with_gitea_client(gitea);
+
+    let config = ServerConfig {
+        port: 18099,
+        redis_url: redis_url.clone(),
+        ..Default::default()
+    };
+    let server = Server::with_state(config.clone(), state);
+    let handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    sleep(Duration::from_millis(500)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("http://{}:{}/api/jobs", config.host, config.port))
+        .json(&serde_json::json!({ "repo": "owner/repo", "branch": "main" }))
+        .send()
+        .await
+        .expect("Failed to make request");
+
+    assert_eq!(response.status(), 201);
+    let job: raibid_common::Job = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(job.commit, "deadbeef");
+    assert_eq!(job.status, raibid_common::JobStatus::Pending);
+
+    // The job is readable through the jobs API
+    let response = reqwest::get(format!(
+        "http://{}:{}/jobs/{}",
+        config.host, config.port, job.id
+    ))
+    .await
+    .expect("Failed to make request");
+    assert_eq!(response.status(), 200);
+
+    handle.abort();
+}
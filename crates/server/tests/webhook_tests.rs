@@ -10,8 +10,10 @@ use axum::{
     http::{Request, StatusCode},
 };
 use hmac::{Hmac, Mac};
+use raibid_common::store::InMemoryWebhookSecretStore;
 use raibid_server::AppState;
 use sha2::Sha256;
+use std::sync::Arc;
 use tower::ServiceExt;
 
 type HmacSha256 = Hmac<Sha256>;
@@ -22,8 +24,10 @@ fn create_test_state(gitea_secret: Option<String>, github_secret: Option<String>
     let redis_url =
         std::env::var("RAIBID_REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
 
+    // Per-repository secrets aren't under test here, so verification doesn't need Redis
     AppState::with_config(&redis_url, gitea_secret, github_secret)
         .unwrap_or_else(|_| AppState::new())
+        .with_webhook_secret_store(Arc::new(InMemoryWebhookSecretStore::new()))
 }
 
 /// Check if Redis is available
@@ -399,3 +403,139 @@ async fn test_invalid_json_payload() {
 
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
+
+#[tokio::test]
+async fn test_github_pull_request_queues_head_ref() {
+    common::init_test_tracing();
+    if !is_redis_available().await {
+        eprintln!("Skipping test: Redis not available");
+        return;
+    }
+
+    let state = std::sync::Arc::new(create_test_state(None, None));
+    let app = raibid_server::routes::webhooks::routes().with_state(state.clone());
+
+    let payload = r#"{
+        "action": "opened",
+        "number": 12,
+        "pull_request": {
+            "number": 12,
+            "title": "Add feature",
+            "head": {
+                "ref": "feature",
+                "sha": "def456",
+                "repo": {"full_name": "contributor/test-repo"}
+            },
+            "base": {
+                "ref": "main",
+                "sha": "abc123",
+                "repo": {"full_name": "owner/test-repo"}
+            }
+        },
+        "repository": {
+            "id": 1,
+            "name": "test-repo",
+            "full_name": "owner/test-repo",
+            "owner": {"id": 1, "login": "owner"},
+            "html_url": "https://github.com/owner/test-repo",
+            "clone_url": "https://github.com/owner/test-repo.git",
+            "ssh_url": "git@github.com:owner/test-repo.git",
+            "default_branch": "main"
+        },
+        "sender": {"login": "contributor"}
+    }"#;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/webhooks/github")
+                .header("content-type", "application/json")
+                .header("X-GitHub-Event", "pull_request")
+                .body(Body::from(payload))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let response: raibid_server::routes::webhooks::WebhookResponse =
+        serde_json::from_slice(&body).unwrap();
+    let job_id = response.job_id.unwrap();
+
+    let job = state.job_store().get(&job_id).await.unwrap().unwrap();
+    assert_eq!(job.repo, "owner/test-repo");
+    assert_eq!(job.branch, "feature");
+    assert_eq!(job.commit, "def456");
+
+    let pr = job.pull_request.unwrap();
+    assert_eq!(pr.number, 12);
+    assert_eq!(pr.base_branch, "main");
+    assert_eq!(pr.head_repo, "contributor/test-repo");
+    assert_eq!(pr.head_ref, "refs/pull/12/head");
+
+    state.job_store().delete(&job_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_github_redelivery_returns_original_job() {
+    common::init_test_tracing();
+    if !is_redis_available().await {
+        eprintln!("Skipping test: Redis not available");
+        return;
+    }
+
+    let state = std::sync::Arc::new(create_test_state(None, None));
+    let app = raibid_server::routes::webhooks::routes().with_state(state);
+    let delivery = uuid::Uuid::new_v4().to_string();
+
+    let payload = r#"{
+        "ref": "refs/heads/main",
+        "before": "abc123",
+        "after": "def456",
+        "repository": {
+            "id": 1,
+            "name": "test-repo",
+            "full_name": "owner/test-repo",
+            "owner": {"id": 1, "login": "owner"},
+            "html_url": "https://github.com/owner/test-repo",
+            "clone_url": "https://github.com/owner/test-repo.git",
+            "ssh_url": "git@github.com:owner/test-repo.git",
+            "default_branch": "main"
+        },
+        "pusher": {"name": "testuser"}
+    }"#;
+
+    let mut responses = Vec::new();
+    for _ in 0..2 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/webhooks/github")
+                    .header("content-type", "application/json")
+                    .header("X-GitHub-Event", "push")
+                    .header("X-GitHub-Delivery", &delivery)
+                    .body(Body::from(payload))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let response: raibid_server::routes::webhooks::WebhookResponse =
+            serde_json::from_slice(&body).unwrap();
+        responses.push((status, response.job_id));
+    }
+
+    assert_eq!(responses[0].0, StatusCode::ACCEPTED);
+    assert_eq!(responses[1].0, StatusCode::OK);
+    assert!(responses[0].1.is_some());
+    assert_eq!(responses[0].1, responses[1].1);
+}
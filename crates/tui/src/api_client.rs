@@ -4,8 +4,10 @@
 //! raibid-ci API server to fetch jobs, agents, queue metrics, and perform actions.
 
 use anyhow::{Context, Result};
-use raibid_common::{Job, JobStatus};
+use raibid_common::{AgentInfo, AgentList, Job, JobStatus, QueueMetrics};
 use serde::{Deserialize, Serialize};
+use std::sync::mpsc;
+use std::thread;
 use std::time::Duration;
 
 /// API client configuration
@@ -167,7 +169,7 @@ impl ApiClient {
             .context("Failed to parse job response")
     }
 
-    /// Get agent list (placeholder - actual endpoint would need to be implemented)
+    /// List registered agents
     pub async fn list_agents(&self) -> Result<Vec<AgentInfo>> {
         let url = format!("{}/agents", self.config.base_url);
 
@@ -179,17 +181,18 @@ impl ApiClient {
             .context("Failed to fetch agents")?;
 
         if !response.status().is_success() {
-            // Return empty list if endpoint doesn't exist yet
-            return Ok(Vec::new());
+            anyhow::bail!("API returned error: {}", response.status());
         }
 
-        response
-            .json::<Vec<AgentInfo>>()
+        let list = response
+            .json::<AgentList>()
             .await
-            .context("Failed to parse agents response")
+            .context("Failed to parse agents response")?;
+
+        Ok(list.agents)
     }
 
-    /// Get queue metrics (placeholder - actual endpoint would need to be implemented)
+    /// Get queue metrics
     pub async fn get_queue_metrics(&self) -> Result<QueueMetrics> {
         let url = format!("{}/metrics/queue", self.config.base_url);
 
@@ -201,8 +204,7 @@ impl ApiClient {
             .context("Failed to fetch queue metrics")?;
 
         if !response.status().is_success() {
-            // Return default metrics if endpoint doesn't exist yet
-            return Ok(QueueMetrics::default());
+            anyhow::bail!("API returned error: {}", response.status());
         }
 
         response
@@ -236,6 +238,54 @@ impl ApiClient {
     }
 }
 
+/// Data fetched from the server by one poll
+#[derive(Debug, Clone)]
+pub struct DashboardUpdate {
+    /// Registered agents (empty when the server is unreachable)
+    pub agents: Vec<AgentInfo>,
+    /// Queue metrics, if they could be fetched
+    pub queue: Option<QueueMetrics>,
+}
+
+/// Poll the agent list and queue metrics in the background
+///
+/// The TUI event loop is synchronous, so requests run on a dedicated thread with
+/// its own runtime. The thread exits once the receiver is dropped.
+pub fn spawn_poller(client: ApiClient, interval: Duration) -> mpsc::Receiver<DashboardUpdate> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                tracing::warn!("Failed to start dashboard poller: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            let agents = runtime.block_on(client.list_agents()).unwrap_or_else(|e| {
+                tracing::debug!("Failed to fetch agents: {:#}", e);
+                Vec::new()
+            });
+            let queue = runtime
+                .block_on(client.get_queue_metrics())
+                .map_err(|e| tracing::debug!("Failed to fetch queue metrics: {:#}", e))
+                .ok();
+
+            if tx.send(DashboardUpdate { agents, queue }).is_err() {
+                return;
+            }
+            thread::sleep(interval);
+        }
+    });
+
+    rx
+}
+
 impl Default for ApiClient {
     fn default() -> Self {
         Self::new().expect("Failed to create default API client")
@@ -262,38 +312,6 @@ pub struct JobTrigger {
     pub commit: Option<String>,
 }
 
-/// Agent information from API
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AgentInfo {
-    pub id: String,
-    pub name: String,
-    pub status: String,
-    pub cpu: u8,
-    pub memory: u8,
-    pub uptime: u64,
-}
-
-/// Queue metrics from API
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct QueueMetrics {
-    pub current_depth: u64,
-    pub max_depth: u64,
-    pub avg_depth: f64,
-    #[serde(default)]
-    pub history: Vec<u64>,
-}
-
-impl Default for QueueMetrics {
-    fn default() -> Self {
-        Self {
-            current_depth: 0,
-            max_depth: 0,
-            avg_depth: 0.0,
-            history: vec![0; 60],
-        }
-    }
-}
-
 /// Health status from API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthStatus {
@@ -322,11 +340,4 @@ mod tests {
         let client = ApiClient::with_config(config);
         assert!(client.is_ok());
     }
-
-    #[test]
-    fn test_queue_metrics_default() {
-        let metrics = QueueMetrics::default();
-        assert_eq!(metrics.current_depth, 0);
-        assert_eq!(metrics.history.len(), 60);
-    }
 }
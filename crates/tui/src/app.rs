@@ -3,12 +3,13 @@
 //! This module contains the main application state and event handling logic.
 
 use anyhow::Result;
+use raibid_common::{AgentInfo, QueueMetrics};
+use std::sync::mpsc::Receiver;
 use std::time::Duration;
 
+use super::api_client::{spawn_poller, ApiClient, DashboardUpdate};
 use super::events::{is_quit_event, Event, EventHandler};
-use super::mock_data::{
-    generate_mock_data, JobStatus, MockAgent, MockDataConfig, MockJob, MockQueueData,
-};
+use super::mock_data::{generate_mock_data, JobStatus, MockDataConfig, MockJob};
 use super::terminal::Terminal;
 use super::ui;
 
@@ -77,6 +78,48 @@ impl Default for AppConfig {
     }
 }
 
+/// Queue depth history for the sparkline, fed by queue metrics from the server
+#[derive(Debug, Clone)]
+pub struct QueueHistory {
+    /// Depth samples, oldest first
+    pub history: Vec<u64>,
+    /// Current queue depth
+    pub current: u64,
+    /// Latest metrics, or `None` while the server is unreachable
+    pub metrics: Option<QueueMetrics>,
+}
+
+impl QueueHistory {
+    /// Number of samples kept for the sparkline
+    pub const SAMPLES: usize = 60;
+
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self {
+            history: vec![0; Self::SAMPLES],
+            current: 0,
+            metrics: None,
+        }
+    }
+
+    /// Record a metrics sample
+    pub fn record(&mut self, metrics: QueueMetrics) {
+        if self.history.len() >= Self::SAMPLES {
+            self.history.remove(0);
+        }
+
+        self.current = metrics.depth();
+        self.history.push(self.current);
+        self.metrics = Some(metrics);
+    }
+}
+
+impl Default for QueueHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Input mode for different interaction states
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
@@ -96,10 +139,12 @@ pub struct App {
     mock_config: MockDataConfig,
     /// Current job list
     jobs: Vec<MockJob>,
-    /// Current agent list
-    agents: Vec<MockAgent>,
-    /// Queue depth data
-    queue_data: MockQueueData,
+    /// Current agent list, as last reported by the server
+    agents: Vec<AgentInfo>,
+    /// Server data fetched by the background poller
+    updates: Option<Receiver<DashboardUpdate>>,
+    /// Queue depth history
+    queue_data: QueueHistory,
     /// Whether the application should quit
     should_quit: bool,
     /// Current active tab
@@ -139,14 +184,18 @@ impl App {
     /// Create a new application with custom configuration
     pub fn with_config(config: AppConfig) -> Self {
         let mock_config = MockDataConfig::default();
-        let (jobs, agents, queue_data) = generate_mock_data(&mock_config);
+        let jobs = generate_mock_data(&mock_config);
+        let updates = ApiClient::new()
+            .ok()
+            .map(|client| spawn_poller(client, config.refresh_interval));
 
         Self {
             config,
             mock_config,
             jobs,
-            agents,
-            queue_data,
+            agents: Vec::new(),
+            updates,
+            queue_data: QueueHistory::new(),
             should_quit: false,
             current_tab: Tab::Jobs,
             selected_job: 0,
@@ -201,15 +250,11 @@ impl App {
             }
         } else {
             match self.current_tab {
-                Tab::Jobs => {
-                    if self.selected_job > 0 {
-                        self.selected_job -= 1;
-                    }
+                Tab::Jobs if self.selected_job > 0 => {
+                    self.selected_job -= 1;
                 }
-                Tab::Agents => {
-                    if self.selected_agent > 0 {
-                        self.selected_agent -= 1;
-                    }
+                Tab::Agents if self.selected_agent > 0 => {
+                    self.selected_agent -= 1;
                 }
                 Tab::Logs => {
                     // Scroll logs up
@@ -229,15 +274,11 @@ impl App {
             }
         } else {
             match self.current_tab {
-                Tab::Jobs => {
-                    if self.selected_job < self.filtered_jobs().len().saturating_sub(1) {
-                        self.selected_job += 1;
-                    }
+                Tab::Jobs if self.selected_job < self.filtered_jobs().len().saturating_sub(1) => {
+                    self.selected_job += 1;
                 }
-                Tab::Agents => {
-                    if self.selected_agent < self.agents.len().saturating_sub(1) {
-                        self.selected_agent += 1;
-                    }
+                Tab::Agents if self.selected_agent < self.agents.len().saturating_sub(1) => {
+                    self.selected_agent += 1;
                 }
                 Tab::Logs => {
                     // Scroll logs down
@@ -258,16 +299,26 @@ impl App {
         self.should_quit = true;
     }
 
-    /// Update application state (refresh mock data)
+    /// Update application state (refresh mock data and apply server updates)
     pub fn update(&mut self) {
         // Regenerate mock data to simulate changes
-        let (jobs, agents, _) = generate_mock_data(&self.mock_config);
+        let jobs = generate_mock_data(&self.mock_config);
         self.jobs = jobs;
-        self.agents = agents;
 
-        // Update queue data incrementally
-        let mut rng = rand::thread_rng();
-        self.queue_data.update(&mut rng);
+        let updates: Vec<DashboardUpdate> = self
+            .updates
+            .as_ref()
+            .map(|updates| updates.try_iter().collect())
+            .unwrap_or_default();
+
+        for update in updates {
+            self.agents = update.agents;
+            match update.queue {
+                Some(metrics) => self.queue_data.record(metrics),
+                None => self.queue_data.metrics = None,
+            }
+        }
+        self.selected_agent = self.selected_agent.min(self.agents.len().saturating_sub(1));
     }
 
     /// Handle an event
@@ -345,14 +396,14 @@ impl App {
                                 KeyCode::Char('/') => self.enter_search_mode(),
                                 KeyCode::Char('c') => self.show_cancel_confirmation(),
                                 KeyCode::Char('r') => self.refresh(),
-                                KeyCode::Esc => {
-                                    // Clear filters and search
-                                    if self.filter_status.is_some() || !self.search_query.is_empty()
-                                    {
-                                        self.filter_status = None;
-                                        self.search_query.clear();
-                                        self.selected_job = 0;
-                                    }
+                                // Clear filters and search
+                                KeyCode::Esc
+                                    if self.filter_status.is_some()
+                                        || !self.search_query.is_empty() =>
+                                {
+                                    self.filter_status = None;
+                                    self.search_query.clear();
+                                    self.selected_job = 0;
                                 }
                                 _ => {}
                             }
@@ -409,13 +460,13 @@ impl App {
 
     /// Get agents reference (for testing and integration tests)
     #[allow(dead_code)]
-    pub fn agents(&self) -> &[MockAgent] {
+    pub fn agents(&self) -> &[AgentInfo] {
         &self.agents
     }
 
     /// Get queue data reference (for testing and integration tests)
     #[allow(dead_code)]
-    pub fn queue_data(&self) -> &MockQueueData {
+    pub fn queue_data(&self) -> &QueueHistory {
         &self.queue_data
     }
 
@@ -597,10 +648,25 @@ mod tests {
         let app = App::new();
         assert!(!app.should_quit());
         assert!(!app.jobs().is_empty());
-        assert!(!app.agents().is_empty());
+        // Agents only appear once the server has been polled
+        assert!(app.agents().is_empty());
         assert_eq!(app.queue_data().history.len(), 60);
     }
 
+    #[test]
+    fn test_queue_history_record() {
+        let mut queue = QueueHistory::new();
+        let mut metrics = QueueMetrics::empty("raibid:jobs", "raibid-workers", 15);
+        metrics.lag = Some(4);
+
+        queue.record(metrics);
+
+        assert_eq!(queue.history.len(), QueueHistory::SAMPLES);
+        assert_eq!(queue.history.last(), Some(&4));
+        assert_eq!(queue.current, 4);
+        assert!(queue.metrics.is_some());
+    }
+
     #[test]
     fn test_app_quit() {
         let mut app = App::new();
@@ -614,13 +680,11 @@ mod tests {
     fn test_app_update() {
         let mut app = App::new();
         let initial_jobs = app.jobs().len();
-        let initial_agents = app.agents().len();
 
         app.update();
 
-        // Should still have the same count of jobs and agents
+        // Should still have the same count of jobs
         assert_eq!(app.jobs().len(), initial_jobs);
-        assert_eq!(app.agents().len(), initial_agents);
     }
 
     #[test]
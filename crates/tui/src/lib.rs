@@ -11,15 +11,13 @@ mod terminal;
 mod ui;
 
 #[allow(unused_imports)]
-pub use api_client::{ApiClient, ApiConfig};
+pub use api_client::{spawn_poller, ApiClient, ApiConfig, DashboardUpdate};
 #[allow(unused_imports)]
-pub use app::{App, AppConfig, InputMode, Tab};
+pub use app::{App, AppConfig, InputMode, QueueHistory, Tab};
 #[allow(unused_imports)]
 pub use events::Event;
 #[allow(unused_imports)]
-pub use mock_data::{
-    generate_mock_data, AgentStatus, JobStatus, MockAgent, MockDataConfig, MockJob, MockQueueData,
-};
+pub use mock_data::{generate_mock_data, JobStatus, MockDataConfig, MockJob};
 #[allow(unused_imports)]
 pub use terminal::{Terminal, MIN_HEIGHT, MIN_WIDTH};
 
@@ -11,19 +11,18 @@ use ratatui::{
     Frame,
 };
 
-use super::app::{InputMode, Tab, UiState};
-use super::mock_data::{
-    generate_system_logs, AgentStatus, JobStatus, LogLevel, MockAgent, MockJob, MockJobLogs,
-    MockQueueData,
-};
+use raibid_common::{AgentInfo, AgentStatus};
+
+use super::app::{InputMode, QueueHistory, Tab, UiState};
+use super::mock_data::{generate_system_logs, JobStatus, LogLevel, MockJob, MockJobLogs};
 
 /// Main render function for the dashboard
 #[allow(clippy::too_many_arguments)]
 pub fn render(
     frame: &mut Frame,
     jobs: &[MockJob],
-    agents: &[MockAgent],
-    queue_data: &MockQueueData,
+    agents: &[AgentInfo],
+    queue_data: &QueueHistory,
     current_tab: Tab,
     selected_job: usize,
     selected_agent: usize,
@@ -363,10 +362,15 @@ fn render_jobs_panel(frame: &mut Frame, area: Rect, jobs: &[MockJob]) {
     frame.render_widget(table, area);
 }
 
-/// Render the agents panel with agent list and resource usage
-fn render_agents_panel(frame: &mut Frame, area: Rect, agents: &[MockAgent]) {
+/// Render the agents panel with agent list, slot usage and host load
+fn render_agents_panel(frame: &mut Frame, area: Rect, agents: &[AgentInfo]) {
+    let online = agents
+        .iter()
+        .filter(|agent| agent.status != AgentStatus::Offline)
+        .count();
+
     let block = Block::default()
-        .title(format!(" Agents ({}) ", agents.len()))
+        .title(format!(" Agents ({}/{} online) ", online, agents.len()))
         .title_style(
             Style::default()
                 .fg(Color::Cyan)
@@ -381,16 +385,33 @@ fn render_agents_panel(frame: &mut Frame, area: Rect, agents: &[MockAgent]) {
             let status_style = match agent.status {
                 AgentStatus::Idle => Style::default().fg(Color::Green),
                 AgentStatus::Busy => Style::default().fg(Color::Yellow),
-                AgentStatus::Starting => Style::default().fg(Color::Blue),
-                AgentStatus::Stopping => Style::default().fg(Color::Gray),
+                AgentStatus::Offline => Style::default().fg(Color::Gray),
             };
 
-            let uptime_str = format_uptime(agent.uptime);
+            let running = agent.current_jobs.len() as u32;
+            let slots = percentage(running, agent.capacity);
+            let load_line = match agent.load {
+                Some(load) => {
+                    let load = (load * 100.0).clamp(0.0, 100.0) as u8;
+                    vec![
+                        Span::raw("  LOAD: "),
+                        Span::styled(
+                            format!("{:3}% ", load),
+                            Style::default().fg(cpu_color(load)),
+                        ),
+                        Span::raw(resource_bar(load)),
+                    ]
+                }
+                None => vec![
+                    Span::raw("  LOAD: "),
+                    Span::styled("n/a", Style::default().fg(Color::Gray)),
+                ],
+            };
 
-            let content = vec![
+            let mut content = vec![
                 Line::from(vec![
                     Span::styled(
-                        agent.name.clone(),
+                        agent.hostname.clone(),
                         Style::default()
                             .fg(Color::White)
                             .add_modifier(Modifier::BOLD),
@@ -399,28 +420,31 @@ fn render_agents_panel(frame: &mut Frame, area: Rect, agents: &[MockAgent]) {
                     Span::styled(agent.status.as_str(), status_style),
                 ]),
                 Line::from(vec![
-                    Span::raw("  CPU: "),
+                    Span::raw("  JOBS: "),
                     Span::styled(
-                        format!("{:3}% ", agent.cpu),
-                        Style::default().fg(cpu_color(agent.cpu)),
+                        format!("{}/{} ", running, agent.capacity),
+                        Style::default().fg(memory_color(slots)),
                     ),
-                    Span::raw(resource_bar(agent.cpu)),
+                    Span::raw(resource_bar(slots)),
                 ]),
+                Line::from(load_line),
                 Line::from(vec![
-                    Span::raw("  MEM: "),
+                    Span::raw("  UP:   "),
                     Span::styled(
-                        format!("{:3}% ", agent.memory),
-                        Style::default().fg(memory_color(agent.memory)),
+                        format_uptime(agent.uptime_secs()),
+                        Style::default().fg(Color::Gray),
                     ),
-                    Span::raw(resource_bar(agent.memory)),
-                ]),
-                Line::from(vec![
-                    Span::raw("  UP:  "),
-                    Span::styled(uptime_str, Style::default().fg(Color::Gray)),
                 ]),
-                Line::from(""), // Empty line for spacing
             ];
 
+            for job_id in &agent.current_jobs {
+                content.push(Line::from(vec![
+                    Span::raw("  RUN:  "),
+                    Span::styled(short_id(job_id), Style::default().fg(Color::Yellow)),
+                ]));
+            }
+            content.push(Line::from("")); // Empty line for spacing
+
             ListItem::new(content)
         })
         .collect();
@@ -430,9 +454,22 @@ fn render_agents_panel(frame: &mut Frame, area: Rect, agents: &[MockAgent]) {
     frame.render_widget(list, area);
 }
 
+/// Get `part` as a percentage of `whole`, capped at 100
+fn percentage(part: u32, whole: u32) -> u8 {
+    if whole == 0 {
+        return 0;
+    }
+    (part.saturating_mul(100) / whole).min(100) as u8
+}
+
+/// Shorten a UUID-style ID for display
+fn short_id(id: &str) -> String {
+    id.chars().take(8).collect()
+}
+
 /// Render the queue panel with sparkline chart
 #[allow(dead_code)]
-fn render_queue_panel(frame: &mut Frame, area: Rect, queue_data: &MockQueueData) {
+fn render_queue_panel(frame: &mut Frame, area: Rect, queue_data: &QueueHistory) {
     let _block = Block::default()
         .title(format!(" Queue Depth ({}) ", queue_data.current))
         .title_style(
@@ -460,7 +497,7 @@ fn render_queue_panel(frame: &mut Frame, area: Rect, queue_data: &MockQueueData)
         0
     };
 
-    let info_text = vec![
+    let mut info_text = vec![
         Line::from(""),
         Line::from(vec![
             Span::raw("  Current: "),
@@ -481,6 +518,40 @@ fn render_queue_panel(frame: &mut Frame, area: Rect, queue_data: &MockQueueData)
         ]),
     ];
 
+    match &queue_data.metrics {
+        Some(metrics) => {
+            let oldest = metrics
+                .oldest_pending_age_secs
+                .map(|secs| format!("{}s", secs))
+                .unwrap_or_else(|| "n/a".to_string());
+
+            info_text.push(Line::from(vec![
+                Span::raw("  Pending: "),
+                Span::styled(
+                    metrics.pending.to_string(),
+                    Style::default().fg(Color::Blue),
+                ),
+            ]));
+            info_text.push(Line::from(vec![
+                Span::raw("  Oldest:  "),
+                Span::styled(oldest, Style::default().fg(Color::Magenta)),
+            ]));
+            info_text.push(Line::from(vec![
+                Span::raw("  Rate:    "),
+                Span::styled(
+                    format!("{:.1}/min", metrics.throughput.per_minute),
+                    Style::default().fg(Color::Green),
+                ),
+            ]));
+        }
+        None => {
+            info_text.push(Line::from(Span::styled(
+                "  Server unreachable",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
     let info_paragraph = ratatui::widgets::Paragraph::new(info_text).block(Block::default());
 
     frame.render_widget(info_paragraph, chunks[0]);
@@ -495,7 +566,7 @@ fn render_queue_panel(frame: &mut Frame, area: Rect, queue_data: &MockQueueData)
         .block(sparkline_block)
         .data(&queue_data.history)
         .style(Style::default().fg(Color::Cyan))
-        .max(30); // Max queue depth for scaling
+        .max(max_depth.max(1));
 
     frame.render_widget(sparkline, chunks[1]);
 }
@@ -569,8 +640,8 @@ fn render_jobs_tab(
     frame: &mut Frame,
     area: Rect,
     jobs: &[MockJob],
-    agents: &[MockAgent],
-    queue_data: &MockQueueData,
+    agents: &[AgentInfo],
+    queue_data: &QueueHistory,
     _selected: usize,
 ) {
     // Create 3-panel layout for content
@@ -591,7 +662,7 @@ fn render_jobs_tab(
 
 /// Render the Agents tab (detailed view)
 #[allow(dead_code)]
-fn render_agents_tab(frame: &mut Frame, area: Rect, agents: &[MockAgent], _selected: usize) {
+fn render_agents_tab(frame: &mut Frame, area: Rect, agents: &[AgentInfo], _selected: usize) {
     // For now, delegate to the agents panel implementation
     render_agents_panel(frame, area, agents);
 }
@@ -1125,6 +1196,13 @@ mod tests {
         assert_eq!(memory_color(90), Color::Red);
     }
 
+    #[test]
+    fn test_percentage() {
+        assert_eq!(percentage(1, 4), 25);
+        assert_eq!(percentage(3, 2), 100);
+        assert_eq!(percentage(1, 0), 0);
+    }
+
     #[test]
     fn test_format_uptime() {
         assert_eq!(format_uptime(30), "30s");
@@ -189,7 +189,7 @@ async fn verify_job_in_redis(config: &TestConfig, job_id: &str) -> Result<(), St
 
     // Check if job exists in Redis Stream
     let stream_len: usize = redis::cmd("XLEN")
-        .arg("ci:jobs")
+        .arg("raibid:jobs")
         .query_async(&mut conn)
         .await
         .map_err(|e| format!("Failed to query Redis stream: {}", e))?;
@@ -381,7 +381,7 @@ mod cleanup_tests {
             if let Ok(mut conn) = redis_client.get_multiplexed_async_connection().await {
                 // Delete test jobs from stream
                 let _: Result<(), redis::RedisError> =
-                    redis::cmd("DEL").arg("ci:jobs").query_async(&mut conn).await;
+                    redis::cmd("DEL").arg("raibid:jobs").query_async(&mut conn).await;
 
                 println!("Cleaned up Redis test data");
             }
@@ -14,7 +14,7 @@ cleanup_redis() {
     echo "Cleaning Redis test data..."
 
     if command -v redis-cli &> /dev/null; then
-        redis-cli -u "${REDIS_URL}" DEL ci:jobs || true
+        redis-cli -u "${REDIS_URL}" DEL raibid:jobs || true
         redis-cli -u "${REDIS_URL}" DEL "job:*" || true
         redis-cli -u "${REDIS_URL}" DEL "job:*:logs" || true
         echo "✓ Redis cleaned"